@@ -6,7 +6,7 @@ import { LedgerConfig } from "./ledger_runner";
 export interface CndConfigFile {
     http_api: HttpApi;
     data?: { dir: string };
-    network: { listen: string[] };
+    network: { listen: string[]; authentication?: string };
     logging: { level: string; structured: boolean };
 }
 
@@ -20,7 +20,8 @@ export class E2ETestActorConfig {
     constructor(
         public readonly httpApiPort: number,
         public readonly comitPort: number,
-        public readonly name: string
+        public readonly name: string,
+        public readonly authentication?: string
     ) {
         this.httpApiPort = httpApiPort;
         this.comitPort = comitPort;
@@ -44,6 +45,9 @@ export class E2ETestActorConfig {
             },
             network: {
                 listen: [`/ip4/0.0.0.0/tcp/${this.comitPort}`],
+                ...(this.authentication && {
+                    authentication: this.authentication,
+                }),
             },
             logging: {
                 level: "TRACE",
@@ -69,7 +73,8 @@ interface BitcoinConnector {
 }
 
 export const ALICE_CONFIG = new E2ETestActorConfig(8000, 9938, "alice");
-export const BOB_CONFIG = new E2ETestActorConfig(8010, 9939, "bob");
+// Bob only speaks secio so that every swap also covers the negotiation with nodes that prefer noise.
+export const BOB_CONFIG = new E2ETestActorConfig(8010, 9939, "bob", "secio");
 export const CHARLIE_CONFIG = new E2ETestActorConfig(8020, 8021, "charlie");
 
 function createLedgerConnectors(ledgerConfig: LedgerConfig): LedgerConnectors {
@@ -23,6 +23,12 @@ export function seconds_until(time: number): number {
     }
 }
 
+/// Peers from a `GET /peers` response that we are connected to, i.e. without
+/// the ones only known through discovery or the address book
+export function connectedPeers(peers: any[]): any[] {
+    return peers.filter(peer => peer.connected);
+}
+
 /// This is needed to use the global variable in TypeScript
 import Global = NodeJS.Global;
 
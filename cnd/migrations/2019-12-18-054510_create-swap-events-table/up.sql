@@ -0,0 +1,9 @@
+-- Your SQL goes here
+
+CREATE TABLE rfc003_swap_events
+(
+    id INTEGER      NOT NULL PRIMARY KEY,
+    swap_id         NOT NULL,
+    event           NOT NULL,
+    at DATETIME DEFAULT CURRENT_TIMESTAMP
+);
@@ -0,0 +1,158 @@
+-- This file should undo anything in `up.sql`
+
+CREATE TABLE rfc003_bitcoin_ethereum_bitcoin_ether_request_messages
+(
+    id INTEGER               NOT NULL PRIMARY KEY,
+    swap_id UNIQUE           NOT NULL,
+    bitcoin_network          NOT NULL,
+    ethereum_chain_id        NOT NULL,
+    bitcoin_amount           NOT NULL,
+    ether_amount             NOT NULL,
+    hash_function            NOT NULL,
+    bitcoin_refund_identity  NOT NULL,
+    ethereum_redeem_identity NOT NULL,
+    bitcoin_expiry           NOT NULL,
+    ethereum_expiry          NOT NULL,
+    secret_hash              NOT NULL
+);
+
+CREATE TABLE rfc003_ethereum_bitcoin_ether_bitcoin_request_messages
+(
+    id INTEGER               NOT NULL PRIMARY KEY,
+    swap_id UNIQUE           NOT NULL,
+    bitcoin_network          NOT NULL,
+    ethereum_chain_id        NOT NULL,
+    bitcoin_amount           NOT NULL,
+    ether_amount             NOT NULL,
+    hash_function            NOT NULL,
+    bitcoin_redeem_identity  NOT NULL,
+    ethereum_refund_identity NOT NULL,
+    bitcoin_expiry           NOT NULL,
+    ethereum_expiry          NOT NULL,
+    secret_hash              NOT NULL
+);
+
+CREATE TABLE rfc003_bitcoin_ethereum_bitcoin_erc20_request_messages
+(
+    id INTEGER               NOT NULL PRIMARY KEY,
+    swap_id UNIQUE           NOT NULL,
+    bitcoin_network          NOT NULL,
+    ethereum_chain_id        NOT NULL,
+    bitcoin_amount           NOT NULL,
+    erc20_amount             NOT NULL,
+    erc20_token_contract     NOT NULL,
+    hash_function            NOT NULL,
+    bitcoin_refund_identity  NOT NULL,
+    ethereum_redeem_identity NOT NULL,
+    bitcoin_expiry           NOT NULL,
+    ethereum_expiry          NOT NULL,
+    secret_hash              NOT NULL
+);
+
+CREATE TABLE rfc003_ethereum_bitcoin_erc20_bitcoin_request_messages
+(
+    id INTEGER               NOT NULL PRIMARY KEY,
+    swap_id UNIQUE           NOT NULL,
+    bitcoin_network          NOT NULL,
+    ethereum_chain_id        NOT NULL,
+    bitcoin_amount           NOT NULL,
+    erc20_amount             NOT NULL,
+    erc20_token_contract     NOT NULL,
+    hash_function            NOT NULL,
+    bitcoin_redeem_identity  NOT NULL,
+    ethereum_refund_identity NOT NULL,
+    bitcoin_expiry           NOT NULL,
+    ethereum_expiry          NOT NULL,
+    secret_hash              NOT NULL
+);
+
+CREATE TABLE rfc003_ethereum_bitcoin_accept_messages
+(
+    id INTEGER               NOT NULL PRIMARY KEY,
+    swap_id UNIQUE           NOT NULL,
+    bitcoin_refund_identity  NOT NULL,
+    ethereum_redeem_identity NOT NULL,
+    at DATETIME DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE rfc003_bitcoin_ethereum_accept_messages
+(
+    id INTEGER               NOT NULL PRIMARY KEY,
+    swap_id UNIQUE           NOT NULL,
+    bitcoin_redeem_identity  NOT NULL,
+    ethereum_refund_identity NOT NULL,
+    at DATETIME DEFAULT CURRENT_TIMESTAMP
+);
+
+INSERT INTO rfc003_bitcoin_ethereum_bitcoin_ether_request_messages
+(
+    swap_id, bitcoin_network, ethereum_chain_id, bitcoin_amount, ether_amount, hash_function,
+    bitcoin_refund_identity, ethereum_redeem_identity, bitcoin_expiry, ethereum_expiry, secret_hash
+)
+SELECT
+    swap_id, json_extract(alpha_ledger, '$.network'), json_extract(beta_ledger, '$.chain_id'),
+    json_extract(alpha_asset, '$.quantity'), json_extract(beta_asset, '$.quantity'), hash_function,
+    alpha_ledger_refund_identity, beta_ledger_redeem_identity, alpha_expiry, beta_expiry, secret_hash
+FROM rfc003_requests
+WHERE alpha_ledger_kind = 'Bitcoin' AND beta_ledger_kind = 'Ethereum'
+  AND alpha_asset_kind = 'Bitcoin' AND beta_asset_kind = 'Ether';
+
+INSERT INTO rfc003_ethereum_bitcoin_ether_bitcoin_request_messages
+(
+    swap_id, bitcoin_network, ethereum_chain_id, bitcoin_amount, ether_amount, hash_function,
+    bitcoin_redeem_identity, ethereum_refund_identity, bitcoin_expiry, ethereum_expiry, secret_hash
+)
+SELECT
+    swap_id, json_extract(beta_ledger, '$.network'), json_extract(alpha_ledger, '$.chain_id'),
+    json_extract(beta_asset, '$.quantity'), json_extract(alpha_asset, '$.quantity'), hash_function,
+    beta_ledger_redeem_identity, alpha_ledger_refund_identity, beta_expiry, alpha_expiry, secret_hash
+FROM rfc003_requests
+WHERE alpha_ledger_kind = 'Ethereum' AND beta_ledger_kind = 'Bitcoin'
+  AND alpha_asset_kind = 'Ether' AND beta_asset_kind = 'Bitcoin';
+
+INSERT INTO rfc003_bitcoin_ethereum_bitcoin_erc20_request_messages
+(
+    swap_id, bitcoin_network, ethereum_chain_id, bitcoin_amount, erc20_amount, erc20_token_contract,
+    hash_function, bitcoin_refund_identity, ethereum_redeem_identity, bitcoin_expiry,
+    ethereum_expiry, secret_hash
+)
+SELECT
+    swap_id, json_extract(alpha_ledger, '$.network'), json_extract(beta_ledger, '$.chain_id'),
+    json_extract(alpha_asset, '$.quantity'), json_extract(beta_asset, '$.quantity'),
+    json_extract(beta_asset, '$.token_contract'), hash_function, alpha_ledger_refund_identity,
+    beta_ledger_redeem_identity, alpha_expiry, beta_expiry, secret_hash
+FROM rfc003_requests
+WHERE alpha_ledger_kind = 'Bitcoin' AND beta_ledger_kind = 'Ethereum'
+  AND alpha_asset_kind = 'Bitcoin' AND beta_asset_kind = 'Erc20';
+
+INSERT INTO rfc003_ethereum_bitcoin_erc20_bitcoin_request_messages
+(
+    swap_id, bitcoin_network, ethereum_chain_id, bitcoin_amount, erc20_amount, erc20_token_contract,
+    hash_function, bitcoin_redeem_identity, ethereum_refund_identity, bitcoin_expiry,
+    ethereum_expiry, secret_hash
+)
+SELECT
+    swap_id, json_extract(beta_ledger, '$.network'), json_extract(alpha_ledger, '$.chain_id'),
+    json_extract(beta_asset, '$.quantity'), json_extract(alpha_asset, '$.quantity'),
+    json_extract(alpha_asset, '$.token_contract'), hash_function, beta_ledger_redeem_identity,
+    alpha_ledger_refund_identity, beta_expiry, alpha_expiry, secret_hash
+FROM rfc003_requests
+WHERE alpha_ledger_kind = 'Ethereum' AND beta_ledger_kind = 'Bitcoin'
+  AND alpha_asset_kind = 'Erc20' AND beta_asset_kind = 'Bitcoin';
+
+INSERT INTO rfc003_bitcoin_ethereum_accept_messages
+(swap_id, bitcoin_redeem_identity, ethereum_refund_identity, at)
+SELECT accepts.swap_id, accepts.alpha_ledger_redeem_identity, accepts.beta_ledger_refund_identity, accepts.at
+FROM rfc003_accepts accepts
+JOIN rfc003_requests requests ON requests.swap_id = accepts.swap_id
+WHERE requests.alpha_ledger_kind = 'Bitcoin';
+
+INSERT INTO rfc003_ethereum_bitcoin_accept_messages
+(swap_id, ethereum_redeem_identity, bitcoin_refund_identity, at)
+SELECT accepts.swap_id, accepts.alpha_ledger_redeem_identity, accepts.beta_ledger_refund_identity, accepts.at
+FROM rfc003_accepts accepts
+JOIN rfc003_requests requests ON requests.swap_id = accepts.swap_id
+WHERE requests.alpha_ledger_kind = 'Ethereum';
+
+DROP TABLE rfc003_requests;
+DROP TABLE rfc003_accepts;
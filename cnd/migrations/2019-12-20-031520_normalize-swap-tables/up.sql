@@ -0,0 +1,144 @@
+-- Your SQL goes here
+
+CREATE TABLE rfc003_requests
+(
+    id INTEGER                   NOT NULL PRIMARY KEY,
+    swap_id UNIQUE               NOT NULL,
+    alpha_ledger_kind            NOT NULL,
+    alpha_ledger                 NOT NULL,
+    beta_ledger_kind             NOT NULL,
+    beta_ledger                  NOT NULL,
+    alpha_asset_kind             NOT NULL,
+    alpha_asset                  NOT NULL,
+    beta_asset_kind              NOT NULL,
+    beta_asset                   NOT NULL,
+    hash_function                NOT NULL,
+    alpha_ledger_refund_identity NOT NULL,
+    beta_ledger_redeem_identity  NOT NULL,
+    alpha_expiry                 NOT NULL,
+    beta_expiry                  NOT NULL,
+    secret_hash                  NOT NULL
+);
+
+CREATE TABLE rfc003_accepts
+(
+    id INTEGER                   NOT NULL PRIMARY KEY,
+    swap_id UNIQUE               NOT NULL,
+    alpha_ledger_redeem_identity NOT NULL,
+    beta_ledger_refund_identity  NOT NULL,
+    at DATETIME DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Move the data of the per ledger/asset tables into the new tables, the
+-- parameters of ledgers and assets are stored as JSON objects.
+
+INSERT INTO rfc003_requests
+(
+    swap_id,
+    alpha_ledger_kind, alpha_ledger,
+    beta_ledger_kind, beta_ledger,
+    alpha_asset_kind, alpha_asset,
+    beta_asset_kind, beta_asset,
+    hash_function,
+    alpha_ledger_refund_identity, beta_ledger_redeem_identity,
+    alpha_expiry, beta_expiry,
+    secret_hash
+)
+SELECT
+    swap_id,
+    'Bitcoin', '{"network":"' || bitcoin_network || '"}',
+    'Ethereum', '{"chain_id":' || ethereum_chain_id || '}',
+    'Bitcoin', '{"quantity":"' || bitcoin_amount || '"}',
+    'Ether', '{"quantity":"' || ether_amount || '"}',
+    hash_function,
+    bitcoin_refund_identity, ethereum_redeem_identity,
+    bitcoin_expiry, ethereum_expiry,
+    secret_hash
+FROM rfc003_bitcoin_ethereum_bitcoin_ether_request_messages;
+
+INSERT INTO rfc003_requests
+(
+    swap_id,
+    alpha_ledger_kind, alpha_ledger,
+    beta_ledger_kind, beta_ledger,
+    alpha_asset_kind, alpha_asset,
+    beta_asset_kind, beta_asset,
+    hash_function,
+    alpha_ledger_refund_identity, beta_ledger_redeem_identity,
+    alpha_expiry, beta_expiry,
+    secret_hash
+)
+SELECT
+    swap_id,
+    'Ethereum', '{"chain_id":' || ethereum_chain_id || '}',
+    'Bitcoin', '{"network":"' || bitcoin_network || '"}',
+    'Ether', '{"quantity":"' || ether_amount || '"}',
+    'Bitcoin', '{"quantity":"' || bitcoin_amount || '"}',
+    hash_function,
+    ethereum_refund_identity, bitcoin_redeem_identity,
+    ethereum_expiry, bitcoin_expiry,
+    secret_hash
+FROM rfc003_ethereum_bitcoin_ether_bitcoin_request_messages;
+
+INSERT INTO rfc003_requests
+(
+    swap_id,
+    alpha_ledger_kind, alpha_ledger,
+    beta_ledger_kind, beta_ledger,
+    alpha_asset_kind, alpha_asset,
+    beta_asset_kind, beta_asset,
+    hash_function,
+    alpha_ledger_refund_identity, beta_ledger_redeem_identity,
+    alpha_expiry, beta_expiry,
+    secret_hash
+)
+SELECT
+    swap_id,
+    'Bitcoin', '{"network":"' || bitcoin_network || '"}',
+    'Ethereum', '{"chain_id":' || ethereum_chain_id || '}',
+    'Bitcoin', '{"quantity":"' || bitcoin_amount || '"}',
+    'Erc20', '{"token_contract":"' || erc20_token_contract || '","quantity":"' || erc20_amount || '"}',
+    hash_function,
+    bitcoin_refund_identity, ethereum_redeem_identity,
+    bitcoin_expiry, ethereum_expiry,
+    secret_hash
+FROM rfc003_bitcoin_ethereum_bitcoin_erc20_request_messages;
+
+INSERT INTO rfc003_requests
+(
+    swap_id,
+    alpha_ledger_kind, alpha_ledger,
+    beta_ledger_kind, beta_ledger,
+    alpha_asset_kind, alpha_asset,
+    beta_asset_kind, beta_asset,
+    hash_function,
+    alpha_ledger_refund_identity, beta_ledger_redeem_identity,
+    alpha_expiry, beta_expiry,
+    secret_hash
+)
+SELECT
+    swap_id,
+    'Ethereum', '{"chain_id":' || ethereum_chain_id || '}',
+    'Bitcoin', '{"network":"' || bitcoin_network || '"}',
+    'Erc20', '{"token_contract":"' || erc20_token_contract || '","quantity":"' || erc20_amount || '"}',
+    'Bitcoin', '{"quantity":"' || bitcoin_amount || '"}',
+    hash_function,
+    ethereum_refund_identity, bitcoin_redeem_identity,
+    ethereum_expiry, bitcoin_expiry,
+    secret_hash
+FROM rfc003_ethereum_bitcoin_erc20_bitcoin_request_messages;
+
+INSERT INTO rfc003_accepts (swap_id, alpha_ledger_redeem_identity, beta_ledger_refund_identity, at)
+SELECT swap_id, bitcoin_redeem_identity, ethereum_refund_identity, at
+FROM rfc003_bitcoin_ethereum_accept_messages;
+
+INSERT INTO rfc003_accepts (swap_id, alpha_ledger_redeem_identity, beta_ledger_refund_identity, at)
+SELECT swap_id, ethereum_redeem_identity, bitcoin_refund_identity, at
+FROM rfc003_ethereum_bitcoin_accept_messages;
+
+DROP TABLE rfc003_bitcoin_ethereum_bitcoin_ether_request_messages;
+DROP TABLE rfc003_ethereum_bitcoin_ether_bitcoin_request_messages;
+DROP TABLE rfc003_bitcoin_ethereum_bitcoin_erc20_request_messages;
+DROP TABLE rfc003_ethereum_bitcoin_erc20_bitcoin_request_messages;
+DROP TABLE rfc003_ethereum_bitcoin_accept_messages;
+DROP TABLE rfc003_bitcoin_ethereum_accept_messages;
@@ -0,0 +1,9 @@
+-- Your SQL goes here
+
+CREATE TABLE peers
+(
+    id INTEGER NOT NULL PRIMARY KEY,
+    peer_id    NOT NULL,
+    address    NOT NULL,
+    UNIQUE (peer_id, address)
+);
@@ -0,0 +1,7 @@
+-- Your SQL goes here
+
+CREATE TABLE banned_peers
+(
+    id INTEGER NOT NULL PRIMARY KEY,
+    peer_id    NOT NULL UNIQUE
+);
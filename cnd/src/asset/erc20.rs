@@ -57,7 +57,7 @@ impl Serialize for Erc20Quantity {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialOrd, Ord, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialOrd, Ord, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct Erc20 {
     pub token_contract: Address,
     pub quantity: Erc20Quantity,
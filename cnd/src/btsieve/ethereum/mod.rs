@@ -3,7 +3,7 @@ mod web3_connector;
 
 pub use self::{
     transaction_pattern::{Event, Topic, TransactionPattern},
-    web3_connector::Web3Connector,
+    web3_connector::{Web3Connector, Web3Connectors},
 };
 use crate::{
     btsieve::{BlockByHash, LatestBlock, Predates, ReceiptByHash},
@@ -1,13 +1,14 @@
 use crate::{
     btsieve::{BlockByHash, LatestBlock, ReceiptByHash},
     ethereum::{BlockId, BlockNumber},
+    swap_protocols::ledger::ethereum::ChainId,
 };
 use anyhow::Context;
 use futures::Future;
 use futures_core::{FutureExt, TryFutureExt};
 use reqwest::{Client, Url};
 use serde::Serialize;
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc};
 
 #[derive(Clone, Debug)]
 pub struct Web3Connector {
@@ -24,6 +25,27 @@ impl Web3Connector {
     }
 }
 
+/// A `Web3Connector` for every Ethereum chain cnd has a node for.
+#[derive(Clone, Debug, Default)]
+pub struct Web3Connectors {
+    connectors: HashMap<ChainId, Web3Connector>,
+}
+
+impl Web3Connectors {
+    pub fn insert(&mut self, chain_id: ChainId, connector: Web3Connector) {
+        self.connectors.insert(chain_id, connector);
+    }
+
+    pub fn get(&self, chain_id: ChainId) -> anyhow::Result<&Web3Connector> {
+        self.connectors.get(&chain_id).ok_or_else(|| {
+            anyhow::anyhow!(
+                "no Ethereum node configured for chain id {}",
+                u32::from(chain_id)
+            )
+        })
+    }
+}
+
 impl LatestBlock for Web3Connector {
     type Block = Option<crate::ethereum::Block<crate::ethereum::Transaction>>;
     type BlockHash = crate::ethereum::H256;
@@ -6,6 +6,7 @@ use crate::{
         rfc003::messages::Decision,
         SwapId, SwapProtocol,
     },
+    timestamp::Timestamp,
 };
 use libp2p_comit::frame::Header;
 use serde::de::Error;
@@ -67,6 +68,18 @@ impl ToHeader for SwapId {
     }
 }
 
+impl FromHeader for Timestamp {
+    fn from_header(header: Header) -> Result<Self, serde_json::Error> {
+        header.value::<Timestamp>()
+    }
+}
+
+impl ToHeader for Timestamp {
+    fn to_header(&self) -> Result<Header, serde_json::Error> {
+        Header::with_value(self)
+    }
+}
+
 impl FromHeader for SwapProtocol {
     fn from_header(mut header: Header) -> Result<Self, serde_json::Error> {
         Ok(match header.value::<String>()?.as_str() {
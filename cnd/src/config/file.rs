@@ -1,4 +1,4 @@
-use crate::config::{Bitcoin, Data, Ethereum, Network, Socket};
+use crate::config::{Bitcoin, Data, Ethereum, Limits, Network, Policy, Socket, Validation};
 use config as config_rs;
 use log::LevelFilter;
 use std::{ffi::OsStr, path::Path};
@@ -16,6 +16,9 @@ pub struct File {
     pub logging: Option<Logging>,
     pub bitcoin: Option<Bitcoin>,
     pub ethereum: Option<Ethereum>,
+    pub validation: Option<Validation>,
+    pub limits: Option<Limits>,
+    pub policy: Option<Policy>,
 }
 
 impl File {
@@ -27,6 +30,9 @@ impl File {
             logging: Option::None,
             bitcoin: Option::None,
             ethereum: Option::None,
+            validation: Option::None,
+            limits: Option::None,
+            policy: Option::None,
         }
     }
 
@@ -79,7 +85,11 @@ pub enum None {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::config::Settings;
+    use crate::{
+        config::{Authentication, EthereumNode, Settings, TradingPair},
+        db::{AssetKind, LedgerKind},
+        swap_protocols::ledger::ethereum::ChainId,
+    };
     use log::LevelFilter;
     use spectral::prelude::*;
     use std::{
@@ -173,11 +183,25 @@ node_url = "http://example.com/"
 
 [ethereum]
 node_url = "http://example.com/"
+
+[[ethereum.additional_nodes]]
+node_url = "http://example.com:8546/"
+chain_id = 3
+
+[validation]
+min_expiry_gap = 3600
+known_chain_ids = [1, 17]
+
+[limits]
+max_requests_per_minute = 10
 "#;
 
         let file = File {
             network: Some(Network {
                 listen: vec!["/ip4/0.0.0.0/tcp/9939".parse().unwrap()],
+                bootstrap_peers: vec![],
+                banned_peers: vec![],
+                authentication: Authentication::default(),
             }),
             http_api: Some(HttpApi {
                 socket: Socket {
@@ -201,13 +225,64 @@ node_url = "http://example.com/"
             }),
             ethereum: Some(Ethereum {
                 node_url: "http://example.com".parse().unwrap(),
+                chain_id: ChainId::regtest(),
+                additional_nodes: vec![EthereumNode {
+                    node_url: "http://example.com:8546".parse().unwrap(),
+                    chain_id: ChainId::ropsten(),
+                }],
             }),
+            validation: Some(Validation {
+                min_expiry_gap: 3600,
+                min_time_to_beta_expiry: 0,
+                known_chain_ids: vec![1, 17],
+                watched_chain_ids: vec![],
+            }),
+            limits: Some(Limits {
+                max_requests_per_minute: 10,
+                ..Limits::default()
+            }),
+            policy: None,
         };
 
         let config = toml::from_str::<File>(contents);
         assert_that(&config).is_ok().is_equal_to(file);
     }
 
+    #[test]
+    fn policy_deserializes_correctly() {
+        let contents = r#"
+ethereum_identity = "0x8457037fcd80a8650c4692d7fcfc1d0a96b92867"
+
+[[pairs]]
+alpha_ledger = "bitcoin"
+beta_ledger = "ethereum"
+alpha_asset = "bitcoin"
+beta_asset = "ether"
+min_alpha_quantity = "100000"
+max_alpha_quantity = "100000000"
+min_rate = 100000000000.0
+min_expiry_gap = 3600
+"#;
+
+        let policy = Policy {
+            ethereum_identity: "8457037fcd80a8650c4692d7fcfc1d0a96b92867".parse().unwrap(),
+            pairs: vec![TradingPair {
+                alpha_ledger: LedgerKind::Bitcoin,
+                beta_ledger: LedgerKind::Ethereum,
+                alpha_asset: AssetKind::Bitcoin,
+                beta_asset: AssetKind::Ether,
+                min_alpha_quantity: Some(100_000.into()),
+                max_alpha_quantity: Some(100_000_000.into()),
+                min_rate: Some(100_000_000_000.0),
+                min_expiry_gap: Some(3600),
+                min_time_to_beta_expiry: None,
+            }],
+        };
+
+        let config = toml::from_str::<Policy>(contents);
+        assert_that(&config).is_ok().is_equal_to(policy);
+    }
+
     #[test]
     fn config_with_defaults_roundtrip() {
         // we start with the default config file
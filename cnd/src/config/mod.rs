@@ -1,8 +1,15 @@
 pub mod file;
 mod serde_bitcoin_network;
+mod serde_decimal_u256;
+mod serde_peer_ids;
 pub mod settings;
 
-use libp2p::Multiaddr;
+use crate::{
+    db::{AssetKind, LedgerKind},
+    ethereum::{Address, U256},
+    swap_protocols::ledger::ethereum::ChainId,
+};
+use libp2p::{Multiaddr, PeerId};
 use serde::{Deserialize, Serialize};
 use std::{net::IpAddr, path::PathBuf};
 
@@ -16,6 +23,32 @@ pub struct Data {
 #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 pub struct Network {
     pub listen: Vec<Multiaddr>,
+    /// Kademlia nodes to join the DHT through, each address has to end with
+    /// the `/p2p/` id of the node.
+    #[serde(default)]
+    pub bootstrap_peers: Vec<Multiaddr>,
+    /// Peers whose requests are ignored.
+    #[serde(default, with = "crate::config::serde_peer_ids")]
+    pub banned_peers: Vec<PeerId>,
+    /// The handshakes offered and accepted when connecting to peers.
+    #[serde(default)]
+    pub authentication: Authentication,
+}
+
+/// The handshakes cnd authenticates connections with.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Authentication {
+    /// Negotiate noise with peers that support it and secio with all others.
+    NoiseOrSecio,
+    Noise,
+    Secio,
+}
+
+impl Default for Authentication {
+    fn default() -> Self {
+        Authentication::NoiseOrSecio
+    }
 }
 
 #[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
@@ -34,6 +67,138 @@ pub struct Bitcoin {
 #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 pub struct Ethereum {
     pub node_url: reqwest::Url,
+    /// The chain id of the network `node_url` is connected to, advertised to
+    /// other nodes.
+    #[serde(default = "ChainId::regtest")]
+    pub chain_id: ChainId,
+    /// Nodes of further chains, needed to watch swaps on them.
+    #[serde(default)]
+    pub additional_nodes: Vec<EthereumNode>,
+}
+
+impl Ethereum {
+    /// The chain ids of all configured nodes, starting with `chain_id`.
+    pub fn chain_ids(&self) -> Vec<ChainId> {
+        std::iter::once(self.chain_id)
+            .chain(self.additional_nodes.iter().map(|node| node.chain_id))
+            .collect()
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct EthereumNode {
+    pub node_url: reqwest::Url,
+    pub chain_id: ChainId,
+}
+
+/// Additional constraints on incoming swap requests.
+///
+/// Requests that do not satisfy them are declined before they are offered to
+/// the user.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Validation {
+    /// The minimum number of seconds between the beta and the alpha expiry.
+    #[serde(default)]
+    pub min_expiry_gap: u32,
+    /// The minimum number of seconds until the beta expiry.
+    #[serde(default)]
+    pub min_time_to_beta_expiry: u32,
+    /// The Ethereum chain ids swaps can be executed on.
+    #[serde(default = "Validation::default_known_chain_ids")]
+    pub known_chain_ids: Vec<u32>,
+    /// The Ethereum chain ids cnd has a node for, taken from the `ethereum`
+    /// section.
+    #[serde(skip)]
+    pub watched_chain_ids: Vec<u32>,
+}
+
+impl Validation {
+    /// Mainnet, Ropsten, Rinkeby, Görli, the local development chain and
+    /// Kovan.
+    fn default_known_chain_ids() -> Vec<u32> {
+        vec![1, 3, 4, 5, 17, 42]
+    }
+}
+
+impl Default for Validation {
+    fn default() -> Self {
+        Validation {
+            min_expiry_gap: 0,
+            min_time_to_beta_expiry: 0,
+            known_chain_ids: Validation::default_known_chain_ids(),
+            watched_chain_ids: vec![ChainId::regtest().into()],
+        }
+    }
+}
+
+/// Limits on the resources other nodes can make cnd spend.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(default)]
+pub struct Limits {
+    /// The maximum number of incoming connections that are set up at the same
+    /// time, further connection attempts are refused until one of them is
+    /// established.
+    pub max_pending_connections: u32,
+    /// The maximum number of peers cnd is connected to, further incoming
+    /// connections are closed once they are established.
+    pub max_connections: usize,
+    /// The maximum number of requests a single peer can send per minute.
+    pub max_requests_per_minute: u32,
+    /// The maximum number of swap requests waiting for an answer, further
+    /// requests are declined.
+    pub max_pending_proposals: usize,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Limits {
+            max_pending_connections: 32,
+            max_connections: 128,
+            max_requests_per_minute: 60,
+            max_pending_proposals: 100,
+        }
+    }
+}
+
+/// Describes which swap requests cnd answers without waiting for the user.
+///
+/// Requests for a swap that is not covered by any of the trading pairs are
+/// declined.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Policy {
+    /// The identity used on Ethereum when accepting a swap.
+    pub ethereum_identity: Address,
+    pub pairs: Vec<TradingPair>,
+}
+
+/// A ledger and asset combination cnd is willing to swap.
+///
+/// Quantities are given in the smallest unit of the asset (satoshi, wei or the
+/// smallest unit of the token), the rate is the quantity of the beta asset per
+/// quantity of the alpha asset in these units.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+pub struct TradingPair {
+    pub alpha_ledger: LedgerKind,
+    pub beta_ledger: LedgerKind,
+    pub alpha_asset: AssetKind,
+    pub beta_asset: AssetKind,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "crate::config::serde_decimal_u256"
+    )]
+    pub min_alpha_quantity: Option<U256>,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "crate::config::serde_decimal_u256"
+    )]
+    pub max_alpha_quantity: Option<U256>,
+    pub min_rate: Option<f64>,
+    /// The minimum number of seconds between the beta and the alpha expiry.
+    pub min_expiry_gap: Option<u32>,
+    /// The minimum number of seconds until the beta expiry.
+    pub min_time_to_beta_expiry: Option<u32>,
 }
 
 #[cfg(test)]
@@ -50,17 +215,71 @@ mod tests {
             r#"
             listen = ["/ip4/0.0.0.0/tcp/9939", "/ip4/127.0.0.1/tcp/9939"]
             "#,
+            r#"
+            listen = ["/ip4/0.0.0.0/tcp/9939"]
+            bootstrap_peers = ["/ip4/1.2.3.4/tcp/9939/p2p/QmfUfpC2frwFvcDzpspnfZitHt5wct6n4kpG5jzgRdsxkY"]
+            "#,
+            r#"
+            listen = ["/ip4/0.0.0.0/tcp/9939"]
+            banned_peers = ["QmfUfpC2frwFvcDzpspnfZitHt5wct6n4kpG5jzgRdsxkY"]
+            "#,
+            r#"
+            listen = ["/ip4/0.0.0.0/tcp/9939"]
+            authentication = "secio"
+            "#,
+            r#"
+            listen = ["/ip4/0.0.0.0/tcp/9939", "/ip4/0.0.0.0/tcp/9940/ws"]
+            "#,
         ];
 
         let expected = vec![
             Network {
                 listen: vec!["/ip4/0.0.0.0/tcp/9939".parse().unwrap()],
+                bootstrap_peers: vec![],
+                banned_peers: vec![],
+                authentication: Authentication::default(),
             },
             Network {
                 listen: (vec![
                     "/ip4/0.0.0.0/tcp/9939".parse().unwrap(),
                     "/ip4/127.0.0.1/tcp/9939".parse().unwrap(),
                 ]),
+                bootstrap_peers: vec![],
+                banned_peers: vec![],
+                authentication: Authentication::default(),
+            },
+            Network {
+                listen: vec!["/ip4/0.0.0.0/tcp/9939".parse().unwrap()],
+                bootstrap_peers: vec![
+                    "/ip4/1.2.3.4/tcp/9939/p2p/QmfUfpC2frwFvcDzpspnfZitHt5wct6n4kpG5jzgRdsxkY"
+                        .parse()
+                        .unwrap(),
+                ],
+                banned_peers: vec![],
+                authentication: Authentication::default(),
+            },
+            Network {
+                listen: vec!["/ip4/0.0.0.0/tcp/9939".parse().unwrap()],
+                bootstrap_peers: vec![],
+                banned_peers: vec!["QmfUfpC2frwFvcDzpspnfZitHt5wct6n4kpG5jzgRdsxkY"
+                    .parse()
+                    .unwrap()],
+                authentication: Authentication::default(),
+            },
+            Network {
+                listen: vec!["/ip4/0.0.0.0/tcp/9939".parse().unwrap()],
+                bootstrap_peers: vec![],
+                banned_peers: vec![],
+                authentication: Authentication::Secio,
+            },
+            Network {
+                listen: vec![
+                    "/ip4/0.0.0.0/tcp/9939".parse().unwrap(),
+                    "/ip4/0.0.0.0/tcp/9940/ws".parse().unwrap(),
+                ],
+                bootstrap_peers: vec![],
+                banned_peers: vec![],
+                authentication: Authentication::default(),
             },
         ];
 
@@ -0,0 +1,37 @@
+use crate::ethereum::{FromDecimalStr, U256};
+use serde::{de, export::fmt, Deserializer, Serializer};
+
+pub fn deserialize<'de, D>(deserializer: D) -> Result<Option<U256>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    struct Visitor;
+
+    impl<'de> de::Visitor<'de> for Visitor {
+        type Value = Option<U256>;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+            formatter.write_str("a decimal number as a string")
+        }
+
+        fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+        where
+            E: de::Error,
+        {
+            U256::from_decimal_str(v)
+                .map(Some)
+                .map_err(|_| E::custom(format!("invalid decimal number {}", v)))
+        }
+    }
+
+    deserializer.deserialize_str(Visitor)
+}
+
+// This is the API serde expects, can't do much about the reference :(
+#[allow(clippy::trivially_copy_pass_by_ref)]
+pub fn serialize<S: Serializer>(value: &Option<U256>, serializer: S) -> Result<S::Ok, S::Error> {
+    match value {
+        Some(value) => serializer.serialize_str(&value.to_string()),
+        None => serializer.serialize_none(),
+    }
+}
@@ -0,0 +1,24 @@
+use libp2p::PeerId;
+use serde::{de, ser::SerializeSeq, Deserialize, Deserializer, Serializer};
+use std::str::FromStr;
+
+pub fn deserialize<'de, D>(deserializer: D) -> Result<Vec<PeerId>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Vec::<String>::deserialize(deserializer)?
+        .iter()
+        .map(|peer_id| {
+            PeerId::from_str(peer_id)
+                .map_err(|_| de::Error::custom(format!("invalid peer id {}", peer_id)))
+        })
+        .collect()
+}
+
+pub fn serialize<S: Serializer>(value: &[PeerId], serializer: S) -> Result<S::Ok, S::Error> {
+    let mut seq = serializer.serialize_seq(Some(value.len()))?;
+    for peer_id in value {
+        seq.serialize_element(&peer_id.to_base58())?;
+    }
+    seq.end()
+}
@@ -1,4 +1,10 @@
-use crate::config::{file, Bitcoin, Data, Ethereum, File, Network, Socket};
+use crate::{
+    config::{
+        file, Authentication, Bitcoin, Data, Ethereum, File, Limits, Network, Policy, Socket,
+        Validation,
+    },
+    swap_protocols::ledger::ethereum::ChainId,
+};
 use anyhow::Context;
 use log::LevelFilter;
 use reqwest::Url;
@@ -18,6 +24,9 @@ pub struct Settings {
     pub logging: Logging,
     pub bitcoin: Bitcoin,
     pub ethereum: Ethereum,
+    pub validation: Validation,
+    pub limits: Limits,
+    pub policy: Option<Policy>,
 }
 
 impl From<Settings> for File {
@@ -29,6 +38,9 @@ impl From<Settings> for File {
             logging: Logging { level, structured },
             bitcoin,
             ethereum,
+            validation,
+            limits,
+            policy,
         } = settings;
 
         File {
@@ -50,6 +62,9 @@ impl From<Settings> for File {
             }),
             bitcoin: Some(bitcoin),
             ethereum: Some(ethereum),
+            validation: Some(validation),
+            limits: Some(limits),
+            policy,
         }
     }
 }
@@ -109,8 +124,21 @@ impl Settings {
             logging,
             bitcoin,
             ethereum,
+            validation,
+            limits,
+            policy,
         } = config_file;
 
+        let ethereum = ethereum.unwrap_or_else(|| Ethereum {
+            node_url: Url::parse("http://localhost:8545").expect("static string to be a valid url"),
+            chain_id: ChainId::regtest(),
+            additional_nodes: vec![],
+        });
+        let validation = Validation {
+            watched_chain_ids: ethereum.chain_ids().into_iter().map(u32::from).collect(),
+            ..validation.unwrap_or_default()
+        };
+
         Ok(Self {
             network: network.unwrap_or_else(|| {
                 let default_socket = "/ip4/0.0.0.0/tcp/9939"
@@ -119,6 +147,9 @@ impl Settings {
 
                 Network {
                     listen: vec![default_socket],
+                    bootstrap_peers: vec![],
+                    banned_peers: vec![],
+                    authentication: Authentication::default(),
                 }
             }),
             http_api: http_api
@@ -165,10 +196,10 @@ impl Settings {
                 node_url: Url::parse("http://localhost:18443")
                     .expect("static string to be a valid url"),
             }),
-            ethereum: ethereum.unwrap_or_else(|| Ethereum {
-                node_url: Url::parse("http://localhost:8545")
-                    .expect("static string to be a valid url"),
-            }),
+            ethereum,
+            validation,
+            limits: limits.unwrap_or_default(),
+            policy,
         })
     }
 }
@@ -177,7 +208,7 @@ impl Settings {
 mod tests {
 
     use super::*;
-    use crate::config::file;
+    use crate::config::{file, EthereumNode};
     use spectral::prelude::*;
     use std::net::{IpAddr, Ipv4Addr};
 
@@ -295,6 +326,31 @@ mod tests {
             .map(|settings| &settings.network)
             .is_equal_to(Network {
                 listen: vec!["/ip4/0.0.0.0/tcp/9939".parse().unwrap()],
+                bootstrap_peers: vec![],
+                banned_peers: vec![],
+                authentication: Authentication::default(),
             })
     }
+
+    #[test]
+    fn chains_of_all_ethereum_nodes_are_watched() {
+        let config_file = File {
+            ethereum: Some(Ethereum {
+                node_url: "http://localhost:8545".parse().unwrap(),
+                chain_id: ChainId::regtest(),
+                additional_nodes: vec![EthereumNode {
+                    node_url: "http://localhost:8546".parse().unwrap(),
+                    chain_id: ChainId::ropsten(),
+                }],
+            }),
+            ..File::default()
+        };
+
+        let settings = Settings::from_config_file_and_defaults(config_file);
+
+        assert_that(&settings)
+            .is_ok()
+            .map(|settings| &settings.validation.watched_chain_ids)
+            .is_equal_to(vec![17, 3])
+    }
 }
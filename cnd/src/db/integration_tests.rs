@@ -2,15 +2,27 @@ use crate::{
     asset::{Bitcoin as BitcoinAsset, Erc20, Ether},
     db::{
         load_swaps::LoadAcceptedSwap,
+        new_types::{DecimalU256, EthereumAddress},
         swap_types::{DetermineTypes, SwapTypes},
-        AssetKind, LedgerKind, Retrieve, Save, Sqlite, Swap,
+        AssetKind, BanList, CommunicationState, DetermineCommunicationState, LedgerKind,
+        LoadDecline, LoadRequest, LoadSwapEvents, PeerAddress, PeerAddressBook, Retrieve, Save,
+        Sqlite, Swap, SwapEventRecord,
     },
     quickcheck::Quickcheck,
     swap_protocols::{
         ledger::{Bitcoin, Ethereum},
-        rfc003::{Accept, Request},
+        rfc003::{
+            create_swap::SwapEvent,
+            events::{Deployed, Funded, Redeemed},
+            messages::SwapDeclineReason,
+            Accept, Decline, Request, Secret,
+        },
+        SwapId,
     },
 };
+use chrono::NaiveDate;
+use diesel::{connection::SimpleConnection, Connection, SqliteConnection};
+use libp2p::{Multiaddr, PeerId};
 use std::path::Path;
 
 macro_rules! db_roundtrip_test {
@@ -114,3 +126,412 @@ db_roundtrip_test!(Ethereum, Bitcoin, Erc20, BitcoinAsset, |role| {
         role,
     }
 });
+db_roundtrip_test!(Ethereum, Ethereum, Ether, Erc20, |role| {
+    SwapTypes {
+        alpha_ledger: LedgerKind::Ethereum,
+        beta_ledger: LedgerKind::Ethereum,
+        alpha_asset: AssetKind::Ether,
+        beta_asset: AssetKind::Erc20,
+        role,
+    }
+});
+db_roundtrip_test!(Ethereum, Ethereum, Erc20, Ether, |role| {
+    SwapTypes {
+        alpha_ledger: LedgerKind::Ethereum,
+        beta_ledger: LedgerKind::Ethereum,
+        alpha_asset: AssetKind::Erc20,
+        beta_asset: AssetKind::Ether,
+        role,
+    }
+});
+db_roundtrip_test!(Ethereum, Ethereum, Erc20, Erc20, |role| {
+    SwapTypes {
+        alpha_ledger: LedgerKind::Ethereum,
+        beta_ledger: LedgerKind::Ethereum,
+        alpha_asset: AssetKind::Erc20,
+        beta_asset: AssetKind::Erc20,
+        role,
+    }
+});
+
+#[test]
+fn saved_swap_events_are_loaded_in_order() -> anyhow::Result<()> {
+    let db = Sqlite::new(&Path::new(":memory:"))?;
+    let swap_id = SwapId::default();
+
+    let transaction = bitcoin::Transaction {
+        version: 1,
+        lock_time: 0,
+        input: vec![],
+        output: vec![],
+    };
+    let deployed = SwapEvent::<Bitcoin, Ethereum, BitcoinAsset, Ether>::AlphaDeployed(Deployed {
+        transaction: transaction.clone(),
+        location: bitcoin::OutPoint::null(),
+    });
+    let funded = SwapEvent::AlphaFunded(Funded {
+        transaction: transaction.clone(),
+        asset: BitcoinAsset::from_sat(100_000_000),
+    });
+    let redeemed = SwapEvent::AlphaRedeemed(Redeemed {
+        transaction,
+        secret: Secret::from(*b"hello world, you are beautiful!!"),
+    });
+
+    let loaded_events = tokio::runtime::Runtime::new()?.block_on(async {
+        for event in vec![deployed.clone(), funded.clone(), redeemed.clone()] {
+            db.save(SwapEventRecord { swap_id, event }).await?;
+        }
+
+        // events of other swaps must not show up
+        db.save(SwapEventRecord {
+            swap_id: SwapId::default(),
+            event: deployed.clone(),
+        })
+        .await?;
+
+        LoadSwapEvents::<Bitcoin, Ethereum, BitcoinAsset, Ether>::load_swap_events(&db, &swap_id)
+            .await
+    })?;
+
+    assert_eq!(loaded_events, vec![deployed, funded, redeemed]);
+
+    Ok(())
+}
+
+#[test]
+fn peer_addresses_are_saved_once_and_deleted_per_peer() -> anyhow::Result<()> {
+    let db = Sqlite::new(&Path::new(":memory:"))?;
+
+    let alice = PeerId::random();
+    let bob = PeerId::random();
+    let first: Multiaddr = "/ip4/127.0.0.1/tcp/9939".parse()?;
+    let second: Multiaddr = "/ip4/10.0.0.1/tcp/9939".parse()?;
+
+    let (before, deleted, after) = tokio::runtime::Runtime::new()?.block_on(async {
+        for (peer_id, address) in vec![
+            (alice.clone(), first.clone()),
+            (bob.clone(), first.clone()),
+            (alice.clone(), second.clone()),
+            (alice.clone(), first.clone()),
+        ] {
+            db.save(PeerAddress { peer_id, address }).await?;
+        }
+
+        let before = db.peer_addresses().await?;
+        let deleted = db.delete_peer(&alice).await?;
+        let after = db.peer_addresses().await?;
+
+        Ok::<_, anyhow::Error>((before, deleted, after))
+    })?;
+
+    assert_eq!(before, vec![
+        (alice, vec![first.clone(), second.clone()]),
+        (bob.clone(), vec![first.clone()]),
+    ]);
+    assert_eq!(deleted, vec![first.clone(), second]);
+    assert_eq!(after, vec![(bob, vec![first])]);
+
+    Ok(())
+}
+
+#[test]
+fn banned_peers_are_saved_once_and_unbanned() -> anyhow::Result<()> {
+    let db = Sqlite::new(&Path::new(":memory:"))?;
+
+    let alice = PeerId::random();
+    let bob = PeerId::random();
+
+    let (before, unbanned, unbanned_again, after) =
+        tokio::runtime::Runtime::new()?.block_on(async {
+            db.ban_peer(&alice).await?;
+            db.ban_peer(&bob).await?;
+            db.ban_peer(&alice).await?;
+
+            let before = db.banned_peers().await?;
+            let unbanned = db.unban_peer(&alice).await?;
+            let unbanned_again = db.unban_peer(&alice).await?;
+            let after = db.banned_peers().await?;
+
+            Ok::<_, anyhow::Error>((before, unbanned, unbanned_again, after))
+        })?;
+
+    assert_eq!(before, vec![alice, bob.clone()]);
+    assert!(unbanned);
+    assert!(!unbanned_again);
+    assert_eq!(after, vec![bob]);
+
+    Ok(())
+}
+
+#[test]
+fn communication_state_follows_saved_messages() {
+    fn prop(
+        swap: Quickcheck<Swap>,
+        request: Quickcheck<Request<Bitcoin, Ethereum, BitcoinAsset, Ether>>,
+        accept: Quickcheck<Accept<Bitcoin, Ethereum>>,
+    ) -> anyhow::Result<bool> {
+        let proposed_swap = swap.0;
+        let proposed_request = Request {
+            swap_id: proposed_swap.swap_id,
+            ..*request
+        };
+
+        let declined_swap = Swap {
+            swap_id: SwapId::default(),
+            ..proposed_swap.clone()
+        };
+        let declined_request = Request {
+            swap_id: declined_swap.swap_id,
+            ..*request
+        };
+
+        let accepted_swap = Swap {
+            swap_id: SwapId::default(),
+            ..proposed_swap.clone()
+        };
+        let accepted_request = Request {
+            swap_id: accepted_swap.swap_id,
+            ..*request
+        };
+
+        let timed_out_swap = Swap {
+            swap_id: SwapId::default(),
+            ..proposed_swap.clone()
+        };
+        let timed_out_request = Request {
+            swap_id: timed_out_swap.swap_id,
+            ..*request
+        };
+
+        let db = Sqlite::new(&Path::new(":memory:"))?;
+
+        let (proposed, declined, accepted, timed_out, loaded_request) =
+            tokio::runtime::Runtime::new()?.block_on(async {
+                db.save(proposed_swap.clone()).await?;
+                db.save(proposed_request.clone()).await?;
+
+                db.save(declined_swap.clone()).await?;
+                db.save(declined_request.clone()).await?;
+                db.save(Decline {
+                    swap_id: declined_swap.swap_id,
+                    reason: None,
+                })
+                .await?;
+
+                db.save(accepted_swap.clone()).await?;
+                db.save(accepted_request.clone()).await?;
+                db.save(Accept {
+                    swap_id: accepted_swap.swap_id,
+                    ..*accept
+                })
+                .await?;
+
+                db.save(timed_out_swap.clone()).await?;
+                db.save(timed_out_request.clone()).await?;
+                db.save(Decline {
+                    swap_id: timed_out_swap.swap_id,
+                    reason: Some(SwapDeclineReason::ProposalExpired),
+                })
+                .await?;
+
+                let proposed = db
+                    .determine_communication_state(&proposed_swap.swap_id)
+                    .await?;
+                let declined = db
+                    .determine_communication_state(&declined_swap.swap_id)
+                    .await?;
+                let accepted = db
+                    .determine_communication_state(&accepted_swap.swap_id)
+                    .await?;
+                let timed_out = db
+                    .determine_communication_state(&timed_out_swap.swap_id)
+                    .await?;
+                let loaded_request =
+                    LoadRequest::<Bitcoin, Ethereum, BitcoinAsset, Ether>::load_request(
+                        &db,
+                        &proposed_swap.swap_id,
+                    )
+                    .await?;
+
+                anyhow::Result::<_>::Ok((proposed, declined, accepted, timed_out, loaded_request))
+            })?;
+
+        Ok(proposed == CommunicationState::Proposed
+            && declined == CommunicationState::Declined
+            && accepted == CommunicationState::Accepted
+            && timed_out == CommunicationState::TimedOut
+            && loaded_request == proposed_request)
+    }
+
+    quickcheck::quickcheck(
+        prop as fn(
+            Quickcheck<Swap>,
+            Quickcheck<Request<Bitcoin, Ethereum, BitcoinAsset, Ether>>,
+            Quickcheck<Accept<Bitcoin, Ethereum>>,
+        ) -> anyhow::Result<bool>,
+    );
+}
+
+#[test]
+fn decline_reason_roundtrip() -> anyhow::Result<()> {
+    let with_reason = Decline {
+        swap_id: SwapId::default(),
+        reason: Some(SwapDeclineReason::UnsatisfactoryRate),
+    };
+    let without_reason = Decline {
+        swap_id: SwapId::default(),
+        reason: None,
+    };
+
+    let db = Sqlite::new(&Path::new(":memory:"))?;
+
+    let (loaded_with_reason, loaded_without_reason) =
+        tokio::runtime::Runtime::new()?.block_on(async {
+            db.save(with_reason).await?;
+            db.save(without_reason).await?;
+
+            let loaded_with_reason = db.load_decline(&with_reason.swap_id).await?;
+            let loaded_without_reason = db.load_decline(&without_reason.swap_id).await?;
+
+            anyhow::Result::<_>::Ok((loaded_with_reason, loaded_without_reason))
+        })?;
+
+    assert_eq!(loaded_with_reason, with_reason);
+    assert_eq!(loaded_without_reason, without_reason);
+
+    Ok(())
+}
+
+#[test]
+fn swaps_saved_before_normalizing_the_swap_tables_can_be_loaded() -> anyhow::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("cnd.sqlite");
+
+    let bitcoin_ether =
+        Request::bitcoin_for_ether(100_000_000, 1_000_000, 2_000_000_000, 1_900_000_000);
+    let ether_bitcoin = Request::<Ethereum, Bitcoin, Ether, BitcoinAsset> {
+        swap_id: SwapId::default(),
+        alpha_ledger: Ethereum::default(),
+        beta_ledger: Bitcoin::default(),
+        alpha_asset: bitcoin_ether.beta_asset,
+        beta_asset: bitcoin_ether.alpha_asset,
+        hash_function: bitcoin_ether.hash_function,
+        alpha_ledger_refund_identity: bitcoin_ether.beta_ledger_redeem_identity,
+        beta_ledger_redeem_identity: bitcoin_ether.alpha_ledger_refund_identity,
+        alpha_expiry: bitcoin_ether.alpha_expiry,
+        beta_expiry: bitcoin_ether.beta_expiry,
+        secret_hash: bitcoin_ether.secret_hash,
+    };
+    let bitcoin_ether_accept = Accept::<Bitcoin, Ethereum> {
+        swap_id: bitcoin_ether.swap_id,
+        alpha_ledger_redeem_identity: bitcoin_ether.alpha_ledger_refund_identity,
+        beta_ledger_refund_identity: bitcoin_ether.beta_ledger_redeem_identity,
+    };
+    let ether_bitcoin_accept = Accept::<Ethereum, Bitcoin> {
+        swap_id: ether_bitcoin.swap_id,
+        alpha_ledger_redeem_identity: ether_bitcoin.alpha_ledger_refund_identity,
+        beta_ledger_refund_identity: ether_bitcoin.beta_ledger_redeem_identity,
+    };
+    let accepted_at = NaiveDate::from_ymd(2019, 12, 1).and_hms(12, 0, 0);
+    let counterparty = PeerId::random();
+
+    // the schema and rows as they were before the swap tables were normalized
+    {
+        let connection = SqliteConnection::establish(&format!("file:{}", path.display()))?;
+        connection.batch_execute(include_str!(
+            "../../migrations/2019-11-11-034058_create-message-tables/up.sql"
+        ))?;
+        connection.batch_execute(include_str!(
+            "../../migrations/2019-12-18-054510_create-swap-events-table/up.sql"
+        ))?;
+        connection.batch_execute(&format!(
+            r#"
+CREATE TABLE __diesel_schema_migrations (
+    version VARCHAR(50) PRIMARY KEY NOT NULL,
+    run_on TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+INSERT INTO __diesel_schema_migrations (version) VALUES ('20191111034058'), ('20191218054510');
+
+INSERT INTO rfc003_swaps (swap_id, role, counterparty)
+VALUES ('{bitcoin_ether_id}', 'Alice', '{counterparty}'), ('{ether_bitcoin_id}', 'Bob', '{counterparty}');
+
+INSERT INTO rfc003_bitcoin_ethereum_bitcoin_ether_request_messages (
+    swap_id, bitcoin_network, ethereum_chain_id, bitcoin_amount, ether_amount, hash_function,
+    bitcoin_refund_identity, ethereum_redeem_identity, bitcoin_expiry, ethereum_expiry, secret_hash
+)
+VALUES (
+    '{bitcoin_ether_id}', '{network}', {chain_id}, '{sat}', '{wei}', '{hash_function}',
+    '{bitcoin_identity}', '{ethereum_identity}', {alpha_expiry}, {beta_expiry}, '{secret_hash}'
+);
+
+INSERT INTO rfc003_ethereum_bitcoin_ether_bitcoin_request_messages (
+    swap_id, bitcoin_network, ethereum_chain_id, bitcoin_amount, ether_amount, hash_function,
+    bitcoin_redeem_identity, ethereum_refund_identity, bitcoin_expiry, ethereum_expiry, secret_hash
+)
+VALUES (
+    '{ether_bitcoin_id}', '{network}', {chain_id}, '{sat}', '{wei}', '{hash_function}',
+    '{bitcoin_identity}', '{ethereum_identity}', {beta_expiry}, {alpha_expiry}, '{secret_hash}'
+);
+
+INSERT INTO rfc003_bitcoin_ethereum_accept_messages (
+    swap_id, bitcoin_redeem_identity, ethereum_refund_identity, at
+)
+VALUES ('{bitcoin_ether_id}', '{bitcoin_identity}', '{ethereum_identity}', '{accepted_at}');
+
+INSERT INTO rfc003_ethereum_bitcoin_accept_messages (
+    swap_id, bitcoin_refund_identity, ethereum_redeem_identity, at
+)
+VALUES ('{ether_bitcoin_id}', '{bitcoin_identity}', '{ethereum_identity}', '{accepted_at}');
+"#,
+            bitcoin_ether_id = bitcoin_ether.swap_id,
+            ether_bitcoin_id = ether_bitcoin.swap_id,
+            counterparty = counterparty,
+            network = bitcoin_ether.alpha_ledger.network,
+            chain_id = u32::from(bitcoin_ether.beta_ledger.chain_id),
+            sat = bitcoin_ether.alpha_asset.as_sat(),
+            wei = DecimalU256(bitcoin_ether.beta_asset.wei()),
+            hash_function = bitcoin_ether.hash_function,
+            bitcoin_identity = bitcoin_ether.alpha_ledger_refund_identity.into_inner(),
+            ethereum_identity = EthereumAddress(bitcoin_ether.beta_ledger_redeem_identity),
+            alpha_expiry = u32::from(bitcoin_ether.alpha_expiry),
+            beta_expiry = u32::from(bitcoin_ether.beta_expiry),
+            secret_hash = bitcoin_ether.secret_hash,
+            accepted_at = accepted_at.format("%Y-%m-%d %H:%M:%S"),
+        ))?;
+    }
+
+    // runs the remaining migrations
+    let db = Sqlite::new(&path)?;
+
+    let (swaps, loaded_bitcoin_ether, loaded_ether_bitcoin) = tokio::runtime::Runtime::new()?
+        .block_on(async {
+            let swaps = Retrieve::all(&db).await?;
+            let bitcoin_ether =
+                LoadAcceptedSwap::<Bitcoin, Ethereum, BitcoinAsset, Ether>::load_accepted_swap(
+                    &db,
+                    &bitcoin_ether.swap_id,
+                )
+                .await?;
+            let ether_bitcoin =
+                LoadAcceptedSwap::<Ethereum, Bitcoin, Ether, BitcoinAsset>::load_accepted_swap(
+                    &db,
+                    &ether_bitcoin.swap_id,
+                )
+                .await?;
+
+            Ok::<_, anyhow::Error>((swaps, bitcoin_ether, ether_bitcoin))
+        })?;
+
+    assert_eq!(swaps.len(), 2);
+    assert_eq!(
+        loaded_bitcoin_ether,
+        (bitcoin_ether, bitcoin_ether_accept, accepted_at)
+    );
+    assert_eq!(
+        loaded_ether_bitcoin,
+        (ether_bitcoin, ether_bitcoin_accept, accepted_at)
+    );
+
+    Ok(())
+}
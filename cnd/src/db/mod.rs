@@ -3,20 +3,26 @@ mod custom_sql_types;
 mod integration_tests;
 mod load_swaps;
 mod new_types;
+mod params;
+mod peers;
 mod save;
 mod schema;
 #[cfg(test)]
 mod serialization_format_stability_tests;
 mod swap;
+mod swap_events;
 mod swap_types;
 #[macro_use]
 pub mod with_swap_types;
 embed_migrations!("./migrations");
 
 pub use self::{
-    load_swaps::{AcceptedSwap, LoadAcceptedSwap},
+    load_swaps::{AcceptedSwap, LoadAcceptedSwap, LoadDecline, LoadRequest},
+    params::{StoredAsset, StoredLedger},
+    peers::{BanList, PeerAddress, PeerAddressBook},
     save::*,
     swap::*,
+    swap_events::*,
     swap_types::*,
 };
 
@@ -128,6 +134,8 @@ struct QueryableSwapRole {
 pub enum Error {
     #[error("swap not found")]
     SwapNotFound,
+    #[error("peer not found")]
+    PeerNotFound,
 }
 
 #[cfg(test)]
@@ -0,0 +1,176 @@
+use crate::{
+    asset::{self, Asset},
+    db::{
+        new_types::{DecimalU256, EthereumAddress, Satoshis},
+        AssetKind, LedgerKind,
+    },
+    ethereum::U256,
+    swap_protocols::{
+        ledger::{ethereum::ChainId, Bitcoin, Ethereum},
+        rfc003::Ledger,
+    },
+};
+use serde::{Deserialize, Serialize};
+
+/// A ledger that can be stored in the generic ledger columns of the database.
+///
+/// The kind of the ledger is stored next to a JSON object that holds its
+/// parameters. Identities are stored in their textual representation.
+pub trait StoredLedger: Ledger {
+    const KIND: LedgerKind;
+
+    fn to_params(&self) -> anyhow::Result<String>;
+    fn from_params(params: &str) -> anyhow::Result<Self>;
+    fn identity_to_text(identity: Self::Identity) -> String;
+    fn identity_from_text(text: &str) -> anyhow::Result<Self::Identity>;
+}
+
+/// An asset that can be stored in the generic asset columns of the database.
+///
+/// The kind of the asset is stored next to a JSON object that holds its
+/// parameters.
+pub trait StoredAsset: Asset {
+    const KIND: AssetKind;
+
+    fn to_params(&self) -> anyhow::Result<String>;
+    fn from_params(params: &str) -> anyhow::Result<Self>;
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct BitcoinLedgerParams {
+    network: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct EthereumLedgerParams {
+    chain_id: u32,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct QuantityParams {
+    quantity: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Erc20Params {
+    token_contract: String,
+    quantity: String,
+}
+
+impl StoredLedger for Bitcoin {
+    const KIND: LedgerKind = LedgerKind::Bitcoin;
+
+    fn to_params(&self) -> anyhow::Result<String> {
+        let params = BitcoinLedgerParams {
+            network: self.network.to_string(),
+        };
+
+        Ok(serde_json::to_string(&params)?)
+    }
+
+    fn from_params(params: &str) -> anyhow::Result<Self> {
+        let params = serde_json::from_str::<BitcoinLedgerParams>(params)?;
+
+        Ok(Bitcoin::new(params.network.parse()?))
+    }
+
+    fn identity_to_text(identity: Self::Identity) -> String {
+        identity.into_inner().to_string()
+    }
+
+    fn identity_from_text(text: &str) -> anyhow::Result<Self::Identity> {
+        let public_key = text.parse::<bitcoin::PublicKey>()?;
+
+        Ok(crate::bitcoin::PublicKey::from(public_key))
+    }
+}
+
+impl StoredLedger for Ethereum {
+    const KIND: LedgerKind = LedgerKind::Ethereum;
+
+    fn to_params(&self) -> anyhow::Result<String> {
+        let params = EthereumLedgerParams {
+            chain_id: self.chain_id.into(),
+        };
+
+        Ok(serde_json::to_string(&params)?)
+    }
+
+    fn from_params(params: &str) -> anyhow::Result<Self> {
+        let params = serde_json::from_str::<EthereumLedgerParams>(params)?;
+
+        Ok(Ethereum::new(ChainId::new(params.chain_id)))
+    }
+
+    fn identity_to_text(identity: Self::Identity) -> String {
+        EthereumAddress(identity).to_string()
+    }
+
+    fn identity_from_text(text: &str) -> anyhow::Result<Self::Identity> {
+        let address = text.parse::<EthereumAddress>()?;
+
+        Ok(address.0)
+    }
+}
+
+impl StoredAsset for asset::Bitcoin {
+    const KIND: AssetKind = AssetKind::Bitcoin;
+
+    fn to_params(&self) -> anyhow::Result<String> {
+        let params = QuantityParams {
+            quantity: Satoshis(self.as_sat()).to_string(),
+        };
+
+        Ok(serde_json::to_string(&params)?)
+    }
+
+    fn from_params(params: &str) -> anyhow::Result<Self> {
+        let params = serde_json::from_str::<QuantityParams>(params)?;
+        let quantity = params.quantity.parse::<Satoshis>()?;
+
+        Ok(asset::Bitcoin::from_sat(quantity.into()))
+    }
+}
+
+impl StoredAsset for asset::Ether {
+    const KIND: AssetKind = AssetKind::Ether;
+
+    fn to_params(&self) -> anyhow::Result<String> {
+        let params = QuantityParams {
+            quantity: DecimalU256(self.wei()).to_string(),
+        };
+
+        Ok(serde_json::to_string(&params)?)
+    }
+
+    fn from_params(params: &str) -> anyhow::Result<Self> {
+        let params = serde_json::from_str::<QuantityParams>(params)?;
+        let quantity = params.quantity.parse::<DecimalU256>()?;
+
+        Ok(asset::Ether::from_wei(U256::from(quantity)))
+    }
+}
+
+impl StoredAsset for asset::Erc20 {
+    const KIND: AssetKind = AssetKind::Erc20;
+
+    fn to_params(&self) -> anyhow::Result<String> {
+        let params = Erc20Params {
+            token_contract: EthereumAddress(self.token_contract).to_string(),
+            quantity: DecimalU256(self.quantity.0).to_string(),
+        };
+
+        Ok(serde_json::to_string(&params)?)
+    }
+
+    fn from_params(params: &str) -> anyhow::Result<Self> {
+        let params = serde_json::from_str::<Erc20Params>(params)?;
+        let token_contract = params.token_contract.parse::<EthereumAddress>()?;
+        let quantity = params.quantity.parse::<DecimalU256>()?;
+
+        Ok(asset::Erc20::new(
+            token_contract.0,
+            asset::Erc20Quantity(quantity.into()),
+        ))
+    }
+}
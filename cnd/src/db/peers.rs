@@ -0,0 +1,164 @@
+use crate::db::{
+    custom_sql_types::Text,
+    schema::{banned_peers, peers},
+    Error, Save, Sqlite,
+};
+use async_trait::async_trait;
+use diesel::{self, prelude::*, RunQueryDsl};
+use libp2p::{Multiaddr, PeerId};
+use std::collections::HashMap;
+
+/// An address under which a peer has been seen.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PeerAddress {
+    pub peer_id: PeerId,
+    pub address: Multiaddr,
+}
+
+/// The addresses of all peers we have seen, kept across restarts.
+#[async_trait]
+pub trait PeerAddressBook: Send + Sync + 'static {
+    async fn peer_addresses(&self) -> anyhow::Result<Vec<(PeerId, Vec<Multiaddr>)>>;
+    /// Forget all addresses of `peer_id`, returning the ones that were removed.
+    async fn delete_peer(&self, peer_id: &PeerId) -> anyhow::Result<Vec<Multiaddr>>;
+}
+
+/// The peers banned at runtime, kept across restarts.
+#[async_trait]
+pub trait BanList: Send + Sync + 'static {
+    async fn banned_peers(&self) -> anyhow::Result<Vec<PeerId>>;
+    async fn ban_peer(&self, peer_id: &PeerId) -> anyhow::Result<()>;
+    /// Returns `false` if the peer was not banned.
+    async fn unban_peer(&self, peer_id: &PeerId) -> anyhow::Result<bool>;
+}
+
+#[derive(Insertable, Debug, Clone)]
+#[table_name = "peers"]
+struct InsertablePeerAddress {
+    peer_id: Text<PeerId>,
+    address: Text<Multiaddr>,
+}
+
+#[derive(Insertable, Debug, Clone)]
+#[table_name = "banned_peers"]
+struct InsertableBannedPeer {
+    peer_id: Text<PeerId>,
+}
+
+#[async_trait]
+impl Save<PeerAddress> for Sqlite {
+    async fn save(&self, peer_address: PeerAddress) -> anyhow::Result<()> {
+        let PeerAddress { peer_id, address } = peer_address;
+
+        let insertable = InsertablePeerAddress {
+            peer_id: Text(peer_id),
+            address: Text(address),
+        };
+
+        // Addresses are seen over and over again, we only keep one row for each.
+        self.do_in_transaction(|connection| {
+            diesel::insert_or_ignore_into(peers::table)
+                .values(&insertable)
+                .execute(connection)
+        })
+        .await?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl PeerAddressBook for Sqlite {
+    async fn peer_addresses(&self) -> anyhow::Result<Vec<(PeerId, Vec<Multiaddr>)>> {
+        let records: Vec<(Text<PeerId>, Text<Multiaddr>)> = self
+            .do_in_transaction(|connection| {
+                peers::table
+                    .order(peers::id.asc())
+                    .select((peers::peer_id, peers::address))
+                    .load(connection)
+            })
+            .await?;
+
+        let mut peers: Vec<(PeerId, Vec<Multiaddr>)> = Vec::new();
+        let mut index = HashMap::new();
+
+        for (Text(peer_id), Text(address)) in records {
+            match index.get(&peer_id) {
+                Some(&i) => peers[i].1.push(address),
+                None => {
+                    index.insert(peer_id.clone(), peers.len());
+                    peers.push((peer_id, vec![address]));
+                }
+            }
+        }
+
+        Ok(peers)
+    }
+
+    async fn delete_peer(&self, peer_id: &PeerId) -> anyhow::Result<Vec<Multiaddr>> {
+        let addresses: Vec<Text<Multiaddr>> = self
+            .do_in_transaction(|connection| {
+                let key = Text(peer_id);
+
+                let addresses = peers::table
+                    .filter(peers::peer_id.eq(&key))
+                    .select(peers::address)
+                    .load(connection)?;
+
+                diesel::delete(peers::table.filter(peers::peer_id.eq(&key))).execute(connection)?;
+
+                Ok::<_, diesel::result::Error>(addresses)
+            })
+            .await?;
+
+        if addresses.is_empty() {
+            return Err(Error::PeerNotFound.into());
+        }
+
+        Ok(addresses.into_iter().map(|Text(address)| address).collect())
+    }
+}
+
+#[async_trait]
+impl BanList for Sqlite {
+    async fn banned_peers(&self) -> anyhow::Result<Vec<PeerId>> {
+        let records: Vec<Text<PeerId>> = self
+            .do_in_transaction(|connection| {
+                banned_peers::table
+                    .order(banned_peers::id.asc())
+                    .select(banned_peers::peer_id)
+                    .load(connection)
+            })
+            .await?;
+
+        Ok(records.into_iter().map(|Text(peer_id)| peer_id).collect())
+    }
+
+    async fn ban_peer(&self, peer_id: &PeerId) -> anyhow::Result<()> {
+        let insertable = InsertableBannedPeer {
+            peer_id: Text(peer_id.clone()),
+        };
+
+        self.do_in_transaction(|connection| {
+            diesel::insert_or_ignore_into(banned_peers::table)
+                .values(&insertable)
+                .execute(connection)
+        })
+        .await?;
+
+        Ok(())
+    }
+
+    async fn unban_peer(&self, peer_id: &PeerId) -> anyhow::Result<bool> {
+        let deleted = self
+            .do_in_transaction(|connection| {
+                let key = Text(peer_id);
+
+                diesel::delete(banned_peers::table.filter(banned_peers::peer_id.eq(&key)))
+                    .execute(connection)
+            })
+            .await?;
+
+        Ok(deleted > 0)
+    }
+}
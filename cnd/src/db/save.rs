@@ -1,13 +1,10 @@
 use crate::{
-    asset,
     db::{
         custom_sql_types::{Text, U32},
-        new_types::{DecimalU256, EthereumAddress, Satoshis},
         schema::{self, *},
-        Sqlite, Swap,
+        AssetKind, LedgerKind, Sqlite, StoredAsset, StoredLedger, Swap,
     },
     swap_protocols::{
-        ledger::{Bitcoin, Ethereum},
         rfc003::{Accept, Decline, Request, SecretHash},
         HashFunction, Role, SwapId,
     },
@@ -57,27 +54,34 @@ impl From<Swap> for InsertableSwap {
 }
 
 #[derive(Insertable, Debug, Clone)]
-#[table_name = "rfc003_bitcoin_ethereum_bitcoin_ether_request_messages"]
-struct InsertableBitcoinEthereumBitcoinEtherRequestMessage {
+#[table_name = "rfc003_requests"]
+struct InsertableRequest {
     swap_id: Text<SwapId>,
-    bitcoin_network: Text<bitcoin::Network>,
-    ethereum_chain_id: U32,
-    bitcoin_amount: Text<Satoshis>,
-    ether_amount: Text<DecimalU256>,
+    alpha_ledger_kind: Text<LedgerKind>,
+    alpha_ledger: String,
+    beta_ledger_kind: Text<LedgerKind>,
+    beta_ledger: String,
+    alpha_asset_kind: Text<AssetKind>,
+    alpha_asset: String,
+    beta_asset_kind: Text<AssetKind>,
+    beta_asset: String,
     hash_function: Text<HashFunction>,
-    bitcoin_refund_identity: Text<bitcoin::PublicKey>,
-    ethereum_redeem_identity: Text<EthereumAddress>,
-    bitcoin_expiry: U32,
-    ethereum_expiry: U32,
+    alpha_ledger_refund_identity: String,
+    beta_ledger_redeem_identity: String,
+    alpha_expiry: U32,
+    beta_expiry: U32,
     secret_hash: Text<SecretHash>,
 }
 
 #[async_trait]
-impl Save<Request<Bitcoin, Ethereum, asset::Bitcoin, asset::Ether>> for Sqlite {
-    async fn save(
-        &self,
-        message: Request<Bitcoin, Ethereum, asset::Bitcoin, asset::Ether>,
-    ) -> anyhow::Result<()> {
+impl<AL, BL, AA, BA> Save<Request<AL, BL, AA, BA>> for Sqlite
+where
+    AL: StoredLedger,
+    BL: StoredLedger,
+    AA: StoredAsset,
+    BA: StoredAsset,
+{
+    async fn save(&self, message: Request<AL, BL, AA, BA>) -> anyhow::Result<()> {
         let Request {
             swap_id,
             alpha_ledger,
@@ -92,22 +96,26 @@ impl Save<Request<Bitcoin, Ethereum, asset::Bitcoin, asset::Ether>> for Sqlite {
             secret_hash,
         } = message;
 
-        let insertable = InsertableBitcoinEthereumBitcoinEtherRequestMessage {
+        let insertable = InsertableRequest {
             swap_id: Text(swap_id),
-            bitcoin_network: Text(alpha_ledger.network),
-            ethereum_chain_id: U32(beta_ledger.chain_id.into()),
-            bitcoin_amount: Text(Satoshis(alpha_asset.as_sat())),
-            ether_amount: Text(DecimalU256(beta_asset.wei())),
+            alpha_ledger_kind: Text(AL::KIND),
+            alpha_ledger: alpha_ledger.to_params()?,
+            beta_ledger_kind: Text(BL::KIND),
+            beta_ledger: beta_ledger.to_params()?,
+            alpha_asset_kind: Text(AA::KIND),
+            alpha_asset: alpha_asset.to_params()?,
+            beta_asset_kind: Text(BA::KIND),
+            beta_asset: beta_asset.to_params()?,
             hash_function: Text(hash_function),
-            bitcoin_refund_identity: Text(alpha_ledger_refund_identity.into_inner()),
-            ethereum_redeem_identity: Text(EthereumAddress(beta_ledger_redeem_identity)),
-            bitcoin_expiry: U32(alpha_expiry.into()),
-            ethereum_expiry: U32(beta_expiry.into()),
+            alpha_ledger_refund_identity: AL::identity_to_text(alpha_ledger_refund_identity),
+            beta_ledger_redeem_identity: BL::identity_to_text(beta_ledger_redeem_identity),
+            alpha_expiry: U32(alpha_expiry.into()),
+            beta_expiry: U32(beta_expiry.into()),
             secret_hash: Text(secret_hash),
         };
 
         self.do_in_transaction(|connection| {
-            diesel::insert_into(rfc003_bitcoin_ethereum_bitcoin_ether_request_messages::table)
+            diesel::insert_into(rfc003_requests::table)
                 .values(&insertable)
                 .execute(connection)
         })
@@ -117,217 +125,35 @@ impl Save<Request<Bitcoin, Ethereum, asset::Bitcoin, asset::Ether>> for Sqlite {
     }
 }
 
-#[derive(Insertable, Debug, Copy, Clone)]
-#[table_name = "rfc003_bitcoin_ethereum_bitcoin_erc20_request_messages"]
-struct InsertableBitcoinEthereumBitcoinErc20RequestMessage {
-    swap_id: Text<SwapId>,
-    bitcoin_network: Text<bitcoin::Network>,
-    ethereum_chain_id: U32,
-    bitcoin_amount: Text<Satoshis>,
-    erc20_amount: Text<DecimalU256>,
-    erc20_token_contract: Text<EthereumAddress>,
-    hash_function: Text<HashFunction>,
-    bitcoin_refund_identity: Text<bitcoin::PublicKey>,
-    ethereum_redeem_identity: Text<EthereumAddress>,
-    bitcoin_expiry: U32,
-    ethereum_expiry: U32,
-    secret_hash: Text<SecretHash>,
-}
-
-#[async_trait]
-impl Save<Request<Bitcoin, Ethereum, asset::Bitcoin, asset::Erc20>> for Sqlite {
-    async fn save(
-        &self,
-        message: Request<Bitcoin, Ethereum, asset::Bitcoin, asset::Erc20>,
-    ) -> anyhow::Result<()> {
-        let Request {
-            swap_id,
-            alpha_ledger,
-            alpha_asset,
-            beta_ledger,
-            beta_asset,
-            hash_function,
-            alpha_ledger_refund_identity,
-            beta_ledger_redeem_identity,
-            alpha_expiry,
-            beta_expiry,
-            secret_hash,
-        } = message;
-
-        let insertable = InsertableBitcoinEthereumBitcoinErc20RequestMessage {
-            swap_id: Text(swap_id),
-            bitcoin_network: Text(alpha_ledger.network),
-            ethereum_chain_id: U32(beta_ledger.chain_id.into()),
-            bitcoin_amount: Text(Satoshis(alpha_asset.as_sat())),
-            erc20_amount: Text(DecimalU256(beta_asset.quantity.0)),
-            erc20_token_contract: Text(EthereumAddress(beta_asset.token_contract)),
-            hash_function: Text(hash_function),
-            bitcoin_refund_identity: Text(alpha_ledger_refund_identity.into_inner()),
-            ethereum_redeem_identity: Text(EthereumAddress(beta_ledger_redeem_identity)),
-            bitcoin_expiry: U32(alpha_expiry.into()),
-            ethereum_expiry: U32(beta_expiry.into()),
-            secret_hash: Text(secret_hash),
-        };
-
-        self.do_in_transaction(|connection| {
-            diesel::insert_into(rfc003_bitcoin_ethereum_bitcoin_erc20_request_messages::table)
-                .values(&insertable)
-                .execute(connection)
-        })
-        .await?;
-
-        Ok(())
-    }
-}
-
-#[derive(Insertable, Debug, Copy, Clone)]
-#[table_name = "rfc003_ethereum_bitcoin_ether_bitcoin_request_messages"]
-struct InsertableEthereumBitcoinEtherBitcoinRequestMessage {
-    swap_id: Text<SwapId>,
-    ethereum_chain_id: U32,
-    bitcoin_network: Text<bitcoin::Network>,
-    ether_amount: Text<DecimalU256>,
-    bitcoin_amount: Text<Satoshis>,
-    hash_function: Text<HashFunction>,
-    ethereum_refund_identity: Text<EthereumAddress>,
-    bitcoin_redeem_identity: Text<bitcoin::PublicKey>,
-    ethereum_expiry: U32,
-    bitcoin_expiry: U32,
-    secret_hash: Text<SecretHash>,
-}
-
-#[async_trait]
-impl Save<Request<Ethereum, Bitcoin, asset::Ether, asset::Bitcoin>> for Sqlite {
-    async fn save(
-        &self,
-        message: Request<Ethereum, Bitcoin, asset::Ether, asset::Bitcoin>,
-    ) -> anyhow::Result<()> {
-        let Request {
-            swap_id,
-            alpha_ledger,
-            alpha_asset,
-            beta_ledger,
-            beta_asset,
-            hash_function,
-            alpha_ledger_refund_identity,
-            beta_ledger_redeem_identity,
-            alpha_expiry,
-            beta_expiry,
-            secret_hash,
-        } = message;
-
-        let insertable = InsertableEthereumBitcoinEtherBitcoinRequestMessage {
-            swap_id: Text(swap_id),
-            ethereum_chain_id: U32(alpha_ledger.chain_id.into()),
-            bitcoin_network: Text(beta_ledger.network),
-            ether_amount: Text(DecimalU256(alpha_asset.wei())),
-            bitcoin_amount: Text(Satoshis(beta_asset.as_sat())),
-            hash_function: Text(hash_function),
-            ethereum_refund_identity: Text(EthereumAddress(alpha_ledger_refund_identity)),
-            bitcoin_redeem_identity: Text(beta_ledger_redeem_identity.into_inner()),
-            ethereum_expiry: U32(alpha_expiry.into()),
-            bitcoin_expiry: U32(beta_expiry.into()),
-            secret_hash: Text(secret_hash),
-        };
-
-        self.do_in_transaction(|connection| {
-            diesel::insert_into(rfc003_ethereum_bitcoin_ether_bitcoin_request_messages::table)
-                .values(&insertable)
-                .execute(connection)
-        })
-        .await?;
-
-        Ok(())
-    }
-}
-#[derive(Insertable, Debug, Copy, Clone)]
-#[table_name = "rfc003_ethereum_bitcoin_erc20_bitcoin_request_messages"]
-struct InsertableEthereumBitcoinErc20BitcoinRequestMessage {
-    swap_id: Text<SwapId>,
-    ethereum_chain_id: U32,
-    bitcoin_network: Text<bitcoin::Network>,
-    erc20_amount: Text<DecimalU256>,
-    erc20_token_contract: Text<EthereumAddress>,
-    bitcoin_amount: Text<Satoshis>,
-    hash_function: Text<HashFunction>,
-    ethereum_refund_identity: Text<EthereumAddress>,
-    bitcoin_redeem_identity: Text<bitcoin::PublicKey>,
-    ethereum_expiry: U32,
-    bitcoin_expiry: U32,
-    secret_hash: Text<SecretHash>,
-}
-
-#[async_trait]
-impl Save<Request<Ethereum, Bitcoin, asset::Erc20, asset::Bitcoin>> for Sqlite {
-    async fn save(
-        &self,
-        message: Request<Ethereum, Bitcoin, asset::Erc20, asset::Bitcoin>,
-    ) -> anyhow::Result<()> {
-        let Request {
-            swap_id,
-            alpha_ledger,
-            alpha_asset,
-            beta_ledger,
-            beta_asset,
-            hash_function,
-            alpha_ledger_refund_identity,
-            beta_ledger_redeem_identity,
-            alpha_expiry,
-            beta_expiry,
-            secret_hash,
-        } = message;
-
-        let insertable = InsertableEthereumBitcoinErc20BitcoinRequestMessage {
-            swap_id: Text(swap_id),
-            ethereum_chain_id: U32(alpha_ledger.chain_id.into()),
-            bitcoin_network: Text(beta_ledger.network),
-            erc20_amount: Text(DecimalU256(alpha_asset.quantity.0)),
-            erc20_token_contract: Text(EthereumAddress(alpha_asset.token_contract)),
-            bitcoin_amount: Text(Satoshis(beta_asset.as_sat())),
-            hash_function: Text(hash_function),
-            ethereum_refund_identity: Text(EthereumAddress(alpha_ledger_refund_identity)),
-            bitcoin_redeem_identity: Text(beta_ledger_redeem_identity.into_inner()),
-            ethereum_expiry: U32(alpha_expiry.into()),
-            bitcoin_expiry: U32(beta_expiry.into()),
-            secret_hash: Text(secret_hash),
-        };
-
-        self.do_in_transaction(|connection| {
-            diesel::insert_into(rfc003_ethereum_bitcoin_erc20_bitcoin_request_messages::table)
-                .values(&insertable)
-                .execute(connection)
-        })
-        .await?;
-
-        Ok(())
-    }
-}
-
-#[derive(Insertable, Debug, Copy, Clone)]
-#[table_name = "rfc003_ethereum_bitcoin_accept_messages"]
-struct InsertableEthereumBitcoinAcceptMessage {
+#[derive(Insertable, Debug, Clone)]
+#[table_name = "rfc003_accepts"]
+struct InsertableAccept {
     swap_id: Text<SwapId>,
-    ethereum_redeem_identity: Text<EthereumAddress>,
-    bitcoin_refund_identity: Text<bitcoin::PublicKey>,
+    alpha_ledger_redeem_identity: String,
+    beta_ledger_refund_identity: String,
 }
 
 #[async_trait]
-impl Save<Accept<Ethereum, Bitcoin>> for Sqlite {
-    async fn save(&self, message: Accept<Ethereum, Bitcoin>) -> anyhow::Result<()> {
+impl<AL, BL> Save<Accept<AL, BL>> for Sqlite
+where
+    AL: StoredLedger,
+    BL: StoredLedger,
+{
+    async fn save(&self, message: Accept<AL, BL>) -> anyhow::Result<()> {
         let Accept {
             swap_id,
             alpha_ledger_redeem_identity,
             beta_ledger_refund_identity,
         } = message;
 
-        let insertable = InsertableEthereumBitcoinAcceptMessage {
+        let insertable = InsertableAccept {
             swap_id: Text(swap_id),
-            ethereum_redeem_identity: Text(EthereumAddress(alpha_ledger_redeem_identity)),
-            bitcoin_refund_identity: Text(beta_ledger_refund_identity.into_inner()),
+            alpha_ledger_redeem_identity: AL::identity_to_text(alpha_ledger_redeem_identity),
+            beta_ledger_refund_identity: BL::identity_to_text(beta_ledger_refund_identity),
         };
 
         self.do_in_transaction(|connection| {
-            diesel::insert_into(rfc003_ethereum_bitcoin_accept_messages::table)
+            diesel::insert_into(rfc003_accepts::table)
                 .values(&insertable)
                 .execute(&*connection)
         })
@@ -336,39 +162,7 @@ impl Save<Accept<Ethereum, Bitcoin>> for Sqlite {
         Ok(())
     }
 }
-#[derive(Insertable, Debug, Copy, Clone)]
-#[table_name = "rfc003_bitcoin_ethereum_accept_messages"]
-struct InsertableBitcoinEthereumAcceptMessage {
-    swap_id: Text<SwapId>,
-    bitcoin_redeem_identity: Text<bitcoin::PublicKey>,
-    ethereum_refund_identity: Text<EthereumAddress>,
-}
-
-#[async_trait]
-impl Save<Accept<Bitcoin, Ethereum>> for Sqlite {
-    async fn save(&self, message: Accept<Bitcoin, Ethereum>) -> anyhow::Result<()> {
-        let Accept {
-            swap_id,
-            alpha_ledger_redeem_identity,
-            beta_ledger_refund_identity,
-        } = message;
-
-        let insertable = InsertableBitcoinEthereumAcceptMessage {
-            swap_id: Text(swap_id),
-            bitcoin_redeem_identity: Text(alpha_ledger_redeem_identity.into_inner()),
-            ethereum_refund_identity: Text(EthereumAddress(beta_ledger_refund_identity)),
-        };
-
-        self.do_in_transaction(|connection| {
-            diesel::insert_into(rfc003_bitcoin_ethereum_accept_messages::table)
-                .values(&insertable)
-                .execute(&*connection)
-        })
-        .await?;
 
-        Ok(())
-    }
-}
 #[derive(Insertable, Debug, Clone)]
 #[table_name = "rfc003_decline_messages"]
 struct InsertableDeclineMessage {
@@ -379,15 +173,11 @@ struct InsertableDeclineMessage {
 #[async_trait]
 impl Save<Decline> for Sqlite {
     async fn save(&self, message: Decline) -> anyhow::Result<()> {
-        let Decline {
-            swap_id,
-            reason: _reason, /* we don't map reason to a DB type because will be gone soon
-                              * (hopefully) */
-        } = message;
+        let Decline { swap_id, reason } = message;
 
         let insertable = InsertableDeclineMessage {
             swap_id: Text(swap_id),
-            reason: None,
+            reason: reason.map(|reason| reason.to_string()),
         };
 
         self.do_in_transaction(|connection| {
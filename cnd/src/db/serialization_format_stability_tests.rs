@@ -3,8 +3,16 @@
 /// activity that involves migration scripts to migrate old data. These tests
 /// make sure we don't change the format accidentally!
 use crate::{
-    db::new_types::{DecimalU256, EthereumAddress, Satoshis},
-    swap_protocols::{rfc003::SecretHash, HashFunction, SwapId},
+    asset,
+    db::{
+        new_types::{DecimalU256, EthereumAddress, Satoshis},
+        AssetKind, LedgerKind, StoredAsset, StoredLedger,
+    },
+    swap_protocols::{
+        ledger::{Bitcoin, Ethereum},
+        rfc003::SecretHash,
+        HashFunction, SwapId,
+    },
 };
 use std::{fmt, str::FromStr};
 
@@ -55,6 +63,34 @@ fn secrethash() {
     );
 }
 
+#[test]
+fn ledger_kind() {
+    roundtrip_test::<LedgerKind>("Bitcoin");
+    roundtrip_test::<LedgerKind>("Ethereum");
+}
+
+#[test]
+fn asset_kind() {
+    roundtrip_test::<AssetKind>("Bitcoin");
+    roundtrip_test::<AssetKind>("Ether");
+    roundtrip_test::<AssetKind>("Erc20");
+}
+
+#[test]
+fn ledger_params() {
+    ledger_params_roundtrip_test::<Bitcoin>(r#"{"network":"regtest"}"#);
+    ledger_params_roundtrip_test::<Ethereum>(r#"{"chain_id":17}"#);
+}
+
+#[test]
+fn asset_params() {
+    asset_params_roundtrip_test::<asset::Bitcoin>(r#"{"quantity":"100000000000"}"#);
+    asset_params_roundtrip_test::<asset::Ether>(r#"{"quantity":"1000000000000000"}"#);
+    asset_params_roundtrip_test::<asset::Erc20>(
+        r#"{"token_contract":"68917b35bacf71dbadf37628b3b7f290f6d88877","quantity":"1000000000000000"}"#,
+    );
+}
+
 /// Given a string representation of a value T, this function will assert
 /// that T can be constructed through the `FromStr` trait and its implementation
 /// is symmetric to the `Display` implementation.
@@ -74,6 +110,24 @@ where
     assert_eq!(written, stored_value)
 }
 
+/// Same as `roundtrip_test` but for the JSON parameters of a ledger.
+fn ledger_params_roundtrip_test<L: StoredLedger>(stored_value: &str) {
+    let read = L::from_params(stored_value).unwrap();
+
+    let written = read.to_params().unwrap();
+
+    assert_eq!(written, stored_value)
+}
+
+/// Same as `roundtrip_test` but for the JSON parameters of an asset.
+fn asset_params_roundtrip_test<A: StoredAsset>(stored_value: &str) {
+    let read = A::from_params(stored_value).unwrap();
+
+    let written = read.to_params().unwrap();
+
+    assert_eq!(written, stored_value)
+}
+
 fn assert_num_variants<E>(expected_number_of_variants: usize)
 where
     E: strum::IntoEnumIterator,
@@ -0,0 +1,97 @@
+use crate::{
+    asset::Asset,
+    db::{custom_sql_types::Text, schema::rfc003_swap_events, Save, Sqlite},
+    swap_protocols::{
+        rfc003::{create_swap::SwapEvent, Ledger},
+        SwapId,
+    },
+};
+use async_trait::async_trait;
+use diesel::{self, prelude::*, RunQueryDsl};
+use serde::{de::DeserializeOwned, Serialize};
+
+/// A `SwapEvent` together with the id of the swap it belongs to.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SwapEventRecord<AL: Ledger, BL: Ledger, AA: Asset, BA: Asset> {
+    pub swap_id: SwapId,
+    pub event: SwapEvent<AL, BL, AA, BA>,
+}
+
+/// Load all events that happened on the ledgers of a swap, in the order they
+/// were saved.
+#[async_trait]
+pub trait LoadSwapEvents<AL: Ledger, BL: Ledger, AA: Asset, BA: Asset> {
+    async fn load_swap_events(
+        &self,
+        swap_id: &SwapId,
+    ) -> anyhow::Result<Vec<SwapEvent<AL, BL, AA, BA>>>;
+}
+
+#[derive(Insertable, Debug, Clone)]
+#[table_name = "rfc003_swap_events"]
+struct InsertableSwapEvent {
+    swap_id: Text<SwapId>,
+    event: String,
+}
+
+#[async_trait]
+impl<AL, BL, AA, BA> Save<SwapEventRecord<AL, BL, AA, BA>> for Sqlite
+where
+    AL: Ledger,
+    BL: Ledger,
+    AA: Asset + Serialize,
+    BA: Asset + Serialize,
+{
+    async fn save(&self, record: SwapEventRecord<AL, BL, AA, BA>) -> anyhow::Result<()> {
+        let SwapEventRecord { swap_id, event } = record;
+
+        // The event is stored as a JSON document because the transactions and HTLC
+        // locations it contains differ for every ledger.
+        let insertable = InsertableSwapEvent {
+            swap_id: Text(swap_id),
+            event: serde_json::to_string(&event)?,
+        };
+
+        self.do_in_transaction(|connection| {
+            diesel::insert_into(rfc003_swap_events::table)
+                .values(&insertable)
+                .execute(connection)
+        })
+        .await?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<AL, BL, AA, BA> LoadSwapEvents<AL, BL, AA, BA> for Sqlite
+where
+    AL: Ledger,
+    BL: Ledger,
+    AA: Asset + DeserializeOwned,
+    BA: Asset + DeserializeOwned,
+{
+    async fn load_swap_events(
+        &self,
+        key: &SwapId,
+    ) -> anyhow::Result<Vec<SwapEvent<AL, BL, AA, BA>>> {
+        let records: Vec<String> = self
+            .do_in_transaction(|connection| {
+                let key = Text(key);
+
+                rfc003_swap_events::table
+                    .filter(rfc003_swap_events::swap_id.eq(key))
+                    .order(rfc003_swap_events::id.asc())
+                    .select(rfc003_swap_events::event)
+                    .load(connection)
+            })
+            .await?;
+
+        let events = records
+            .iter()
+            .map(|record| serde_json::from_str(record))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(events)
+    }
+}
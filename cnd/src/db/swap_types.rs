@@ -2,9 +2,10 @@ use crate::{
     asset,
     db::{custom_sql_types::Text, schema, Sqlite},
     diesel::{ExpressionMethods, OptionalExtension, QueryDsl, RunQueryDsl},
-    swap_protocols::{ledger, Role, SwapId},
+    swap_protocols::{ledger, rfc003::messages::SwapDeclineReason, Role, SwapId},
 };
 use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
 use strum_macros::{Display, EnumString};
 
 /// Determine swap types for swaps currently stored in the database.
@@ -19,61 +20,64 @@ pub trait DetermineTypes: Send + Sync + 'static {
 #[async_trait]
 impl DetermineTypes for Sqlite {
     async fn determine_types(&self, key: &SwapId) -> anyhow::Result<SwapTypes> {
+        use schema::rfc003_requests as requests;
+
         let role = self.role(key).await?;
 
-        if self
-            .rfc003_bitcoin_ethereum_bitcoin_ether_request_messages_has_swap(key)
-            .await?
-        {
-            return Ok(SwapTypes {
-                alpha_ledger: LedgerKind::Bitcoin,
-                beta_ledger: LedgerKind::Ethereum,
-                alpha_asset: AssetKind::Bitcoin,
-                beta_asset: AssetKind::Ether,
-                role,
-            });
-        }
+        let record: QueryableSwapTypes = self
+            .do_in_transaction(|connection| {
+                let key = Text(key);
+
+                requests::table
+                    .filter(requests::swap_id.eq(key))
+                    .select((
+                        requests::alpha_ledger_kind,
+                        requests::beta_ledger_kind,
+                        requests::alpha_asset_kind,
+                        requests::beta_asset_kind,
+                    ))
+                    .first(connection)
+            })
+            .await?;
+
+        Ok(SwapTypes {
+            alpha_ledger: *record.alpha_ledger_kind,
+            beta_ledger: *record.beta_ledger_kind,
+            alpha_asset: *record.alpha_asset_kind,
+            beta_asset: *record.beta_asset_kind,
+            role,
+        })
+    }
+}
 
-        if self
-            .rfc003_ethereum_bitcoin_ether_bitcoin_request_messages_has_swap(key)
-            .await?
-        {
-            return Ok(SwapTypes {
-                alpha_ledger: LedgerKind::Ethereum,
-                beta_ledger: LedgerKind::Bitcoin,
-                alpha_asset: AssetKind::Ether,
-                beta_asset: AssetKind::Bitcoin,
-                role,
-            });
+/// Determine how far the communication of a swap stored in the database got.
+#[async_trait]
+pub trait DetermineCommunicationState: Send + Sync + 'static {
+    async fn determine_communication_state(
+        &self,
+        key: &SwapId,
+    ) -> anyhow::Result<CommunicationState>;
+}
+
+#[async_trait]
+impl DetermineCommunicationState for Sqlite {
+    async fn determine_communication_state(
+        &self,
+        key: &SwapId,
+    ) -> anyhow::Result<CommunicationState> {
+        if self.rfc003_proposal_expired(key).await? {
+            return Ok(CommunicationState::TimedOut);
         }
 
-        if self
-            .rfc003_bitcoin_ethereum_bitcoin_erc20_request_messages_has_swap(key)
-            .await?
-        {
-            return Ok(SwapTypes {
-                alpha_ledger: LedgerKind::Bitcoin,
-                beta_ledger: LedgerKind::Ethereum,
-                alpha_asset: AssetKind::Bitcoin,
-                beta_asset: AssetKind::Erc20,
-                role,
-            });
+        if self.rfc003_decline_messages_has_swap(key).await? {
+            return Ok(CommunicationState::Declined);
         }
 
-        if self
-            .rfc003_ethereum_bitcoin_erc20_bitcoin_request_messages_has_swap(key)
-            .await?
-        {
-            return Ok(SwapTypes {
-                alpha_ledger: LedgerKind::Ethereum,
-                beta_ledger: LedgerKind::Bitcoin,
-                alpha_asset: AssetKind::Erc20,
-                beta_asset: AssetKind::Bitcoin,
-                role,
-            });
+        if self.rfc003_accepts_has_swap(key).await? {
+            return Ok(CommunicationState::Accepted);
         }
 
-        unreachable!("we got role for swap so the swap_id must exist")
+        Ok(CommunicationState::Proposed)
     }
 }
 
@@ -100,10 +104,26 @@ macro_rules! impl_has_swap {
 }
 
 impl Sqlite {
-    impl_has_swap!(rfc003_bitcoin_ethereum_bitcoin_ether_request_messages);
-    impl_has_swap!(rfc003_ethereum_bitcoin_ether_bitcoin_request_messages);
-    impl_has_swap!(rfc003_bitcoin_ethereum_bitcoin_erc20_request_messages);
-    impl_has_swap!(rfc003_ethereum_bitcoin_erc20_bitcoin_request_messages);
+    impl_has_swap!(rfc003_accepts);
+    impl_has_swap!(rfc003_decline_messages);
+
+    async fn rfc003_proposal_expired(&self, key: &SwapId) -> anyhow::Result<bool> {
+        use schema::rfc003_decline_messages as declines;
+
+        let record: Option<QueryableSwap> = self
+            .do_in_transaction(|connection| {
+                let key = Text(key);
+                declines::table
+                    .filter(declines::swap_id.eq(key))
+                    .filter(declines::reason.eq(SwapDeclineReason::ProposalExpired.to_string()))
+                    .select((declines::swap_id,))
+                    .first(&*connection)
+                    .optional()
+            })
+            .await?;
+
+        Ok(record.is_some())
+    }
 }
 
 #[derive(Queryable, Debug, Clone, PartialEq)]
@@ -111,6 +131,14 @@ struct QueryableSwap {
     swap_id: Text<SwapId>,
 }
 
+#[derive(Queryable, Debug, Clone, PartialEq)]
+struct QueryableSwapTypes {
+    alpha_ledger_kind: Text<LedgerKind>,
+    beta_ledger_kind: Text<LedgerKind>,
+    alpha_asset_kind: Text<AssetKind>,
+    beta_asset_kind: Text<AssetKind>,
+}
+
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub struct SwapTypes {
     pub alpha_ledger: LedgerKind,
@@ -120,7 +148,17 @@ pub struct SwapTypes {
     pub role: Role,
 }
 
-#[derive(Debug, Clone, Copy, Display, EnumString, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CommunicationState {
+    Proposed,
+    Accepted,
+    Declined,
+    /// The proposal was not answered within its validity window.
+    TimedOut,
+}
+
+#[derive(Debug, Clone, Copy, Display, EnumString, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum LedgerKind {
     Bitcoin,
     Ethereum,
@@ -135,7 +173,8 @@ impl From<ledger::LedgerKind> for LedgerKind {
     }
 }
 
-#[derive(Clone, Copy, Debug, Display, EnumString, PartialEq)]
+#[derive(Clone, Copy, Debug, Display, EnumString, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum AssetKind {
     Bitcoin,
     Ether,
@@ -113,6 +113,66 @@ macro_rules! with_swap_types {
 
                 _match_role!(role, $fn)
             }
+            SwapTypes {
+                alpha_ledger: LedgerKind::Ethereum,
+                beta_ledger: LedgerKind::Ethereum,
+                alpha_asset: AssetKind::Ether,
+                beta_asset: AssetKind::Erc20,
+                ..
+            } => {
+                #[allow(dead_code)]
+                type AL = Ethereum;
+                #[allow(dead_code)]
+                type BL = Ethereum;
+                #[allow(dead_code)]
+                type AA = asset::Ether;
+                #[allow(dead_code)]
+                type BA = asset::Erc20;
+                #[allow(dead_code)]
+                type AcceptBody = crate::http_api::routes::rfc003::accept::RedeemAndRefund<AL, BL>;
+
+                _match_role!(role, $fn)
+            }
+            SwapTypes {
+                alpha_ledger: LedgerKind::Ethereum,
+                beta_ledger: LedgerKind::Ethereum,
+                alpha_asset: AssetKind::Erc20,
+                beta_asset: AssetKind::Ether,
+                ..
+            } => {
+                #[allow(dead_code)]
+                type AL = Ethereum;
+                #[allow(dead_code)]
+                type BL = Ethereum;
+                #[allow(dead_code)]
+                type AA = asset::Erc20;
+                #[allow(dead_code)]
+                type BA = asset::Ether;
+                #[allow(dead_code)]
+                type AcceptBody = crate::http_api::routes::rfc003::accept::RedeemAndRefund<AL, BL>;
+
+                _match_role!(role, $fn)
+            }
+            SwapTypes {
+                alpha_ledger: LedgerKind::Ethereum,
+                beta_ledger: LedgerKind::Ethereum,
+                alpha_asset: AssetKind::Erc20,
+                beta_asset: AssetKind::Erc20,
+                ..
+            } => {
+                #[allow(dead_code)]
+                type AL = Ethereum;
+                #[allow(dead_code)]
+                type BL = Ethereum;
+                #[allow(dead_code)]
+                type AA = asset::Erc20;
+                #[allow(dead_code)]
+                type BA = asset::Erc20;
+                #[allow(dead_code)]
+                type AcceptBody = crate::http_api::routes::rfc003::accept::RedeemAndRefund<AL, BL>;
+
+                _match_role!(role, $fn)
+            }
             _ => unimplemented!(),
         }
     }};
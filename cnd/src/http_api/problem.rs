@@ -3,6 +3,7 @@ use crate::{
     http_api::routes::rfc003::handlers::{
         post_swap::UnsupportedSwap, InvalidAction, InvalidActionInvocation,
     },
+    network::RequestError,
 };
 use http_api_problem::HttpApiProblem;
 use warp::{
@@ -41,6 +42,10 @@ pub fn from_anyhow(e: anyhow::Error) -> HttpApiProblem {
         return HttpApiProblem::new("Swap not found.").set_status(StatusCode::NOT_FOUND);
     }
 
+    if let Some(db::Error::PeerNotFound) = e.downcast_ref::<db::Error>() {
+        return HttpApiProblem::new("Peer not found.").set_status(StatusCode::NOT_FOUND);
+    }
+
     if let Some(e) = e.downcast_ref::<UnexpectedQueryParameters>() {
         log::error!("{}", e);
 
@@ -69,6 +74,23 @@ pub fn from_anyhow(e: anyhow::Error) -> HttpApiProblem {
         return problem;
     }
 
+    if let Some(e) = e.downcast_ref::<RequestError>() {
+        log::warn!("{}", e);
+
+        let (title, status) = match e {
+            RequestError::Connecting => ("Peer unreachable.", StatusCode::BAD_GATEWAY),
+            RequestError::Connection => ("Connection to peer closed.", StatusCode::BAD_GATEWAY),
+            RequestError::Timeout => ("Peer did not respond.", StatusCode::GATEWAY_TIMEOUT),
+            RequestError::InvalidResponse | RequestError::InternalError => {
+                ("Invalid response from peer.", StatusCode::BAD_GATEWAY)
+            }
+        };
+
+        return HttpApiProblem::new(title)
+            .set_status(status)
+            .set_detail(e.to_string());
+    }
+
     if e.is::<serde_json::Error>() {
         log::error!("deserialization error: {}", e);
 
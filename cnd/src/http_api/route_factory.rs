@@ -28,7 +28,7 @@ pub fn create(
     let dependencies = warp::any().map(move || dependencies.clone());
 
     let cors = warp::cors()
-        .allow_methods(vec!["GET", "POST"])
+        .allow_methods(vec!["GET", "POST", "DELETE"])
         .allow_header("content-type");
     let cors = match allowed_origins {
         AllowedOrigins::None => cors.allow_origins(Vec::<&str>::new()),
@@ -78,6 +78,40 @@ pub fn create(
         .and(dependencies.clone())
         .and_then(http_api::routes::peers::get_peers);
 
+    let post_peer = warp::post()
+        .and(warp::path("peers"))
+        .and(warp::path::end())
+        .and(dependencies.clone())
+        .and(warp::body::json())
+        .and_then(http_api::routes::peers::post_peer);
+
+    let delete_peer = warp::delete()
+        .and(warp::path("peers"))
+        .and(warp::path::param())
+        .and(warp::path::end())
+        .and(dependencies.clone())
+        .and_then(http_api::routes::peers::delete_peer);
+
+    let get_bans = warp::get()
+        .and(warp::path("bans"))
+        .and(warp::path::end())
+        .and(dependencies.clone())
+        .and_then(http_api::routes::bans::get_bans);
+
+    let post_ban = warp::post()
+        .and(warp::path("bans"))
+        .and(warp::path::end())
+        .and(dependencies.clone())
+        .and(warp::body::json())
+        .and_then(http_api::routes::bans::post_ban);
+
+    let delete_ban = warp::delete()
+        .and(warp::path("bans"))
+        .and(warp::path::param())
+        .and(warp::path::end())
+        .and(dependencies.clone())
+        .and_then(http_api::routes::bans::delete_ban);
+
     let get_info_siren = warp::get()
         .and(warp::path::end())
         .and(warp::header::exact("accept", "application/vnd.siren+json"))
@@ -97,6 +131,11 @@ pub fn create(
         .or(rfc003_action)
         .or(get_swaps)
         .or(get_peers)
+        .or(post_peer)
+        .or(delete_peer)
+        .or(get_bans)
+        .or(post_ban)
+        .or(delete_ban)
         .or(get_info_siren)
         .or(get_info)
         .recover(http_api::unpack_problem)
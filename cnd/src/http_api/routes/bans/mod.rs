@@ -0,0 +1,67 @@
+use crate::{
+    http_api::{problem, routes::into_rejection, Http},
+    network::BanPeers,
+    swap_protocols::Facade,
+};
+use http_api_problem::HttpApiProblem;
+use libp2p::PeerId;
+use serde::{Deserialize, Serialize};
+use warp::{http::StatusCode, Rejection, Reply};
+
+#[derive(Serialize, Debug)]
+pub struct BansResource {
+    peers: Vec<Http<PeerId>>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct BanBody {
+    peer_id: Http<PeerId>,
+}
+
+#[allow(clippy::needless_pass_by_value)]
+pub async fn get_bans(dependencies: Facade) -> Result<impl Reply, Rejection> {
+    let peers = dependencies
+        .banned_peers()
+        .await
+        .into_iter()
+        .map(Http)
+        .collect();
+
+    Ok(warp::reply::json(&BansResource { peers }))
+}
+
+#[allow(clippy::needless_pass_by_value)]
+pub async fn post_ban(
+    dependencies: Facade,
+    body: serde_json::Value,
+) -> Result<impl Reply, Rejection> {
+    let BanBody { peer_id } = serde_json::from_value(body)
+        .map_err(anyhow::Error::from)
+        .map_err(problem::from_anyhow)
+        .map_err(into_rejection)?;
+
+    dependencies
+        .ban_peer(peer_id.0)
+        .await
+        .map_err(problem::from_anyhow)
+        .map_err(into_rejection)?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[allow(clippy::needless_pass_by_value)]
+pub async fn delete_ban(peer_id: PeerId, dependencies: Facade) -> Result<impl Reply, Rejection> {
+    let unbanned = dependencies
+        .unban_peer(&peer_id)
+        .await
+        .map_err(problem::from_anyhow)
+        .map_err(into_rejection)?;
+
+    if unbanned {
+        Ok(StatusCode::NO_CONTENT)
+    } else {
+        Err(into_rejection(
+            HttpApiProblem::new("Peer is not banned.").set_status(StatusCode::NOT_FOUND),
+        ))
+    }
+}
@@ -10,7 +10,13 @@ pub async fn handle_get_swaps(dependencies: Facade) -> anyhow::Result<siren::Ent
     for swap in Retrieve::all(&dependencies).await?.into_iter() {
         let types = dependencies.determine_types(&swap.swap_id).await?;
 
-        let sub_entity = build_rfc003_siren_entity(&dependencies, swap, types, IncludeState::No)?;
+        let sub_entity = build_rfc003_siren_entity(
+            &dependencies,
+            swap,
+            types,
+            &dependencies.validation,
+            IncludeState::No,
+        )?;
         entity.push_sub_entity(siren::SubEntity::from_entity(sub_entity, &["item"]));
     }
 
@@ -1,6 +1,7 @@
 use http_api_problem::HttpApiProblem;
 use warp::Rejection;
 
+pub mod bans;
 pub mod index;
 pub mod peers;
 pub mod rfc003;
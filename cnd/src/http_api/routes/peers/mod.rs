@@ -1,7 +1,16 @@
-use crate::{http_api::Http, network::ComitPeers, swap_protocols::Facade};
+use crate::{
+    db::PeerAddressBook,
+    http_api::{problem, routes::into_rejection, Http},
+    network::{
+        Capabilities, ComitPeers, DiscoveredPeers, ManagePeers, PeerCapabilities, PeerInfo,
+        PeerInformation,
+    },
+    swap_protocols::Facade,
+};
 use libp2p::{Multiaddr, PeerId};
-use serde::Serialize;
-use warp::{Rejection, Reply};
+use serde::{Deserialize, Serialize};
+use std::convert::TryFrom;
+use warp::{http::StatusCode, Rejection, Reply};
 
 #[derive(Serialize, Debug)]
 pub struct PeersResource {
@@ -12,18 +21,147 @@ pub struct PeersResource {
 pub struct Peer {
     id: Http<PeerId>,
     endpoints: Vec<Multiaddr>,
+    connected: bool,
+    /// Set for peers we are not connected to but know about through discovery.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    discovered_via: Option<DiscoveryMechanism>,
+    /// What the peer told us it can swap the last time we connected to it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    capabilities: Option<Capabilities>,
+    /// The round-trip time of the last ping. This and the following fields are
+    /// only set for peers we are connected to.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    latency_ms: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    agent_version: Option<String>,
+    /// The address the peer sees our connection coming from.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    observed_address: Option<Multiaddr>,
+}
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum DiscoveryMechanism {
+    Mdns,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct PeerAddressBody {
+    peer_id: Http<PeerId>,
+    address: Multiaddr,
 }
 
 #[allow(clippy::needless_pass_by_value)]
 pub async fn get_peers(dependencies: Facade) -> Result<impl Reply, Rejection> {
-    let peers = dependencies
+    let mut peers = dependencies
         .comit_peers()
         .await
         .map(|(peer, addresses)| Peer {
             id: Http(peer),
             endpoints: addresses,
+            connected: true,
+            discovered_via: None,
+            capabilities: None,
+            latency_ms: None,
+            agent_version: None,
+            observed_address: None,
         })
-        .collect();
+        .collect::<Vec<_>>();
+
+    for (peer, addresses) in dependencies.discovered_peers().await {
+        if peers.iter().all(|known| known.id.0 != peer) {
+            peers.push(Peer {
+                id: Http(peer),
+                endpoints: addresses,
+                connected: false,
+                discovered_via: Some(DiscoveryMechanism::Mdns),
+                capabilities: None,
+                latency_ms: None,
+                agent_version: None,
+                observed_address: None,
+            });
+        }
+    }
+
+    let address_book = dependencies
+        .peer_addresses()
+        .await
+        .map_err(problem::from_anyhow)
+        .map_err(into_rejection)?;
+
+    for (peer, addresses) in address_book {
+        match peers.iter_mut().find(|known| known.id.0 == peer) {
+            // The endpoints of connected peers are the ones we are connected through.
+            Some(known) if known.connected => {}
+            Some(known) => {
+                for address in addresses {
+                    if !known.endpoints.contains(&address) {
+                        known.endpoints.push(address);
+                    }
+                }
+            }
+            None => peers.push(Peer {
+                id: Http(peer),
+                endpoints: addresses,
+                connected: false,
+                discovered_via: None,
+                capabilities: None,
+                latency_ms: None,
+                agent_version: None,
+                observed_address: None,
+            }),
+        }
+    }
+
+    let mut capabilities = dependencies.peer_capabilities().await;
+    let mut peer_info = dependencies.peer_info().await;
+    for peer in peers.iter_mut() {
+        peer.capabilities = capabilities.remove(&peer.id.0);
+
+        // What we learned through ping and identify is only current while connected.
+        if !peer.connected {
+            continue;
+        }
+        if let Some(PeerInfo {
+            latency,
+            agent_version,
+            observed_address,
+        }) = peer_info.remove(&peer.id.0)
+        {
+            peer.latency_ms = latency
+                .map(|latency| u64::try_from(latency.as_millis()).unwrap_or(u64::max_value()));
+            peer.agent_version = agent_version;
+            peer.observed_address = observed_address;
+        }
+    }
 
     Ok(warp::reply::json(&PeersResource { peers }))
 }
+
+#[allow(clippy::needless_pass_by_value)]
+pub async fn post_peer(
+    dependencies: Facade,
+    body: serde_json::Value,
+) -> Result<impl Reply, Rejection> {
+    handle_post_peer(dependencies, body)
+        .await
+        .map(|_| StatusCode::NO_CONTENT)
+        .map_err(problem::from_anyhow)
+        .map_err(into_rejection)
+}
+
+async fn handle_post_peer(dependencies: Facade, body: serde_json::Value) -> anyhow::Result<()> {
+    let PeerAddressBody { peer_id, address } = serde_json::from_value(body)?;
+
+    dependencies.add_peer_address(peer_id.0, address).await
+}
+
+#[allow(clippy::needless_pass_by_value)]
+pub async fn delete_peer(peer_id: PeerId, dependencies: Facade) -> Result<impl Reply, Rejection> {
+    dependencies
+        .remove_peer(&peer_id)
+        .await
+        .map(|_| StatusCode::NO_CONTENT)
+        .map_err(problem::from_anyhow)
+        .map_err(into_rejection)
+}
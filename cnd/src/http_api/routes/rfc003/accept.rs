@@ -81,3 +81,44 @@ impl IntoAcceptMessage<Bitcoin, Ethereum> for OnlyRefund<Ethereum> {
         }
     }
 }
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct RedeemAndRefund<AL: Ledger, BL: Ledger> {
+    pub alpha_ledger_redeem_identity: AL::Identity,
+    pub beta_ledger_refund_identity: BL::Identity,
+}
+
+impl ListRequiredFields for Accept<Ethereum, Ethereum> {
+    fn list_required_fields() -> Vec<siren::Field> {
+        vec![
+            siren::Field {
+                name: "alpha_ledger_redeem_identity".to_owned(),
+                class: vec!["ethereum".to_owned(), "address".to_owned()],
+                _type: Some("text".to_owned()),
+                value: None,
+                title: Some("Alpha ledger redeem identity".to_owned()),
+            },
+            siren::Field {
+                name: "beta_ledger_refund_identity".to_owned(),
+                class: vec!["ethereum".to_owned(), "address".to_owned()],
+                _type: Some("text".to_owned()),
+                value: None,
+                title: Some("Beta ledger refund identity".to_owned()),
+            },
+        ]
+    }
+}
+
+impl IntoAcceptMessage<Ethereum, Ethereum> for RedeemAndRefund<Ethereum, Ethereum> {
+    fn into_accept_message(
+        self,
+        id: SwapId,
+        _: &dyn DeriveIdentities,
+    ) -> messages::Accept<Ethereum, Ethereum> {
+        messages::Accept {
+            swap_id: id,
+            alpha_ledger_redeem_identity: self.alpha_ledger_redeem_identity,
+            beta_ledger_refund_identity: self.beta_ledger_refund_identity,
+        }
+    }
+}
@@ -2,9 +2,9 @@ use crate::{
     http_api::action::ListRequiredFields,
     swap_protocols::rfc003::{actions::Decline, messages::SwapDeclineReason, Ledger},
 };
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Copy, Deserialize)]
+#[derive(Debug, Clone, Copy, Deserialize, Serialize)]
 pub struct DeclineBody {
     pub reason: Option<HttpApiSwapDeclineReason>,
 }
@@ -26,10 +26,18 @@ pub fn to_swap_decline_reason(
 ) -> Option<SwapDeclineReason> {
     reason.map(|reason| match reason {
         HttpApiSwapDeclineReason::UnsatisfactoryRate => SwapDeclineReason::UnsatisfactoryRate,
+        HttpApiSwapDeclineReason::UnsatisfactoryQuantity => {
+            SwapDeclineReason::UnsatisfactoryQuantity
+        }
+        HttpApiSwapDeclineReason::UnsatisfactoryExpiry => SwapDeclineReason::UnsatisfactoryExpiry,
+        HttpApiSwapDeclineReason::UnsupportedSwap => SwapDeclineReason::UnsupportedSwap,
     })
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
 pub enum HttpApiSwapDeclineReason {
     UnsatisfactoryRate,
+    UnsatisfactoryQuantity,
+    UnsatisfactoryExpiry,
+    UnsupportedSwap,
 }
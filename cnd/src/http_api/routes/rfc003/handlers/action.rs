@@ -1,5 +1,5 @@
 use crate::{
-    db::{DetermineTypes, Save},
+    db::{DetermineTypes, LoadAcceptedSwap, Save},
     http_api::{
         action::{
             ActionExecutionParameters, ActionResponseBody, IntoResponsePayload, ListRequiredFields,
@@ -9,8 +9,7 @@ use crate::{
         routes::rfc003::decline::{to_swap_decline_reason, DeclineBody},
     },
     init_swap::init_accepted_swap,
-    libp2p_comit_ext::ToHeader,
-    network::PendingRequestFor,
+    network::{rfc003_accept_response, rfc003_decline_response, PendingRequestFor},
     seed::DeriveSwapSeed,
     swap_protocols::{
         actions::Actions,
@@ -18,14 +17,13 @@ use crate::{
             self,
             actions::{Action, ActionKind},
             bob::State,
-            messages::{Decision, IntoAcceptMessage},
+            messages::IntoAcceptMessage,
             state_store::StateStore,
         },
         Facade, SwapId,
     },
 };
 use anyhow::Context;
-use libp2p_comit::frame::Response;
 use std::{
     fmt::{self, Debug, Display},
     string::ToString,
@@ -69,6 +67,9 @@ pub async fn handle_action(
                     body.into_accept_message(swap_id, &dependencies.derive_swap_seed(swap_id));
 
                 Save::save(&dependencies, accept_message).await?;
+                let (_, _, accepted_at) =
+                    LoadAcceptedSwap::<AL, BL, AA, BA>::load_accepted_swap(&dependencies, &swap_id)
+                        .await?;
 
                 log::trace!("received accept action: {}", swap_id);
 
@@ -81,7 +82,14 @@ pub async fn handle_action(
                 })?;
 
                 let swap_request = state.request();
-                init_accepted_swap(&dependencies, swap_request, accept_message, types.role)?;
+                init_accepted_swap(
+                    &dependencies,
+                    swap_request,
+                    accept_message,
+                    accepted_at,
+                    types.role,
+                )
+                .await?;
 
                 Ok(ActionResponseBody::None)
             }
@@ -178,41 +186,6 @@ trait SelectAction<Accept, Decline, Deploy, Fund, Redeem, Refund>:
     }
 }
 
-fn rfc003_accept_response<AL: rfc003::Ledger, BL: rfc003::Ledger>(
-    message: rfc003::messages::Accept<AL, BL>,
-) -> Response {
-    Response::empty()
-        .with_header(
-            "decision",
-            Decision::Accepted
-                .to_header()
-                .expect("Decision should not fail to serialize"),
-        )
-        .with_body(
-            serde_json::to_value(rfc003::messages::AcceptResponseBody::<AL, BL> {
-                beta_ledger_refund_identity: message.beta_ledger_refund_identity,
-                alpha_ledger_redeem_identity: message.alpha_ledger_redeem_identity,
-            })
-            .expect("body should always serialize into serde_json::Value"),
-        )
-}
-
-fn rfc003_decline_response(message: rfc003::messages::Decline) -> Response {
-    Response::empty()
-        .with_header(
-            "decision",
-            Decision::Declined
-                .to_header()
-                .expect("Decision shouldn't fail to serialize"),
-        )
-        .with_body(
-            serde_json::to_value(rfc003::messages::DeclineResponseBody {
-                reason: message.reason,
-            })
-            .expect("decline body should always serialize into serde_json::Value"),
-        )
-}
-
 impl<Accept, Decline, Deploy, Fund, Redeem, Refund, I>
     SelectAction<Accept, Decline, Deploy, Fund, Redeem, Refund> for I
 where
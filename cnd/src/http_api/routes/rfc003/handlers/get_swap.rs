@@ -8,5 +8,11 @@ pub async fn handle_get_swap(dependencies: Facade, id: SwapId) -> anyhow::Result
     let swap = Retrieve::get(&dependencies, &id).await?;
     let types = dependencies.determine_types(&id).await?;
 
-    build_rfc003_siren_entity(&dependencies, swap, types, IncludeState::Yes)
+    build_rfc003_siren_entity(
+        &dependencies,
+        swap,
+        types,
+        &dependencies.validation,
+        IncludeState::Yes,
+    )
 }
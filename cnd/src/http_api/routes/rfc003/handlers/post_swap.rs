@@ -1,9 +1,9 @@
 use crate::{
     asset::Asset,
-    db::{Save, Sqlite, Swap},
+    db::{LoadSwapEvents, Save, Sqlite, Swap, SwapEventRecord},
     ethereum,
     http_api::{HttpAsset, HttpLedger},
-    init_swap::init_accepted_swap,
+    init_swap::handle_swap_response,
     network::{DialInformation, SendRequest},
     seed::DeriveSwapSeed,
     swap_protocols::{
@@ -16,11 +16,11 @@ use crate::{
     },
     timestamp::Timestamp,
 };
-use anyhow::Context;
 use futures_core::future::TryFutureExt;
 use serde::{Deserialize, Serialize};
 use std::str::FromStr;
 
+#[allow(clippy::cognitive_complexity)]
 pub async fn handle_post_swap(
     dependencies: Facade,
     body: serde_json::Value,
@@ -128,6 +128,78 @@ pub async fn handle_post_swap(
             );
             initiate_request(dependencies, id, peer, request).await?;
         }
+        SwapRequestBody {
+            alpha_ledger: HttpLedger::Ethereum(alpha_ledger),
+            beta_ledger: HttpLedger::Ethereum(beta_ledger),
+            alpha_asset: HttpAsset::Ether(alpha_asset),
+            beta_asset: HttpAsset::Erc20(beta_asset),
+            alpha_expiry,
+            beta_expiry,
+            identities,
+            peer,
+        } => {
+            let identities = identities.into_identities(&seed)?;
+            let request = new_request(
+                id,
+                alpha_ledger,
+                beta_ledger,
+                alpha_asset,
+                beta_asset,
+                alpha_expiry,
+                beta_expiry,
+                identities,
+                secret_hash,
+            );
+            initiate_request(dependencies, id, peer, request).await?;
+        }
+        SwapRequestBody {
+            alpha_ledger: HttpLedger::Ethereum(alpha_ledger),
+            beta_ledger: HttpLedger::Ethereum(beta_ledger),
+            alpha_asset: HttpAsset::Erc20(alpha_asset),
+            beta_asset: HttpAsset::Ether(beta_asset),
+            alpha_expiry,
+            beta_expiry,
+            identities,
+            peer,
+        } => {
+            let identities = identities.into_identities(&seed)?;
+            let request = new_request(
+                id,
+                alpha_ledger,
+                beta_ledger,
+                alpha_asset,
+                beta_asset,
+                alpha_expiry,
+                beta_expiry,
+                identities,
+                secret_hash,
+            );
+            initiate_request(dependencies, id, peer, request).await?;
+        }
+        SwapRequestBody {
+            alpha_ledger: HttpLedger::Ethereum(alpha_ledger),
+            beta_ledger: HttpLedger::Ethereum(beta_ledger),
+            alpha_asset: HttpAsset::Erc20(alpha_asset),
+            beta_asset: HttpAsset::Erc20(beta_asset),
+            alpha_expiry,
+            beta_expiry,
+            identities,
+            peer,
+        } => {
+            let identities = identities.into_identities(&seed)?;
+            let request = new_request(
+                id,
+                alpha_ledger,
+                beta_ledger,
+                alpha_asset,
+                beta_asset,
+                alpha_expiry,
+                beta_expiry,
+                identities,
+                secret_hash,
+            );
+            initiate_request(dependencies, id, peer, request).await?;
+        }
         _ => {
             return Err(anyhow::Error::from(UnsupportedSwap {
                 alpha_ledger: body.alpha_ledger,
@@ -192,7 +264,12 @@ async fn initiate_request<AL, BL, AA, BA>(
     swap_request: rfc003::Request<AL, BL, AA, BA>,
 ) -> anyhow::Result<()>
 where
-    Sqlite: Save<Request<AL, BL, AA, BA>> + Save<Accept<AL, BL>> + Save<Swap> + Save<Decline>,
+    Sqlite: Save<Request<AL, BL, AA, BA>>
+        + Save<Accept<AL, BL>>
+        + Save<Swap>
+        + Save<Decline>
+        + Save<SwapEventRecord<AL, BL, AA, BA>>
+        + LoadSwapEvents<AL, BL, AA, BA>,
     AL: Ledger,
     BL: Ledger,
     AA: Asset,
@@ -201,6 +278,12 @@ where
 {
     log::trace!("initiating new request: {}", swap_request.swap_id);
 
+    // The swap is only created once the counterparty received the request, failures
+    // to reach it are reported to the client instead.
+    let response = dependencies
+        .send_request(peer.clone(), swap_request.clone())
+        .await?;
+
     let counterparty = peer.peer_id.clone();
     let seed = dependencies.derive_swap_seed(id);
 
@@ -210,33 +293,15 @@ where
     let state = State::proposed(swap_request.clone(), seed);
     StateStore::insert(&dependencies, id, state);
 
-    let future = {
+    tokio::task::spawn(
         async move {
-            let response = dependencies
-                .send_request(peer.clone(), swap_request.clone())
-                .await
-                .with_context(|| format!("Failed to send swap request to {}", peer.clone()))?;
-
-            match response {
-                Ok(accept) => {
-                    Save::save(&dependencies, accept).await?;
-
-                    init_accepted_swap(&dependencies, swap_request, accept, Role::Alice)?;
-                }
-                Err(decline) => {
-                    log::info!("Swap declined: {}", decline.swap_id);
-                    let state = State::declined(swap_request.clone(), decline, seed);
-                    StateStore::insert(&dependencies, id, state.clone());
-                    Save::save(&dependencies, decline).await?;
-                }
-            };
-            Ok(())
+            let response = response.await;
+            handle_swap_response(dependencies, peer, swap_request, response).await
         }
-    };
-
-    tokio::task::spawn(future.map_err(|e: anyhow::Error| {
-        log::error!("{}", e);
-    }));
+        .map_err(|e: anyhow::Error| {
+            log::error!("{}", e);
+        }),
+    );
 
     Ok(())
 }
@@ -388,6 +453,34 @@ impl IntoIdentities<ledger::Ethereum, ledger::Bitcoin> for HttpIdentities {
     }
 }
 
+impl IntoIdentities<ledger::Ethereum, ledger::Ethereum> for HttpIdentities {
+    fn into_identities(
+        self,
+        _: &dyn DeriveIdentities,
+    ) -> anyhow::Result<Identities<ledger::Ethereum, ledger::Ethereum>> {
+        let HttpIdentities {
+            alpha_ledger_refund_identity,
+            beta_ledger_redeem_identity,
+        } = self;
+
+        let alpha_ledger_refund_identity = alpha_ledger_refund_identity.ok_or_else(|| {
+            anyhow::Error::from(MissingIdentity {
+                kind: IdentityKind::AlphaLedgerRefundIdentity,
+            })
+        })?;
+        let beta_ledger_redeem_identity = beta_ledger_redeem_identity.ok_or_else(|| {
+            anyhow::Error::from(MissingIdentity {
+                kind: IdentityKind::BetaLedgerRedeemIdentity,
+            })
+        })?;
+
+        Ok(Identities {
+            alpha_ledger_refund_identity,
+            beta_ledger_redeem_identity,
+        })
+    }
+}
+
 fn default_alpha_expiry() -> Timestamp {
     Timestamp::now().plus(60 * 60 * 24)
 }
@@ -399,7 +492,9 @@ fn default_beta_expiry() -> Timestamp {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{network::DialInformation, swap_protocols::ledger::ethereum::ChainId};
+    use crate::{
+        network::DialInformation, seed::RootSeed, swap_protocols::ledger::ethereum::ChainId,
+    };
     use spectral::prelude::*;
 
     #[test]
@@ -504,4 +599,53 @@ mod tests {
                 chain_id: ChainId::new(3),
             }));
     }
+
+    #[test]
+    fn ethereum_to_ethereum_swap_can_span_two_chains() {
+        let body = r#"{
+                "alpha_ledger": {
+                    "name": "ethereum",
+                    "chain_id": 1
+                },
+                "beta_ledger": {
+                    "name": "ethereum",
+                    "chain_id": 3
+                },
+                "alpha_asset": {
+                    "name": "ether",
+                    "quantity": "10000000000000000000"
+                },
+                "beta_asset": {
+                    "name": "erc20",
+                    "quantity": "10000000000000000000",
+                    "token_contract": "0xB97048628DB6B661D4C2aA833e95Dbe1A905B280"
+                },
+                "alpha_ledger_refund_identity": "0x00a329c0648769a73afac7f9381e08fb43dbea72",
+                "beta_ledger_redeem_identity": "0x00a329c0648769a73afac7f9381e08fb43dbea72",
+                "peer": "Qma9T5YraSnpRDZqRR4krcSJabThc8nwZuJV3LercPHufi"
+            }"#;
+        let body = serde_json::from_str::<SwapRequestBody>(body);
+
+        assert_that(&body)
+            .is_ok()
+            .map(|b| &(b.alpha_ledger, b.beta_ledger))
+            .is_equal_to(&(
+                HttpLedger::Ethereum(ledger::Ethereum::new(ChainId::mainnet())),
+                HttpLedger::Ethereum(ledger::Ethereum::new(ChainId::ropsten())),
+            ));
+    }
+
+    #[test]
+    fn ethereum_to_ethereum_swap_requires_both_identities() {
+        let seed = RootSeed::from([0u8; 32]).derive_swap_seed(SwapId::default());
+        let identities = HttpIdentities {
+            alpha_ledger_refund_identity: Some(ethereum::Address::zero()),
+            beta_ledger_redeem_identity: None,
+        };
+
+        let identities: anyhow::Result<Identities<ledger::Ethereum, ledger::Ethereum>> =
+            identities.into_identities(&seed);
+
+        assert_that(&identities).is_err();
+    }
 }
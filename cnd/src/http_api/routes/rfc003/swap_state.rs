@@ -2,7 +2,7 @@
 use crate::{
     asset::Asset,
     http_api::{Http, SwapStatus},
-    swap_protocols::rfc003::{self, Ledger, SecretHash},
+    swap_protocols::rfc003::{self, messages::SwapDeclineReason, Ledger, SecretHash},
     timestamp::Timestamp,
 };
 use serde::Serialize;
@@ -51,6 +51,7 @@ pub enum SwapCommunicationState {
     Sent,
     Accepted,
     Declined,
+    TimedOut,
 }
 
 impl<AL: Ledger, BL: Ledger, AA: Asset, BA: Asset> From<rfc003::SwapCommunication<AL, BL, AA, BA>>
@@ -79,8 +80,11 @@ impl<AL: Ledger, BL: Ledger, AA: Asset, BA: Asset> From<rfc003::SwapCommunicatio
                 beta_refund_identity: Some(Http(response.beta_ledger_refund_identity)),
                 secret_hash: request.secret_hash,
             },
-            Declined { request, .. } => Self {
-                status: SwapCommunicationState::Declined,
+            Declined { request, response } => Self {
+                status: match response.reason {
+                    Some(SwapDeclineReason::ProposalExpired) => SwapCommunicationState::TimedOut,
+                    _ => SwapCommunicationState::Declined,
+                },
                 alpha_expiry: request.alpha_expiry,
                 beta_expiry: request.beta_expiry,
                 alpha_redeem_identity: None,
@@ -166,6 +170,17 @@ impl<L: Ledger, A: Asset> From<rfc003::LedgerState<L, A>>
                 refund_tx: Some(Http(refund_transaction)),
                 redeem_tx: None,
             },
+            Expired {
+                htlc_location,
+                deploy_transaction,
+            } => Self {
+                status,
+                htlc_location: htlc_location.map(Http),
+                deploy_tx: deploy_transaction.map(Http),
+                fund_tx: None,
+                refund_tx: None,
+                redeem_tx: None,
+            },
         }
     }
 }
@@ -179,7 +194,7 @@ impl SwapStatus {
         use self::SwapCommunicationState::*;
         use crate::swap_protocols::rfc003::HtlcState::*;
 
-        if swap_communication_state == Declined {
+        if swap_communication_state == Declined || swap_communication_state == TimedOut {
             return SwapStatus::NotSwapped;
         }
 
@@ -187,6 +202,7 @@ impl SwapStatus {
             (Redeemed, Redeemed) => SwapStatus::Swapped,
             (IncorrectlyFunded, _) => SwapStatus::NotSwapped,
             (Refunded, _) | (_, Refunded) => SwapStatus::NotSwapped,
+            (Expired, _) | (_, Expired) => SwapStatus::Expired,
             _ => SwapStatus::InProgress,
         }
     }
@@ -195,10 +211,11 @@ impl SwapStatus {
 #[cfg(test)]
 impl quickcheck::Arbitrary for SwapCommunicationState {
     fn arbitrary<G: quickcheck::Gen>(g: &mut G) -> Self {
-        match g.next_u32() % 3 {
+        match g.next_u32() % 4 {
             0 => SwapCommunicationState::Declined,
             1 => SwapCommunicationState::Accepted,
             2 => SwapCommunicationState::Sent,
+            3 => SwapCommunicationState::TimedOut,
             _ => unreachable!(),
         }
     }
@@ -244,6 +261,14 @@ mod tests {
         )
     }
 
+    #[test]
+    fn given_timed_out_should_not_be_swapped() {
+        assert_eq!(
+            SwapStatus::new(TimedOut, NotDeployed, NotDeployed),
+            SwapStatus::NotSwapped
+        )
+    }
+
     #[test]
     fn given_both_redeem_should_be_swapped() {
         assert_eq!(
@@ -268,6 +293,22 @@ mod tests {
         )
     }
 
+    #[test]
+    fn given_alpha_expired_should_be_expired() {
+        assert_eq!(
+            SwapStatus::new(Accepted, Expired, NotDeployed),
+            SwapStatus::Expired
+        )
+    }
+
+    #[test]
+    fn given_beta_expired_should_be_expired() {
+        assert_eq!(
+            SwapStatus::new(Accepted, Funded, Expired),
+            SwapStatus::Expired
+        )
+    }
+
     quickcheck::quickcheck! {
         fn test(
             swap_communication_state: SwapCommunicationState,
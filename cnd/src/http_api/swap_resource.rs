@@ -2,19 +2,21 @@
 
 use crate::{
     asset,
+    config::Validation,
     db::{Swap, SwapTypes},
     http_api::{
         action::ToSirenAction,
         route_factory::swap_path,
-        routes::rfc003::{LedgerState, SwapCommunication, SwapState},
+        routes::rfc003::{LedgerState, SwapCommunication, SwapCommunicationState, SwapState},
         Http, HttpAsset, HttpLedger,
     },
     swap_protocols::{
         actions::Actions,
         ledger,
-        rfc003::{self, state_store::StateStore},
+        rfc003::{self, state_store::StateStore, validation::validate},
         HashFunction, SwapId, SwapProtocol,
     },
+    timestamp::Timestamp,
 };
 use http_api_problem::HttpApiProblem;
 use libp2p::PeerId;
@@ -28,6 +30,12 @@ pub struct SwapResource<S> {
     pub counterparty: Http<PeerId>,
     pub protocol: Http<SwapProtocol>,
     pub status: SwapStatus,
+    /// Set while the ledgers of the swap cannot be watched, e.g. because a
+    /// connector is not reachable.
+    pub degraded: bool,
+    /// Problems with the parameters of a swap that has not been answered yet.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
     pub parameters: SwapParameters,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub state: Option<S>,
@@ -47,6 +55,7 @@ pub enum SwapStatus {
     InProgress,
     Swapped,
     NotSwapped,
+    Expired,
     InternalFailure,
 }
 
@@ -93,6 +102,24 @@ impl_from_request_for_swap_parameters!(
     asset::Erc20,
     asset::Bitcoin
 );
+impl_from_request_for_swap_parameters!(
+    ledger::Ethereum,
+    ledger::Ethereum,
+    asset::Ether,
+    asset::Erc20
+);
+impl_from_request_for_swap_parameters!(
+    ledger::Ethereum,
+    ledger::Ethereum,
+    asset::Erc20,
+    asset::Ether
+);
+impl_from_request_for_swap_parameters!(
+    ledger::Ethereum,
+    ledger::Ethereum,
+    asset::Erc20,
+    asset::Erc20
+);
 
 pub enum IncludeState {
     Yes,
@@ -103,6 +130,7 @@ pub fn build_rfc003_siren_entity<S: StateStore>(
     state_store: &S,
     swap: Swap,
     types: SwapTypes,
+    validation: &Validation,
     include_state: IncludeState,
 ) -> anyhow::Result<siren::Entity> {
     let id = swap.swap_id;
@@ -115,7 +143,8 @@ pub fn build_rfc003_siren_entity<S: StateStore>(
         let communication = SwapCommunication::from(state.swap_communication.clone());
         let alpha_ledger = LedgerState::from(state.alpha_ledger_state.clone());
         let beta_ledger = LedgerState::from(state.beta_ledger_state.clone());
-        let parameters = SwapParameters::from(state.clone().request());
+        let request = state.clone().request();
+        let parameters = SwapParameters::from(request.clone());
         let actions = state.actions();
 
         let status = SwapStatus::new(
@@ -124,9 +153,21 @@ pub fn build_rfc003_siren_entity<S: StateStore>(
             beta_ledger.status,
         );
 
+        // the invariants can be violated by the passing of time while the swap is
+        // proposed
+        let warnings = match communication.status {
+            SwapCommunicationState::Sent => validate(&request, validation, Timestamp::now())
+                .iter()
+                .map(ToString::to_string)
+                .collect(),
+            _ => vec![],
+        };
+
         let swap = SwapResource {
             id: Http(id),
             status,
+            degraded: state_store.is_degraded(&id),
+            warnings,
             protocol: Http(SwapProtocol::Rfc003(HashFunction::Sha256)),
             parameters,
             role: swap.role.to_string(),
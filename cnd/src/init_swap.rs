@@ -1,51 +1,401 @@
 use crate::{
     asset::Asset,
+    db::{LoadAcceptedSwap, LoadSwapEvents, Save, SwapEventRecord},
+    network::{DialInformation, RequestError, SendRequest},
     seed::DeriveSwapSeed,
     swap_protocols::{
         rfc003::{
-            alice, bob, create_swap, events::HtlcEvents, state_store::StateStore, Accept, Ledger,
-            Request,
+            self, alice, bob, create_swap, events::HtlcEvents, messages::SwapDeclineReason,
+            state_store::StateStore, Accept, Decline, Ledger, Request,
         },
         Role,
     },
 };
+use anyhow::Context;
+use chrono::NaiveDateTime;
 
+/// Inserts the state of an accepted swap into the state store and spawns a
+/// task that watches both ledgers of the swap.
+///
+/// `accepted_at` is the time the swap was accepted, the ledgers are watched
+/// from this point in time on.
+///
+/// Any events already saved for this swap are applied to the state before it
+/// becomes visible, this restores the ledger states of swaps loaded from the
+/// database.
 #[allow(clippy::cognitive_complexity)]
-pub fn init_accepted_swap<D, AL: Ledger, BL: Ledger, AA: Asset, BA: Asset>(
+pub async fn init_accepted_swap<D, AL: Ledger, BL: Ledger, AA: Asset, BA: Asset>(
     dependencies: &D,
     request: Request<AL, BL, AA, BA>,
     accept: Accept<AL, BL>,
+    accepted_at: NaiveDateTime,
     role: Role,
 ) -> anyhow::Result<()>
 where
-    D: StateStore + Clone + DeriveSwapSeed + HtlcEvents<AL, AA> + HtlcEvents<BL, BA>,
+    D: StateStore
+        + Clone
+        + DeriveSwapSeed
+        + HtlcEvents<AL, AA>
+        + HtlcEvents<BL, BA>
+        + LoadSwapEvents<AL, BL, AA, BA>
+        + Save<SwapEventRecord<AL, BL, AA, BA>>,
 {
     let id = request.swap_id;
     let seed = dependencies.derive_swap_seed(id);
     log::trace!("initialising accepted swap: {}", id);
 
+    let known_events = dependencies.load_swap_events(&id).await?;
+
     match role {
         Role::Alice => {
             let state = alice::State::accepted(request.clone(), accept, seed);
             StateStore::insert(dependencies, id, state);
 
+            for event in known_events.iter().cloned() {
+                StateStore::update::<alice::State<AL, BL, AA, BA>>(dependencies, &id, event);
+            }
+
             tokio::task::spawn(create_swap::<D, alice::State<AL, BL, AA, BA>>(
                 dependencies.clone(),
                 request,
                 accept,
+                accepted_at,
+                known_events,
             ));
         }
         Role::Bob => {
             let state = bob::State::accepted(request.clone(), accept, seed);
             StateStore::insert(dependencies, id, state);
 
+            for event in known_events.iter().cloned() {
+                StateStore::update::<bob::State<AL, BL, AA, BA>>(dependencies, &id, event);
+            }
+
             tokio::task::spawn(create_swap::<D, bob::State<AL, BL, AA, BA>>(
                 dependencies.clone(),
                 request,
                 accept,
+                accepted_at,
+                known_events,
             ));
         }
     };
 
     Ok(())
 }
+
+/// Sends the request of a swap to the counterparty and initialises the swap
+/// according to the response.
+///
+/// This is used by Alice for swaps loaded from the database that did not
+/// receive a response yet. A request that is not answered within its validity
+/// window is treated like a declined one.
+pub async fn send_swap_request<D, AL: Ledger, BL: Ledger, AA: Asset, BA: Asset>(
+    dependencies: D,
+    peer: DialInformation,
+    swap_request: Request<AL, BL, AA, BA>,
+) -> anyhow::Result<()>
+where
+    D: StateStore
+        + Clone
+        + DeriveSwapSeed
+        + SendRequest
+        + HtlcEvents<AL, AA>
+        + HtlcEvents<BL, BA>
+        + LoadSwapEvents<AL, BL, AA, BA>
+        + LoadAcceptedSwap<AL, BL, AA, BA>
+        + Save<SwapEventRecord<AL, BL, AA, BA>>
+        + Save<Accept<AL, BL>>
+        + Save<Decline>,
+{
+    let response = match dependencies
+        .send_request(peer.clone(), swap_request.clone())
+        .await
+    {
+        Ok(response) => response.await,
+        Err(e) => Err(e),
+    };
+
+    handle_swap_response(dependencies, peer, swap_request, response).await
+}
+
+/// Initialises a swap according to the response to its request.
+pub async fn handle_swap_response<D, AL: Ledger, BL: Ledger, AA: Asset, BA: Asset>(
+    dependencies: D,
+    peer: DialInformation,
+    swap_request: Request<AL, BL, AA, BA>,
+    response: Result<rfc003::Response<AL, BL>, RequestError>,
+) -> anyhow::Result<()>
+where
+    D: StateStore
+        + Clone
+        + DeriveSwapSeed
+        + HtlcEvents<AL, AA>
+        + HtlcEvents<BL, BA>
+        + LoadSwapEvents<AL, BL, AA, BA>
+        + LoadAcceptedSwap<AL, BL, AA, BA>
+        + Save<SwapEventRecord<AL, BL, AA, BA>>
+        + Save<Accept<AL, BL>>
+        + Save<Decline>,
+{
+    let id = swap_request.swap_id;
+
+    let response = match response {
+        Err(RequestError::Timeout) => Err(Decline {
+            swap_id: id,
+            reason: Some(SwapDeclineReason::ProposalExpired),
+        }),
+        result => result.with_context(|| format!("Failed to send swap request to {}", peer))?,
+    };
+
+    match response {
+        Ok(accept) => {
+            Save::save(&dependencies, accept).await?;
+            let (_, _, accepted_at) = dependencies.load_accepted_swap(&id).await?;
+
+            init_accepted_swap(
+                &dependencies,
+                swap_request,
+                accept,
+                accepted_at,
+                Role::Alice,
+            )
+            .await?;
+        }
+        Err(decline) => {
+            log::info!("Swap declined: {}", decline.swap_id);
+            let seed = dependencies.derive_swap_seed(id);
+            let state = alice::State::declined(swap_request.clone(), decline, seed);
+            StateStore::insert(&dependencies, id, state);
+            Save::save(&dependencies, decline).await?;
+        }
+    };
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        asset,
+        db::AcceptedSwap,
+        seed::{RootSeed, SwapSeed},
+        swap_protocols::{
+            ledger::{Bitcoin, Ethereum},
+            rfc003::{
+                create_swap::{HtlcParams, SwapEvent},
+                events::{Deployed, Funded, Redeemed, Refunded},
+                state_store::{self, InMemoryStateStore},
+                ActorState,
+            },
+            SwapId,
+        },
+        timestamp::Timestamp,
+    };
+    use futures_core::future::{self, Either};
+    use libp2p::PeerId;
+    use spectral::prelude::*;
+    use std::{
+        sync::{Arc, Mutex},
+        time::Duration,
+    };
+
+    /// Hands out the time the accept message was saved at and records the
+    /// start of the swap the ledgers are watched from.
+    #[derive(Clone, Default)]
+    struct Dependencies {
+        state_store: Arc<InMemoryStateStore>,
+        accepts: Arc<Mutex<Vec<Accept<Bitcoin, Ethereum>>>>,
+        starts_of_swap: Arc<Mutex<Vec<Timestamp>>>,
+    }
+
+    fn accepted_at() -> NaiveDateTime {
+        NaiveDateTime::from_timestamp(1_500_000_000, 0)
+    }
+
+    impl StateStore for Dependencies {
+        fn insert<A: ActorState>(&self, key: SwapId, value: A) {
+            self.state_store.insert(key, value)
+        }
+
+        fn get<A: ActorState>(&self, key: &SwapId) -> Result<Option<A>, state_store::Error> {
+            self.state_store.get(key)
+        }
+
+        fn update<A: ActorState>(
+            &self,
+            key: &SwapId,
+            update: SwapEvent<A::AL, A::BL, A::AA, A::BA>,
+        ) {
+            self.state_store.update::<A>(key, update)
+        }
+
+        fn set_degraded(&self, key: &SwapId, degraded: bool) {
+            self.state_store.set_degraded(key, degraded)
+        }
+
+        fn is_degraded(&self, key: &SwapId) -> bool {
+            self.state_store.is_degraded(key)
+        }
+    }
+
+    impl DeriveSwapSeed for Dependencies {
+        fn derive_swap_seed(&self, id: SwapId) -> SwapSeed {
+            RootSeed::from(*b"hello world, you are beautiful!!").derive_swap_seed(id)
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl HtlcEvents<Bitcoin, asset::Bitcoin> for Dependencies {
+        async fn htlc_deployed(
+            &self,
+            _: HtlcParams<Bitcoin, asset::Bitcoin>,
+            start_of_swap: Timestamp,
+        ) -> anyhow::Result<Deployed<Bitcoin>> {
+            self.starts_of_swap.lock().unwrap().push(start_of_swap);
+            future::pending().await
+        }
+
+        async fn htlc_funded(
+            &self,
+            _: HtlcParams<Bitcoin, asset::Bitcoin>,
+            _: &Deployed<Bitcoin>,
+            _: Timestamp,
+        ) -> anyhow::Result<Funded<Bitcoin, asset::Bitcoin>> {
+            future::pending().await
+        }
+
+        async fn htlc_redeemed_or_refunded(
+            &self,
+            _: HtlcParams<Bitcoin, asset::Bitcoin>,
+            _: &Deployed<Bitcoin>,
+            _: &Funded<Bitcoin, asset::Bitcoin>,
+            _: Timestamp,
+        ) -> anyhow::Result<Either<Redeemed<Bitcoin>, Refunded<Bitcoin>>> {
+            future::pending().await
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl HtlcEvents<Ethereum, asset::Ether> for Dependencies {
+        async fn htlc_deployed(
+            &self,
+            _: HtlcParams<Ethereum, asset::Ether>,
+            start_of_swap: Timestamp,
+        ) -> anyhow::Result<Deployed<Ethereum>> {
+            self.starts_of_swap.lock().unwrap().push(start_of_swap);
+            future::pending().await
+        }
+
+        async fn htlc_funded(
+            &self,
+            _: HtlcParams<Ethereum, asset::Ether>,
+            _: &Deployed<Ethereum>,
+            _: Timestamp,
+        ) -> anyhow::Result<Funded<Ethereum, asset::Ether>> {
+            future::pending().await
+        }
+
+        async fn htlc_redeemed_or_refunded(
+            &self,
+            _: HtlcParams<Ethereum, asset::Ether>,
+            _: &Deployed<Ethereum>,
+            _: &Funded<Ethereum, asset::Ether>,
+            _: Timestamp,
+        ) -> anyhow::Result<Either<Redeemed<Ethereum>, Refunded<Ethereum>>> {
+            future::pending().await
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl LoadSwapEvents<Bitcoin, Ethereum, asset::Bitcoin, asset::Ether> for Dependencies {
+        async fn load_swap_events(
+            &self,
+            _: &SwapId,
+        ) -> anyhow::Result<Vec<SwapEvent<Bitcoin, Ethereum, asset::Bitcoin, asset::Ether>>>
+        {
+            Ok(vec![])
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl LoadAcceptedSwap<Bitcoin, Ethereum, asset::Bitcoin, asset::Ether> for Dependencies {
+        async fn load_accepted_swap(
+            &self,
+            _: &SwapId,
+        ) -> anyhow::Result<AcceptedSwap<Bitcoin, Ethereum, asset::Bitcoin, asset::Ether>> {
+            let accept = self
+                .accepts
+                .lock()
+                .unwrap()
+                .first()
+                .copied()
+                .ok_or_else(|| anyhow::anyhow!("accept was not saved"))?;
+
+            Ok((
+                Request::bitcoin_for_ether(100_000_000, 1_000_000, 2_000_000_000, 1_900_000_000),
+                accept,
+                accepted_at(),
+            ))
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl Save<SwapEventRecord<Bitcoin, Ethereum, asset::Bitcoin, asset::Ether>> for Dependencies {
+        async fn save(
+            &self,
+            _: SwapEventRecord<Bitcoin, Ethereum, asset::Bitcoin, asset::Ether>,
+        ) -> anyhow::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl Save<Accept<Bitcoin, Ethereum>> for Dependencies {
+        async fn save(&self, accept: Accept<Bitcoin, Ethereum>) -> anyhow::Result<()> {
+            self.accepts.lock().unwrap().push(accept);
+            Ok(())
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl Save<Decline> for Dependencies {
+        async fn save(&self, _: Decline) -> anyhow::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn accepted_swap_is_watched_from_the_time_the_accept_was_saved() {
+        let mut runtime = tokio::runtime::Runtime::new().unwrap();
+        let dependencies = Dependencies::default();
+        let request =
+            Request::bitcoin_for_ether(100_000_000, 1_000_000, 2_000_000_000, 1_900_000_000);
+        let accept = Accept {
+            swap_id: request.swap_id,
+            alpha_ledger_redeem_identity: request.alpha_ledger_refund_identity,
+            beta_ledger_refund_identity: request.beta_ledger_redeem_identity,
+        };
+        let peer = DialInformation {
+            peer_id: PeerId::random(),
+            address_hint: None,
+        };
+
+        let starts_of_swap = runtime.block_on(async {
+            handle_swap_response(dependencies.clone(), peer, request, Ok(Ok(accept)))
+                .await
+                .unwrap();
+
+            // give the spawned swap a chance to start watching both ledgers
+            while dependencies.starts_of_swap.lock().unwrap().len() < 2 {
+                tokio::time::delay_for(Duration::from_millis(10)).await;
+            }
+
+            dependencies.starts_of_swap.lock().unwrap().clone()
+        });
+
+        let accepted_at = Timestamp::from(accepted_at());
+        assert_that(&starts_of_swap).is_equal_to(vec![accepted_at, accepted_at]);
+    }
+}
@@ -33,6 +33,7 @@ pub mod init_swap;
 pub mod load_swaps;
 pub mod logging;
 pub mod network;
+pub mod policy;
 #[cfg(test)]
 pub mod quickcheck;
 pub mod seed;
@@ -74,6 +75,16 @@ pub fn data_dir() -> Option<PathBuf> {
     ProjectDirs::from("", "", "comit").map(|proj_dirs| proj_dirs.data_dir().to_path_buf())
 }
 
+/// The version of cnd together with the abbreviated hash of the commit it was
+/// built from.
+pub fn version() -> String {
+    let version: &'static str = env!("CARGO_PKG_VERSION");
+    let commit: &'static str = env!("GIT_HASH");
+    let length: usize = 12; // Abbreviate the hash, 12 digits is plenty.
+
+    format!("{} ({})", version, &commit[..length])
+}
+
 pub trait CreateLedgerEvents<L: Ledger, A: Asset> {
     fn create_ledger_events(&self) -> Box<dyn HtlcEvents<L, A>>;
 }
@@ -1,4 +1,5 @@
 use libp2p_comit::frame::Header;
+use serde::{de::Error as _, Deserialize, Deserializer};
 
 pub trait FromHeader
 where
@@ -11,26 +12,28 @@ pub trait ToHeader {
     fn to_header(&self) -> Result<Header, serde_json::Error>;
 }
 
-#[macro_export(local_inner_macros)]
-macro_rules! header {
-    ($e:expr) => {
-        header_internal!($e, {
-            log::info!("Header was not present, early returning with decline response!");
-            let decline_body = DeclineResponseBody {
-                reason: Some(SwapDeclineReason::MissingMandatoryHeader),
-            };
+/// Deserializes a `Header` into `T`, for use with
+/// `#[serde(deserialize_with = "from_header")]`.
+pub fn from_header<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromHeader,
+{
+    let header = Header::deserialize(deserializer)?;
 
-            return Err(Response::empty().with_header(
-                "decision",
-                Decision::Declined
-                    .to_header()
-                    .expect("Decision should not fail to serialize"),
-            )
-            .with_body(serde_json::to_value(decline_body).expect(
-                "decline body should always serialize into serde_json::Value",
-            )));
-        })
-    };
+    T::from_header(header).map_err(D::Error::custom)
+}
+
+/// Like `from_header` but for headers that are optional.
+pub fn optional_from_header<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromHeader,
+{
+    Option::<Header>::deserialize(deserializer)?
+        .map(T::from_header)
+        .transpose()
+        .map_err(D::Error::custom)
 }
 
 #[macro_export]
@@ -57,31 +60,3 @@ macro_rules! body {
         }
     };
 }
-
-#[macro_export]
-macro_rules! header_internal {
-    ($e:expr, $none:expr) => {
-        match $e {
-            Some(Ok(header)) => header,
-            Some(Err(e)) => {
-                log::error!("Failed to deserialize header because of unexpected field: {}", e);
-
-                let decline_body = DeclineResponseBody {
-                    reason: Some(SwapDeclineReason::BadJsonField),
-                };
-
-                return Err(Response::empty().with_header(
-                    "decision",
-                    Decision::Declined
-                        .to_header()
-                        .expect("Decision should not fail to serialize"),
-                )
-                .with_body(serde_json::to_value(decline_body).expect(
-                    "decline body should always serialize into serde_json::Value",
-                )));
-
-            },
-            None => $none,
-        }
-    };
-}
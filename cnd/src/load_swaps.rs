@@ -1,14 +1,23 @@
 #![allow(clippy::type_repetition_in_bounds)]
 use crate::{
-    asset,
-    db::{DetermineTypes, LoadAcceptedSwap, Retrieve},
-    init_swap::init_accepted_swap,
+    asset::{self, Asset},
+    db::{
+        CommunicationState, DetermineCommunicationState, DetermineTypes, LoadAcceptedSwap,
+        LoadDecline, LoadRequest, LoadSwapEvents, Retrieve, Save, SwapEventRecord,
+    },
+    init_swap::{init_accepted_swap, send_swap_request},
+    network::{DialInformation, SendRequest},
     seed::DeriveSwapSeed,
     swap_protocols::{
         ledger::{Bitcoin, Ethereum},
-        rfc003::{events::HtlcEvents, state_store::StateStore},
+        rfc003::{
+            bob, events::HtlcEvents, messages::SwapDeclineReason, state_store::StateStore, Accept,
+            Decline, Ledger, Request,
+        },
+        Role,
     },
 };
+use futures_core::future::TryFutureExt;
 
 #[allow(clippy::cognitive_complexity)]
 pub async fn load_swaps_from_database<D>(dependencies: D) -> anyhow::Result<()>
@@ -18,13 +27,44 @@ where
         + DeriveSwapSeed
         + Retrieve
         + DetermineTypes
+        + DetermineCommunicationState
+        + LoadDecline
+        + SendRequest
         + HtlcEvents<Bitcoin, asset::Bitcoin>
         + HtlcEvents<Ethereum, asset::Ether>
         + HtlcEvents<Ethereum, asset::Erc20>
+        + LoadRequest<Bitcoin, Ethereum, asset::Bitcoin, asset::Ether>
+        + LoadRequest<Ethereum, Bitcoin, asset::Ether, asset::Bitcoin>
+        + LoadRequest<Bitcoin, Ethereum, asset::Bitcoin, asset::Erc20>
+        + LoadRequest<Ethereum, Bitcoin, asset::Erc20, asset::Bitcoin>
+        + LoadRequest<Ethereum, Ethereum, asset::Ether, asset::Erc20>
+        + LoadRequest<Ethereum, Ethereum, asset::Erc20, asset::Ether>
+        + LoadRequest<Ethereum, Ethereum, asset::Erc20, asset::Erc20>
         + LoadAcceptedSwap<Bitcoin, Ethereum, asset::Bitcoin, asset::Ether>
         + LoadAcceptedSwap<Ethereum, Bitcoin, asset::Ether, asset::Bitcoin>
         + LoadAcceptedSwap<Bitcoin, Ethereum, asset::Bitcoin, asset::Erc20>
-        + LoadAcceptedSwap<Ethereum, Bitcoin, asset::Erc20, asset::Bitcoin>,
+        + LoadAcceptedSwap<Ethereum, Bitcoin, asset::Erc20, asset::Bitcoin>
+        + LoadAcceptedSwap<Ethereum, Ethereum, asset::Ether, asset::Erc20>
+        + LoadAcceptedSwap<Ethereum, Ethereum, asset::Erc20, asset::Ether>
+        + LoadAcceptedSwap<Ethereum, Ethereum, asset::Erc20, asset::Erc20>
+        + LoadSwapEvents<Bitcoin, Ethereum, asset::Bitcoin, asset::Ether>
+        + LoadSwapEvents<Ethereum, Bitcoin, asset::Ether, asset::Bitcoin>
+        + LoadSwapEvents<Bitcoin, Ethereum, asset::Bitcoin, asset::Erc20>
+        + LoadSwapEvents<Ethereum, Bitcoin, asset::Erc20, asset::Bitcoin>
+        + LoadSwapEvents<Ethereum, Ethereum, asset::Ether, asset::Erc20>
+        + LoadSwapEvents<Ethereum, Ethereum, asset::Erc20, asset::Ether>
+        + LoadSwapEvents<Ethereum, Ethereum, asset::Erc20, asset::Erc20>
+        + Save<SwapEventRecord<Bitcoin, Ethereum, asset::Bitcoin, asset::Ether>>
+        + Save<SwapEventRecord<Ethereum, Bitcoin, asset::Ether, asset::Bitcoin>>
+        + Save<SwapEventRecord<Bitcoin, Ethereum, asset::Bitcoin, asset::Erc20>>
+        + Save<SwapEventRecord<Ethereum, Bitcoin, asset::Erc20, asset::Bitcoin>>
+        + Save<SwapEventRecord<Ethereum, Ethereum, asset::Ether, asset::Erc20>>
+        + Save<SwapEventRecord<Ethereum, Ethereum, asset::Erc20, asset::Ether>>
+        + Save<SwapEventRecord<Ethereum, Ethereum, asset::Erc20, asset::Erc20>>
+        + Save<Accept<Bitcoin, Ethereum>>
+        + Save<Accept<Ethereum, Bitcoin>>
+        + Save<Accept<Ethereum, Ethereum>>
+        + Save<Decline>,
 {
     log::debug!("loading swaps from database ...");
 
@@ -33,19 +73,198 @@ where
         log::debug!("got swap from database: {}", swap_id);
 
         let types = DetermineTypes::determine_types(&dependencies, &swap_id).await?;
+        let communication_state =
+            DetermineCommunicationState::determine_communication_state(&dependencies, &swap_id)
+                .await?;
 
         with_swap_types!(types, {
-            let accepted =
-                LoadAcceptedSwap::<AL, BL, AA, BA>::load_accepted_swap(&dependencies, &swap_id)
+            match communication_state {
+                CommunicationState::Accepted => {
+                    let accepted = LoadAcceptedSwap::<AL, BL, AA, BA>::load_accepted_swap(
+                        &dependencies,
+                        &swap_id,
+                    )
                     .await;
 
-            match accepted {
-                Ok((request, accept, _at)) => {
-                    init_accepted_swap(&dependencies, request, accept, types.role)?;
+                    match accepted {
+                        Ok((request, accept, at)) => {
+                            init_accepted_swap(&dependencies, request, accept, at, types.role)
+                                .await?;
+                        }
+                        Err(e) => log::error!("failed to load swap: {}, continuing ...", e),
+                    };
+                }
+                CommunicationState::Proposed => {
+                    let request =
+                        LoadRequest::<AL, BL, AA, BA>::load_request(&dependencies, &swap_id).await;
+
+                    match (request, types.role) {
+                        (Ok(request), Role::Alice) => {
+                            let seed = dependencies.derive_swap_seed(swap_id);
+                            let state = ROLE::proposed(request.clone(), seed);
+                            StateStore::insert(&dependencies, swap_id, state);
+
+                            let peer = DialInformation {
+                                peer_id: swap.counterparty.clone(),
+                                address_hint: None,
+                            };
+
+                            tokio::task::spawn(
+                                send_swap_request(dependencies.clone(), peer, request).map_err(
+                                    |e: anyhow::Error| {
+                                        log::error!("{}", e);
+                                    },
+                                ),
+                            );
+                        }
+                        (Ok(request), Role::Bob) => {
+                            if let Err(e) = decline_lost_proposal(&dependencies, request).await {
+                                log::error!("failed to decline swap {}: {:?}", swap_id, e);
+                            }
+                        }
+                        (Err(e), _) => log::error!("failed to load swap: {}, continuing ...", e),
+                    };
                 }
-                Err(e) => log::error!("failed to load swap: {}, continuing ...", e),
-            };
+                CommunicationState::Declined | CommunicationState::TimedOut => {
+                    let request =
+                        LoadRequest::<AL, BL, AA, BA>::load_request(&dependencies, &swap_id).await;
+                    let decline = LoadDecline::load_decline(&dependencies, &swap_id).await;
+
+                    match (request, decline) {
+                        (Ok(request), Ok(decline)) => {
+                            let seed = dependencies.derive_swap_seed(swap_id);
+                            let state = ROLE::declined(request, decline, seed);
+                            StateStore::insert(&dependencies, swap_id, state);
+                        }
+                        (Err(e), _) | (_, Err(e)) => {
+                            log::error!("failed to load swap: {}, continuing ...", e)
+                        }
+                    };
+                }
+            }
         });
     }
     Ok(())
 }
+
+/// Declines a proposal Bob loaded from the database.
+///
+/// The channel to answer the request was lost when cnd stopped. If Alice sends
+/// the request again, she is answered with this decline.
+async fn decline_lost_proposal<D, AL, BL, AA, BA>(
+    dependencies: &D,
+    request: Request<AL, BL, AA, BA>,
+) -> anyhow::Result<()>
+where
+    D: StateStore + DeriveSwapSeed + Save<Decline>,
+    AL: Ledger,
+    BL: Ledger,
+    AA: Asset,
+    BA: Asset,
+{
+    let swap_id = request.swap_id;
+    let decline = Decline {
+        swap_id,
+        reason: Some(SwapDeclineReason::ProposalExpired),
+    };
+    dependencies.save(decline).await?;
+
+    let seed = dependencies.derive_swap_seed(swap_id);
+    let state = bob::State::declined(request, decline, seed);
+    StateStore::insert(dependencies, swap_id, state);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        seed::{RootSeed, SwapSeed},
+        swap_protocols::{
+            rfc003::{
+                self,
+                state_store::{self, InMemoryStateStore},
+                ActorState, SwapCommunication,
+            },
+            SwapId,
+        },
+    };
+    use spectral::prelude::*;
+    use std::sync::{Arc, Mutex};
+
+    type BobState = bob::State<Bitcoin, Ethereum, asset::Bitcoin, asset::Ether>;
+
+    #[derive(Clone, Default)]
+    struct Dependencies {
+        state_store: Arc<InMemoryStateStore>,
+        declines: Arc<Mutex<Vec<Decline>>>,
+    }
+
+    impl StateStore for Dependencies {
+        fn insert<A: ActorState>(&self, key: SwapId, value: A) {
+            self.state_store.insert(key, value)
+        }
+
+        fn get<A: ActorState>(&self, key: &SwapId) -> Result<Option<A>, state_store::Error> {
+            self.state_store.get(key)
+        }
+
+        fn update<A: ActorState>(
+            &self,
+            key: &SwapId,
+            update: rfc003::create_swap::SwapEvent<A::AL, A::BL, A::AA, A::BA>,
+        ) {
+            self.state_store.update::<A>(key, update)
+        }
+
+        fn set_degraded(&self, key: &SwapId, degraded: bool) {
+            self.state_store.set_degraded(key, degraded)
+        }
+
+        fn is_degraded(&self, key: &SwapId) -> bool {
+            self.state_store.is_degraded(key)
+        }
+    }
+
+    impl DeriveSwapSeed for Dependencies {
+        fn derive_swap_seed(&self, id: SwapId) -> SwapSeed {
+            RootSeed::from(*b"hello world, you are beautiful!!").derive_swap_seed(id)
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl Save<Decline> for Dependencies {
+        async fn save(&self, decline: Decline) -> anyhow::Result<()> {
+            self.declines.lock().unwrap().push(decline);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn proposal_loaded_by_bob_is_declined() {
+        let mut runtime = tokio::runtime::Runtime::new().unwrap();
+        let dependencies = Dependencies::default();
+        let request =
+            Request::bitcoin_for_ether(100_000_000, 1_000_000, 2_000_000_000, 1_900_000_000);
+        let id = request.swap_id;
+
+        runtime
+            .block_on(decline_lost_proposal(&dependencies, request))
+            .unwrap();
+
+        let decline = Decline {
+            swap_id: id,
+            reason: Some(SwapDeclineReason::ProposalExpired),
+        };
+        assert_that(&*dependencies.declines.lock().unwrap()).is_equal_to(vec![decline]);
+
+        let state = StateStore::get::<BobState>(&dependencies, &id)
+            .unwrap()
+            .unwrap();
+        matches::assert_matches!(
+            state.swap_communication,
+            SwapCommunication::Declined { response, .. } if response == decline
+        );
+    }
+}
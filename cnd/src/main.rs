@@ -3,12 +3,16 @@
 use crate::cli::Options;
 use anyhow::Context;
 use cnd::{
-    btsieve::{bitcoin::BitcoindConnector, ethereum::Web3Connector},
+    btsieve::{
+        bitcoin::BitcoindConnector,
+        ethereum::{Web3Connector, Web3Connectors},
+    },
     config::{self, Settings},
     db::Sqlite,
     http_api::route_factory,
     load_swaps,
     network::Swarm,
+    policy,
     seed::RootSeed,
     swap_protocols::{rfc003::state_store::InMemoryStateStore, Facade},
 };
@@ -48,29 +52,45 @@ fn main() -> anyhow::Result<()> {
         BitcoindConnector::new(node_url, network)?
     };
 
-    let ethereum_connector = Web3Connector::new(settings.clone().ethereum.node_url);
+    let ethereum_connectors = {
+        let config::Ethereum {
+            node_url,
+            chain_id,
+            additional_nodes,
+        } = settings.clone().ethereum;
+        let mut connectors = Web3Connectors::default();
+        connectors.insert(chain_id, Web3Connector::new(node_url));
+        for node in additional_nodes {
+            connectors.insert(node.chain_id, Web3Connector::new(node.node_url));
+        }
+        connectors
+    };
 
     let state_store = Arc::new(InMemoryStateStore::default());
 
     let database = Sqlite::new_in_dir(&settings.data.dir)?;
 
+    let (swap_requests_sender, swap_requests) = tokio::sync::mpsc::unbounded_channel();
+
     let swarm = Swarm::new(
         &settings,
         seed,
         &mut runtime,
         &bitcoin_connector,
-        &ethereum_connector,
+        &ethereum_connectors,
         &state_store,
         &database,
+        swap_requests_sender,
     )?;
 
     let deps = Facade {
         bitcoin_connector,
-        ethereum_connector,
+        ethereum_connectors,
         state_store: Arc::clone(&state_store),
         seed,
         swarm,
         db: database,
+        validation: settings.validation.clone(),
     };
 
     runtime.block_on(
@@ -79,6 +99,14 @@ fn main() -> anyhow::Result<()> {
             .compat(),
     )?;
 
+    if let Some(policy) = settings.policy.clone() {
+        runtime.spawn_std(policy::answer_swap_requests(
+            policy,
+            deps.clone(),
+            swap_requests,
+        ));
+    }
+
     runtime.spawn_std(spawn_warp_instance(settings, deps));
 
     // Block the current thread.
@@ -89,12 +117,8 @@ fn main() -> anyhow::Result<()> {
 #[allow(clippy::print_stdout)] // We cannot use `log` before we have the config file
 fn version() {
     let name: &'static str = "COMIT network daemon";
-    let version: &'static str = env!("CARGO_PKG_VERSION");
-    let commit: &'static str = env!("GIT_HASH");
-    let length: usize = 12; // Abbreviate the hash, 12 digits is plenty.
-    let short = &commit[..length];
 
-    println!("{} {} ({})", name, version, short);
+    println!("{} {}", name, cnd::version());
 }
 
 async fn spawn_warp_instance(settings: Settings, dependencies: Facade) {
@@ -0,0 +1,136 @@
+use crate::swap_protocols::ledger::ethereum::ChainId;
+use libp2p_comit::{
+    frame::{OutboundRequest, Response},
+    MalformedRequest, NoHeaders, RequestType,
+};
+use serde::{de::IgnoredAny, Deserialize, Serialize};
+
+/// The request type through which nodes exchange their `Capabilities`.
+pub const CAPABILITIES: &str = "CAPABILITIES";
+
+#[derive(Clone, Copy, Debug)]
+pub struct CapabilitiesRequest;
+
+impl RequestType for CapabilitiesRequest {
+    const NAME: &'static str = CAPABILITIES;
+    const HEADERS: &'static [&'static str] = &[];
+    /// Every node asks for the capabilities of its peers when connecting to
+    /// them, answering does not touch the database.
+    const RATE_LIMITED: bool = false;
+
+    type Headers = NoHeaders;
+    type Body = IgnoredAny;
+
+    fn decline(_: &MalformedRequest) -> Response {
+        Response::empty()
+    }
+}
+
+/// What a node can swap, exchanged with every peer we connect to.
+///
+/// All lists use the names of the respective headers of a `SWAP` request.
+/// Missing fields are treated as empty so nodes can add new kinds of
+/// capabilities without breaking older ones.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Capabilities {
+    pub swap_protocols: Vec<String>,
+    pub ledgers: Vec<String>,
+    pub assets: Vec<String>,
+    pub networks: Networks,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Networks {
+    pub bitcoin: Vec<String>,
+    /// Chain ids of the Ethereum networks.
+    pub ethereum: Vec<u32>,
+}
+
+impl Capabilities {
+    /// The capabilities of this node when connected to `bitcoin_network` and
+    /// the Ethereum networks with `ethereum_chain_ids`.
+    pub fn local(bitcoin_network: bitcoin::Network, ethereum_chain_ids: Vec<ChainId>) -> Self {
+        let bitcoin_network = match bitcoin_network {
+            bitcoin::Network::Bitcoin => "mainnet",
+            bitcoin::Network::Testnet => "testnet",
+            bitcoin::Network::Regtest => "regtest",
+        };
+
+        Self {
+            swap_protocols: vec!["comit-rfc-003".into()],
+            ledgers: vec!["bitcoin".into(), "ethereum".into()],
+            assets: vec!["bitcoin".into(), "ether".into(), "erc20".into()],
+            networks: Networks {
+                bitcoin: vec![bitcoin_network.into()],
+                ethereum: ethereum_chain_ids.into_iter().map(u32::from).collect(),
+            },
+        }
+    }
+
+    pub fn request() -> OutboundRequest {
+        OutboundRequest::new(CAPABILITIES)
+    }
+
+    pub fn to_response(&self) -> Response {
+        Response::empty()
+            .with_body(serde_json::to_value(self).expect("capabilities should always serialize"))
+    }
+
+    pub fn from_response(response: &Response) -> Result<Self, serde_json::Error> {
+        serde_json::from_value(response.body().clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use libp2p_comit::{test_harness::ConnectedNodes, Comit, PendingTypedRequest};
+    use spectral::prelude::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn capabilities_roundtrip_through_a_response() {
+        let capabilities = Capabilities::local(bitcoin::Network::Regtest, vec![ChainId::regtest()]);
+
+        let decoded = Capabilities::from_response(&capabilities.to_response());
+
+        assert_that(&decoded).is_ok().is_equal_to(&capabilities);
+    }
+
+    #[test]
+    fn unknown_and_missing_capabilities_are_ignored() {
+        let response = Response::empty().with_body(serde_json::json!({
+            "ledgers": ["bitcoin"],
+            "payment_channels": ["lightning"]
+        }));
+
+        let decoded = Capabilities::from_response(&response);
+
+        assert_that(&decoded).is_ok().is_equal_to(&Capabilities {
+            ledgers: vec!["bitcoin".into()],
+            ..Capabilities::default()
+        });
+    }
+
+    #[test]
+    fn capabilities_are_exchanged_between_nodes() {
+        let capabilities = Capabilities::local(bitcoin::Network::Regtest, vec![ChainId::regtest()]);
+        let mut nodes = ConnectedNodes::new(
+            Comit::new(HashMap::new()),
+            Comit::new(HashMap::new()).with_request_type::<CapabilitiesRequest>(),
+        );
+
+        let response = nodes.request_response(Capabilities::request(), |request| {
+            let PendingTypedRequest { channel, .. } =
+                request.decode::<CapabilitiesRequest>().unwrap();
+            channel.send(capabilities.to_response()).unwrap();
+        });
+
+        let response = response.unwrap();
+        assert_that(&Capabilities::from_response(&response))
+            .is_ok()
+            .is_equal_to(&capabilities);
+    }
+}
@@ -1,12 +1,21 @@
+pub mod capabilities;
+pub mod swap_request;
 pub mod transport;
 
-pub use transport::ComitTransport;
+pub use self::{
+    capabilities::{Capabilities, CapabilitiesRequest, CAPABILITIES},
+    swap_request::{SwapHeaders, SwapRequest},
+    transport::ComitTransport,
+};
 
 use crate::{
     asset::{Asset, AssetKind},
-    btsieve::{bitcoin::BitcoindConnector, ethereum::Web3Connector},
-    config::Settings,
-    db::{Save, Sqlite, Swap},
+    btsieve::{bitcoin::BitcoindConnector, ethereum::Web3Connectors},
+    config::{Limits, Settings, Validation},
+    db::{
+        BanList, CommunicationState, DetermineCommunicationState, LoadAcceptedSwap, LoadDecline,
+        PeerAddress, PeerAddressBook, Retrieve, Save, Sqlite, Swap,
+    },
     libp2p_comit_ext::{FromHeader, ToHeader},
     seed::{DeriveSwapSeed, RootSeed},
     swap_protocols::{
@@ -14,115 +23,393 @@ use crate::{
             self, bob,
             messages::{Decision, DeclineResponseBody, Request, SwapDeclineReason},
             state_store::{InMemoryStateStore, StateStore},
+            validation::validate,
             Ledger,
         },
         HashFunction, LedgerKind, Role, SwapId, SwapProtocol,
     },
+    timestamp::Timestamp,
 };
 use async_trait::async_trait;
 use futures::{
     future::Future,
     stream::Stream,
-    sync::oneshot::{self, Sender},
+    sync::{
+        mpsc,
+        oneshot::{self, Sender},
+    },
+    Async, Poll,
+};
+use futures_core::{
+    compat::Future01CompatExt,
+    future::{BoxFuture, FutureExt, TryFutureExt},
 };
-use futures_core::{compat::Future01CompatExt, FutureExt, TryFutureExt};
 use libp2p::{
-    core::muxing::SubstreamRef,
+    core::{muxing::SubstreamRef, ConnectedPoint},
+    identify::{Identify, IdentifyEvent},
     identity::{self, ed25519},
-    mdns::Mdns,
-    swarm::{
-        protocols_handler::DummyProtocolsHandler, ExpandedSwarm, IntoProtocolsHandlerSelect,
-        NetworkBehaviourEventProcess,
+    kad::{
+        record::{store::MemoryStore, Key, Record},
+        GetRecordOk, Kademlia, KademliaEvent, Quorum,
     },
+    mdns::{Mdns, MdnsEvent},
+    multiaddr::Protocol,
+    ping::{Ping, PingConfig, PingEvent, PingSuccess},
+    swarm::{NetworkBehaviourEventProcess, SwarmBuilder},
     Multiaddr, NetworkBehaviour, PeerId,
 };
 use libp2p_comit::{
-    frame::{self, CodecError, OutboundRequest, Response, ValidatedInboundRequest},
-    handler::{ComitHandler, ProtocolInEvent, ProtocolOutEvent},
-    BehaviourOutEvent, Comit, PendingInboundRequest,
-};
-use libp2p_core::{
-    either::{EitherError, EitherOutput},
-    muxing::StreamMuxerBox,
+    frame::{self, OutboundRequest, Response},
+    BehaviourOutEvent, Comit, PendingResponse, PendingTypedRequest, RequestLimit, RequestType,
 };
+use libp2p_core::muxing::StreamMuxerBox;
 use std::{
     collections::{HashMap, HashSet},
     fmt::Display,
     io,
     sync::{Arc, Mutex},
+    time::Duration,
 };
+use tokio::sync::mpsc::UnboundedSender;
 use tokio_compat::runtime::{Runtime, TaskExecutor};
 
+type InnerSwarm = libp2p::Swarm<ComitTransport, ComitNode<SubstreamRef<Arc<StreamMuxerBox>>>>;
+
+/// A handle to the libp2p swarm, which is owned by the task polling it.
+///
+/// Everything that needs the swarm itself is sent to that task as a
+/// `Command`, so using the handle never waits for the swarm to be polled.
 #[derive(Clone, derivative::Derivative)]
 #[derivative(Debug)]
-#[allow(clippy::type_complexity)]
 pub struct Swarm {
     #[derivative(Debug = "ignore")]
-    swarm: Arc<
-        Mutex<
-            ExpandedSwarm<
-                ComitTransport,
-                ComitNode<SubstreamRef<Arc<StreamMuxerBox>>>,
-                EitherOutput<ProtocolInEvent, void::Void>,
-                EitherOutput<ProtocolOutEvent, void::Void>,
-                IntoProtocolsHandlerSelect<
-                    ComitHandler<SubstreamRef<Arc<StreamMuxerBox>>>,
-                    DummyProtocolsHandler<SubstreamRef<Arc<StreamMuxerBox>>>,
-                >,
-                EitherError<CodecError, void::Void>,
-            >,
-        >,
-    >,
+    commands: mpsc::UnboundedSender<Command>,
     local_peer_id: PeerId,
+    db: Sqlite,
+    #[derivative(Debug = "ignore")]
+    response_channels: ResponseChannels,
+    peer_capabilities: Arc<Mutex<HashMap<PeerId, Capabilities>>>,
+    peer_info: Arc<Mutex<HashMap<PeerId, PeerInfo>>>,
+}
+
+/// What a `Swarm` handle asks the task owning the swarm to do. Answers are
+/// sent back through the contained channels.
+#[derive(Debug)]
+enum Command {
+    SendRequest {
+        dial_information: DialInformation,
+        request: OutboundRequest,
+        response: oneshot::Sender<PendingResponse>,
+    },
+    ComitPeers(oneshot::Sender<Vec<(PeerId, Vec<Multiaddr>)>>),
+    DiscoveredPeers(oneshot::Sender<Vec<(PeerId, Vec<Multiaddr>)>>),
+    ListenAddresses(oneshot::Sender<Vec<Multiaddr>>),
+    PublishAddresses(Vec<Multiaddr>),
+    AddAddress {
+        peer_id: PeerId,
+        address: Multiaddr,
+    },
+    RemoveAddresses {
+        peer_id: PeerId,
+        addresses: Vec<Multiaddr>,
+    },
+    BannedPeers(oneshot::Sender<Vec<PeerId>>),
+    BanPeer(PeerId),
+    UnbanPeer {
+        peer_id: PeerId,
+        unbanned: oneshot::Sender<bool>,
+    },
 }
 
 impl Swarm {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         settings: &Settings,
         seed: RootSeed,
         runtime: &mut Runtime,
         bitcoin_connector: &BitcoindConnector,
-        ethereum_connector: &Web3Connector,
+        ethereum_connectors: &Web3Connectors,
         state_store: &Arc<InMemoryStateStore>,
         database: &Sqlite,
+        swap_requests: UnboundedSender<SwapId>,
     ) -> anyhow::Result<Self> {
         let local_key_pair = derive_key_pair(&seed);
         let local_peer_id = PeerId::from(local_key_pair.clone().public());
         log::info!("Starting with peer_id: {}", local_peer_id);
 
-        let transport = transport::build_comit_transport(local_key_pair);
-        let behaviour = ComitNode::new(
+        let transport = transport::build_comit_transport(
+            local_key_pair.clone(),
+            settings.network.authentication,
+        )?;
+        let mut behaviour = ComitNode::new(
+            local_key_pair.public(),
             bitcoin_connector.clone(),
-            ethereum_connector.clone(),
+            ethereum_connectors.clone(),
             Arc::clone(&state_store),
             seed,
             database.clone(),
             runtime.executor(),
+            settings.validation.clone(),
+            settings.limits,
+            Capabilities::local(settings.bitcoin.network, settings.ethereum.chain_ids()),
+            swap_requests,
         )?;
-        let mut swarm = libp2p::Swarm::new(transport, behaviour, local_peer_id.clone());
+
+        let mut banned_peers = settings.network.banned_peers.clone();
+        banned_peers.extend(runtime.block_on_std(database.banned_peers())?);
+        for peer_id in banned_peers.clone() {
+            behaviour.ban_peer(peer_id);
+        }
+
+        for address in settings.network.bootstrap_peers.clone() {
+            let (peer_id, address) = split_peer_id(address)?;
+            behaviour.add_bootstrap_peer(peer_id, address);
+        }
+        if !settings.network.bootstrap_peers.is_empty() {
+            behaviour.bootstrap();
+        }
+
+        let counterparties = runtime.block_on_std(counterparties_of_ongoing_swaps(database))?;
+        for (peer_id, addresses) in runtime.block_on_std(database.peer_addresses())? {
+            for address in addresses {
+                behaviour.add_address(peer_id.clone(), address);
+            }
+            if counterparties.contains(&peer_id) {
+                behaviour.dial(peer_id);
+            }
+        }
+
+        let mut swarm = SwarmBuilder::new(transport, behaviour, local_peer_id.clone())
+            .incoming_limit(settings.limits.max_pending_connections)
+            .build();
+
+        for peer_id in banned_peers {
+            libp2p::Swarm::ban_peer_id(&mut swarm, peer_id);
+        }
 
         for addr in settings.network.listen.clone() {
             libp2p::Swarm::listen_on(&mut swarm, addr)
                 .expect("Could not listen on specified address");
         }
 
-        let swarm = Arc::new(Mutex::new(swarm));
+        let (commands, mut command_receiver) = mpsc::unbounded();
+        let handle = Self {
+            commands,
+            local_peer_id,
+            db: swarm.db.clone(),
+            response_channels: swarm.response_channels.clone(),
+            peer_capabilities: swarm.peer_capabilities.clone(),
+            peer_info: swarm.peer_info.clone(),
+        };
 
-        let swarm_worker = futures::stream::poll_fn({
-            let swarm = swarm.clone();
-            move || swarm.lock().unwrap().poll()
-        })
-        .for_each(|_| Ok(()))
-        .map_err(|e| {
-            log::error!("failed with {:?}", e);
+        let swarm_worker = futures::future::poll_fn(move || -> Poll<(), ()> {
+            while let Async::Ready(command) = command_receiver
+                .poll()
+                .expect("receiving from an unbounded channel never fails")
+            {
+                match command {
+                    Some(command) => handle_command(&mut swarm, command),
+                    // All handles are gone, nobody can use the swarm anymore.
+                    None => return Ok(Async::Ready(())),
+                }
+            }
+
+            loop {
+                match swarm.poll() {
+                    Ok(Async::Ready(Some(_))) => {}
+                    Ok(Async::Ready(None)) => return Ok(Async::Ready(())),
+                    Ok(Async::NotReady) => return Ok(Async::NotReady),
+                    Err(e) => {
+                        log::error!("failed with {:?}", e);
+                        return Err(());
+                    }
+                }
+            }
         });
 
         runtime.spawn(swarm_worker);
+        runtime.spawn_std(publish_listen_addresses(handle.clone()));
 
-        Ok(Self {
-            swarm,
-            local_peer_id,
-        })
+        Ok(handle)
+    }
+
+    fn send_command(&self, command: Command) {
+        self.commands
+            .unbounded_send(command)
+            .unwrap_or_else(|_| log::error!("the swarm is no longer running"))
+    }
+
+    /// Sends the command built by `command`, the returned future resolves to
+    /// the answer of the swarm.
+    fn ask<T>(&self, command: impl FnOnce(oneshot::Sender<T>) -> Command) -> oneshot::Receiver<T> {
+        let (sender, receiver) = oneshot::channel();
+        self.send_command(command(sender));
+
+        receiver
+    }
+}
+
+/// Executes `command` on the swarm. Handles might have stopped waiting for
+/// the answer already, which is fine.
+fn handle_command(swarm: &mut InnerSwarm, command: Command) {
+    match command {
+        Command::SendRequest {
+            dial_information,
+            request,
+            response,
+        } => {
+            let _ = response.send(swarm.send_request(dial_information, request));
+        }
+        Command::ComitPeers(peers) => {
+            let _ = peers.send(swarm.comit.connected_peers().collect());
+        }
+        Command::DiscoveredPeers(peers) => {
+            let _ = peers.send(swarm.mdns_peers().collect());
+        }
+        Command::ListenAddresses(addresses) => {
+            let _ = addresses.send(
+                libp2p::Swarm::listeners(swarm)
+                    .chain(libp2p::Swarm::external_addresses(swarm))
+                    .cloned()
+                    .collect(),
+            );
+        }
+        Command::PublishAddresses(addresses) => swarm.publish_addresses(addresses),
+        Command::AddAddress { peer_id, address } => swarm.add_address(peer_id, address),
+        Command::RemoveAddresses { peer_id, addresses } => {
+            for address in addresses {
+                swarm.remove_address(&peer_id, &address);
+            }
+        }
+        Command::BannedPeers(peers) => {
+            let _ = peers.send(swarm.banned_peers().cloned().collect());
+        }
+        Command::BanPeer(peer_id) => {
+            swarm.ban_peer(peer_id.clone());
+            libp2p::Swarm::ban_peer_id(swarm, peer_id);
+        }
+        Command::UnbanPeer { peer_id, unbanned } => {
+            libp2p::Swarm::unban_peer_id(swarm, peer_id.clone());
+            let _ = unbanned.send(swarm.unban_peer(&peer_id));
+        }
+    }
+}
+
+/// How often the addresses we listen on are published to the DHT.
+const ADDRESS_PUBLICATION_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Keep the record of the addresses we listen on in the DHT up to date, so
+/// that other nodes can reach us knowing only our `PeerId`.
+async fn publish_listen_addresses(swarm: Swarm) {
+    loop {
+        let addresses = swarm.listen_addresses().await;
+
+        // We only know the addresses we listen on once the swarm has been polled.
+        let wait = if addresses.is_empty() {
+            Duration::from_secs(1)
+        } else {
+            swarm.send_command(Command::PublishAddresses(addresses));
+            ADDRESS_PUBLICATION_INTERVAL
+        };
+
+        tokio::time::delay_for(wait).await;
+    }
+}
+
+/// Split an address of the form `<address>/p2p/<peer id>` into its parts.
+/// The peers of all swaps that have not been declined or timed out, we
+/// reconnect to them on startup.
+async fn counterparties_of_ongoing_swaps(database: &Sqlite) -> anyhow::Result<HashSet<PeerId>> {
+    let mut counterparties = HashSet::new();
+
+    for swap in database.all().await? {
+        let state = database
+            .determine_communication_state(&swap.swap_id)
+            .await?;
+        match state {
+            CommunicationState::Proposed | CommunicationState::Accepted => {
+                counterparties.insert(swap.counterparty);
+            }
+            CommunicationState::Declined | CommunicationState::TimedOut => {}
+        }
+    }
+
+    Ok(counterparties)
+}
+
+fn split_peer_id(mut address: Multiaddr) -> anyhow::Result<(PeerId, Multiaddr)> {
+    match address.pop() {
+        Some(Protocol::P2p(multihash)) => {
+            let peer_id = PeerId::from_multihash(multihash)
+                .map_err(|_| anyhow::anyhow!("invalid peer id in {}", address))?;
+
+            Ok((peer_id, address))
+        }
+        _ => Err(anyhow::anyhow!(
+            "bootstrap peer address {} does not end with a peer id",
+            address
+        )),
+    }
+}
+
+/// The key of the DHT record holding the addresses of `peer_id`.
+fn addresses_record_key(peer_id: &PeerId) -> Key {
+    Key::new(&peer_id.as_bytes())
+}
+
+/// The DHT record announcing that `peer_id` can be reached at `addresses`.
+fn addresses_record(peer_id: &PeerId, addresses: &[Multiaddr]) -> Record {
+    let value = serde_json::to_vec(addresses).expect("addresses always serialize");
+
+    Record::new(addresses_record_key(peer_id), value)
+}
+
+/// The peer whose addresses are stored under `key`.
+fn peer_id_of_record_key(key: &Key) -> Option<PeerId> {
+    PeerId::from_bytes(key.to_vec()).ok()
+}
+
+/// The addresses stored in a DHT record created through `addresses_record`.
+fn addresses_of_record(record: &Record) -> anyhow::Result<Vec<Multiaddr>> {
+    let addresses = serde_json::from_slice::<Vec<Multiaddr>>(&record.value)?;
+    anyhow::ensure!(!addresses.is_empty(), "the record holds no addresses");
+
+    Ok(addresses)
+}
+
+/// The addresses a peer announced through identify that are worth dialing.
+///
+/// Peers can announce anything, only publicly routable addresses are kept and
+/// at most `MAX_ANNOUNCED_ADDRESSES` of them.
+fn announced_addresses(listen_addrs: Vec<Multiaddr>) -> Vec<Multiaddr> {
+    listen_addrs
+        .into_iter()
+        .filter(is_global)
+        .take(MAX_ANNOUNCED_ADDRESSES)
+        .collect()
+}
+
+/// Whether `address` can be reached from outside the host and network of the
+/// peer.
+fn is_global(address: &Multiaddr) -> bool {
+    match address.iter().next() {
+        Some(Protocol::Ip4(ip)) => {
+            !(ip.is_loopback()
+                || ip.is_private()
+                || ip.is_link_local()
+                || ip.is_unspecified()
+                || ip.is_broadcast()
+                || ip.is_documentation())
+        }
+        Some(Protocol::Ip6(ip)) => {
+            let first_segment = ip.segments()[0];
+            let is_unique_local = first_segment & 0xfe00 == 0xfc00;
+            let is_link_local = first_segment & 0xffc0 == 0xfe80;
+
+            !(ip.is_loopback() || ip.is_unspecified() || is_unique_local || is_link_local)
+        }
+        Some(Protocol::Dns4(_)) | Some(Protocol::Dns6(_)) => true,
+        _ => false,
     }
 }
 
@@ -137,11 +424,14 @@ fn derive_key_pair(seed: &RootSeed) -> identity::Keypair {
 pub struct ComitNode<TSubstream> {
     comit: Comit<TSubstream>,
     mdns: Mdns<TSubstream>,
+    kademlia: Kademlia<TSubstream, MemoryStore>,
+    ping: Ping<TSubstream>,
+    identify: Identify<TSubstream>,
 
     #[behaviour(ignore)]
     pub bitcoin_connector: BitcoindConnector,
     #[behaviour(ignore)]
-    pub ethereum_connector: Web3Connector,
+    pub ethereum_connectors: Web3Connectors,
     #[behaviour(ignore)]
     pub state_store: Arc<InMemoryStateStore>,
     #[behaviour(ignore)]
@@ -149,11 +439,51 @@ pub struct ComitNode<TSubstream> {
     #[behaviour(ignore)]
     pub db: Sqlite,
     #[behaviour(ignore)]
-    response_channels: Arc<Mutex<HashMap<SwapId, oneshot::Sender<Response>>>>,
+    response_channels: ResponseChannels,
     #[behaviour(ignore)]
     task_executor: TaskExecutor,
+    #[behaviour(ignore)]
+    validation: Validation,
+    #[behaviour(ignore)]
+    max_pending_proposals: usize,
+    #[behaviour(ignore)]
+    swap_requests: UnboundedSender<SwapId>,
+    #[behaviour(ignore)]
+    mdns_peers: HashMap<PeerId, HashSet<Multiaddr>>,
+    #[behaviour(ignore)]
+    local_peer_id: PeerId,
+    #[behaviour(ignore)]
+    capabilities: Capabilities,
+    #[behaviour(ignore)]
+    peer_capabilities: Arc<Mutex<HashMap<PeerId, Capabilities>>>,
+    #[behaviour(ignore)]
+    peer_info: Arc<Mutex<HashMap<PeerId, PeerInfo>>>,
 }
 
+/// What we learned about a peer through the ping and identify protocols.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PeerInfo {
+    /// The round-trip time of the last successful ping.
+    pub latency: Option<Duration>,
+    /// The software the peer runs, e.g. `cnd/0.4.0 (0123456789ab)` for cnd.
+    pub agent_version: Option<String>,
+    /// The address the peer sees our connection coming from.
+    pub observed_address: Option<Multiaddr>,
+}
+
+/// The version of the COMIT protocols we announce through identify.
+const IDENTIFY_PROTOCOL_VERSION: &str = "comit/1.1.0";
+
+/// How many of the addresses a peer announces through identify we dial.
+const MAX_ANNOUNCED_ADDRESSES: usize = 8;
+
+/// How long a swap request stays valid if it is not answered, in seconds.
+pub const PROPOSAL_VALIDITY: u32 = 60 * 60;
+
+/// The channels to answer pending swap requests through, together with the end
+/// of the validity window of each request.
+type ResponseChannels = Arc<Mutex<HashMap<SwapId, (oneshot::Sender<Response>, Option<Timestamp>)>>>;
+
 #[derive(Clone, Debug, PartialEq)]
 pub struct DialInformation {
     pub peer_id: PeerId,
@@ -176,9 +506,22 @@ pub enum RequestError {
     #[error("peer node produced an invalid response")]
     InvalidResponse,
     #[error("failed to establish a new connection to make the request")]
-    Connecting(io::ErrorKind),
-    #[error("unable to send the data on the existing connection")]
+    Connecting,
+    #[error("the connection to the peer node was closed before it answered")]
     Connection,
+    #[error("peer node did not answer the request within its validity window")]
+    Timeout,
+}
+
+impl From<libp2p_comit::RequestError> for RequestError {
+    fn from(e: libp2p_comit::RequestError) -> Self {
+        match e {
+            libp2p_comit::RequestError::DialFailure => RequestError::Connecting,
+            libp2p_comit::RequestError::Timeout => RequestError::Timeout,
+            libp2p_comit::RequestError::SubstreamClosed => RequestError::Connection,
+            libp2p_comit::RequestError::MalformedResponse => RequestError::InvalidResponse,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy, serde::Deserialize)]
@@ -187,35 +530,57 @@ pub struct Reason {
 }
 
 impl<TSubstream> ComitNode<TSubstream> {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
+        local_public_key: identity::PublicKey,
         bitcoin_connector: BitcoindConnector,
-        ethereum_connector: Web3Connector,
+        ethereum_connectors: Web3Connectors,
         state_store: Arc<InMemoryStateStore>,
         seed: RootSeed,
         db: Sqlite,
         task_executor: TaskExecutor,
+        validation: Validation,
+        limits: Limits,
+        capabilities: Capabilities,
+        swap_requests: UnboundedSender<SwapId>,
     ) -> Result<Self, io::Error> {
-        let mut swap_headers = HashSet::new();
-        swap_headers.insert("id".into());
-        swap_headers.insert("alpha_ledger".into());
-        swap_headers.insert("beta_ledger".into());
-        swap_headers.insert("alpha_asset".into());
-        swap_headers.insert("beta_asset".into());
-        swap_headers.insert("protocol".into());
-
-        let mut known_headers = HashMap::new();
-        known_headers.insert("SWAP".into(), swap_headers);
+        let local_peer_id = local_public_key.clone().into_peer_id();
 
         Ok(Self {
-            comit: Comit::new(known_headers),
+            comit: Comit::new(HashMap::new())
+                .with_request_type::<SwapRequest>()
+                .with_request_type::<CapabilitiesRequest>()
+                .with_request_limit(RequestLimit {
+                    max_requests: limits.max_requests_per_minute,
+                    period: Duration::from_secs(60),
+                })
+                .with_connection_limit(limits.max_connections),
             mdns: Mdns::new()?,
+            kademlia: Kademlia::new(
+                local_peer_id.clone(),
+                MemoryStore::new(local_peer_id.clone()),
+            ),
+            ping: Ping::new(PingConfig::new()),
+            identify: Identify::new(
+                IDENTIFY_PROTOCOL_VERSION.into(),
+                format!("cnd/{}", crate::version()),
+                local_public_key,
+            ),
             bitcoin_connector,
-            ethereum_connector,
+            ethereum_connectors,
             state_store,
             seed,
             db,
             response_channels: Arc::new(Mutex::new(HashMap::new())),
             task_executor,
+            validation,
+            max_pending_proposals: limits.max_pending_proposals,
+            swap_requests,
+            mdns_peers: HashMap::new(),
+            local_peer_id,
+            capabilities,
+            peer_capabilities: Arc::new(Mutex::new(HashMap::new())),
+            peer_info: Arc::new(Mutex::new(HashMap::new())),
         })
     }
 
@@ -223,186 +588,621 @@ impl<TSubstream> ComitNode<TSubstream> {
         &mut self,
         peer_id: DialInformation,
         request: OutboundRequest,
-    ) -> Box<dyn Future<Item = Response, Error = ()> + Send> {
+    ) -> PendingResponse {
+        if let Some(address) = &peer_id.address_hint {
+            self.remember_address(peer_id.peer_id.clone(), address.clone());
+        }
+
+        if peer_id.address_hint.is_none() && !self.comit.knows_peer(&peer_id.peer_id) {
+            log::debug!("looking up the addresses of {} in the DHT", peer_id.peer_id);
+            self.kademlia
+                .get_record(&addresses_record_key(&peer_id.peer_id), Quorum::One);
+        }
+
         self.comit
             .send_request((peer_id.peer_id, peer_id.address_hint), request)
     }
+
+    /// Dial `peer_id` at `address` from now on.
+    pub fn add_address(&mut self, peer_id: PeerId, address: Multiaddr) {
+        self.comit.add_address(peer_id, address);
+    }
+
+    pub fn remove_address(&mut self, peer_id: &PeerId, address: &Multiaddr) {
+        self.comit.remove_address(peer_id, address);
+    }
+
+    /// Connect to `peer_id` at the addresses added through `add_address`.
+    pub fn dial(&mut self, peer_id: PeerId) {
+        self.comit.dial(peer_id);
+    }
+
+    /// Save `address` in the address book, so we can dial `peer_id` after a
+    /// restart.
+    fn remember_address(&self, peer_id: PeerId, address: Multiaddr) {
+        let db = self.db.clone();
+
+        self.task_executor.spawn(
+            async move {
+                if let Err(e) = db.save(PeerAddress { peer_id, address }).await {
+                    log::warn!("failed to save the address of a peer: {:#}", e);
+                }
+
+                Ok(())
+            }
+            .boxed()
+            .compat(),
+        );
+    }
+
+    pub fn ban_peer(&mut self, peer_id: PeerId) -> bool {
+        self.comit.ban_peer(peer_id)
+    }
+
+    pub fn unban_peer(&mut self, peer_id: &PeerId) -> bool {
+        self.comit.unban_peer(peer_id)
+    }
+
+    pub fn banned_peers(&self) -> impl Iterator<Item = &PeerId> {
+        self.comit.banned_peers()
+    }
+
+    pub fn add_bootstrap_peer(&mut self, peer_id: PeerId, address: Multiaddr) {
+        self.kademlia.add_address(&peer_id, address);
+    }
+
+    pub fn bootstrap(&mut self) {
+        self.kademlia.bootstrap();
+    }
+
+    /// Publish the addresses we can be reached at under our `PeerId`.
+    pub fn publish_addresses(&mut self, addresses: Vec<Multiaddr>) {
+        let record = addresses_record(&self.local_peer_id, &addresses);
+
+        if let Err(e) = self.kademlia.put_record(record, Quorum::One) {
+            log::warn!("failed to publish our addresses: {:?}", e);
+        }
+    }
+
+    /// Drops what we learned about `peer_id` while it was connected, it is
+    /// learned again on the next connection.
+    fn forget_peer(&self, peer_id: &PeerId) {
+        self.peer_capabilities.lock().unwrap().remove(peer_id);
+        self.peer_info.lock().unwrap().remove(peer_id);
+    }
+
+    /// Ask `peer_id` what it can swap, the answer is kept until the peer
+    /// disconnects.
+    fn request_capabilities(&mut self, peer_id: PeerId, address: Option<Multiaddr>) {
+        let response = self
+            .comit
+            .send_request((peer_id.clone(), address), Capabilities::request());
+        let peer_capabilities = self.peer_capabilities.clone();
+
+        self.task_executor.spawn(
+            async move {
+                let capabilities = response
+                    .compat()
+                    .await
+                    .map_err(anyhow::Error::from)
+                    .and_then(|response| Ok(Capabilities::from_response(&response)?));
+
+                match capabilities {
+                    Ok(capabilities) => {
+                        peer_capabilities
+                            .lock()
+                            .unwrap()
+                            .insert(peer_id, capabilities);
+                    }
+                    // Peers speaking version 1.0.0 of the protocol do not know the request.
+                    Err(e) => log::debug!("failed to get the capabilities of {}: {:#}", peer_id, e),
+                }
+
+                Ok(())
+            }
+            .boxed()
+            .compat(),
+        );
+    }
+
+    /// Peers found on the local network through mDNS, together with the
+    /// addresses they announced.
+    pub fn mdns_peers(&self) -> impl Iterator<Item = (PeerId, Vec<Multiaddr>)> + '_ {
+        self.mdns_peers
+            .iter()
+            .map(|(peer, addresses)| (peer.clone(), addresses.iter().cloned().collect()))
+    }
 }
 
+#[allow(clippy::cognitive_complexity, clippy::too_many_arguments)]
 async fn handle_request(
     db: Sqlite,
     seed: RootSeed,
     state_store: Arc<InMemoryStateStore>,
+    response_channels: ResponseChannels,
+    validation: Validation,
+    max_pending_proposals: usize,
     counterparty: PeerId,
-    mut request: ValidatedInboundRequest,
-) -> Result<SwapId, Response> {
-    match request.request_type() {
-        "SWAP" => {
-            let protocol: SwapProtocol = header!(request
-                .take_header("protocol")
-                .map(SwapProtocol::from_header));
-            match protocol {
-                SwapProtocol::Rfc003(hash_function) => {
-                    let swap_id = header!(request.take_header("id").map(SwapId::from_header));
-                    let alpha_ledger = header!(request
-                        .take_header("alpha_ledger")
-                        .map(LedgerKind::from_header));
-                    let beta_ledger = header!(request
-                        .take_header("beta_ledger")
-                        .map(LedgerKind::from_header));
-                    let alpha_asset = header!(request
-                        .take_header("alpha_asset")
-                        .map(AssetKind::from_header));
-                    let beta_asset = header!(request
-                        .take_header("beta_asset")
-                        .map(AssetKind::from_header));
-
-                    match (alpha_ledger, beta_ledger, alpha_asset, beta_asset) {
-                        (
-                            LedgerKind::Bitcoin(alpha_ledger),
-                            LedgerKind::Ethereum(beta_ledger),
-                            AssetKind::Bitcoin(alpha_asset),
-                            AssetKind::Ether(beta_asset),
-                        ) => {
-                            let request = rfc003_swap_request(
-                                swap_id,
-                                alpha_ledger,
-                                beta_ledger,
-                                alpha_asset,
-                                beta_asset,
-                                hash_function,
-                                body!(request.take_body_as()),
-                            );
-                            insert_state_for_bob(
-                                db.clone(),
-                                seed,
-                                state_store.clone(),
-                                counterparty,
-                                request,
-                            )
-                            .await
-                            .expect("Could not save state to db");
-                            Ok(swap_id)
-                        }
-                        (
-                            LedgerKind::Ethereum(alpha_ledger),
-                            LedgerKind::Bitcoin(beta_ledger),
-                            AssetKind::Ether(alpha_asset),
-                            AssetKind::Bitcoin(beta_asset),
-                        ) => {
-                            let request = rfc003_swap_request(
-                                swap_id,
-                                alpha_ledger,
-                                beta_ledger,
-                                alpha_asset,
-                                beta_asset,
-                                hash_function,
-                                body!(request.take_body_as()),
-                            );
-                            insert_state_for_bob(
-                                db.clone(),
-                                seed,
-                                state_store.clone(),
-                                counterparty,
-                                request,
-                            )
-                            .await
-                            .expect("Could not save state to db");
-                            Ok(swap_id)
-                        }
-                        (
-                            LedgerKind::Bitcoin(alpha_ledger),
-                            LedgerKind::Ethereum(beta_ledger),
-                            AssetKind::Bitcoin(alpha_asset),
-                            AssetKind::Erc20(beta_asset),
-                        ) => {
-                            let request = rfc003_swap_request(
-                                swap_id,
-                                alpha_ledger,
-                                beta_ledger,
-                                alpha_asset,
-                                beta_asset,
-                                hash_function,
-                                body!(request.take_body_as()),
-                            );
-                            insert_state_for_bob(
-                                db.clone(),
-                                seed,
-                                state_store.clone(),
-                                counterparty,
-                                request,
-                            )
-                            .await
-                            .expect("Could not save state to db");
-
-                            Ok(swap_id)
-                        }
-                        (
-                            LedgerKind::Ethereum(alpha_ledger),
-                            LedgerKind::Bitcoin(beta_ledger),
-                            AssetKind::Erc20(alpha_asset),
-                            AssetKind::Bitcoin(beta_asset),
-                        ) => {
-                            let request = rfc003_swap_request(
-                                swap_id,
-                                alpha_ledger,
-                                beta_ledger,
-                                alpha_asset,
-                                beta_asset,
-                                hash_function,
-                                body!(request.take_body_as()),
-                            );
-                            insert_state_for_bob(
-                                db.clone(),
-                                seed,
-                                state_store.clone(),
-                                counterparty,
-                                request,
-                            )
-                            .await
-                            .expect("Could not save state to db");
-                            Ok(swap_id)
-                        }
-                        (alpha_ledger, beta_ledger, alpha_asset, beta_asset) => {
-                            log::warn!(
-                                    "swapping {:?} to {:?} from {:?} to {:?} is currently not supported", alpha_asset, beta_asset, alpha_ledger, beta_ledger
-                                );
-
-                            let decline_body = DeclineResponseBody {
-                                reason: Some(SwapDeclineReason::UnsupportedSwap),
-                            };
-
-                            Err(Response::empty()
-                                .with_header(
-                                    "decision",
-                                    Decision::Declined
-                                        .to_header()
-                                        .expect("Decision should not fail to serialize"),
-                                )
-                                .with_body(serde_json::to_value(decline_body).expect(
-                                    "decline body should always serialize into serde_json::Value",
-                                )))
-                        }
-                    }
+    headers: SwapHeaders,
+    body: serde_json::Value,
+) -> Result<PendingProposal, Response> {
+    let SwapHeaders {
+        id: swap_id,
+        protocol,
+        alpha_ledger,
+        beta_ledger,
+        alpha_asset,
+        beta_asset,
+        valid_until,
+    } = headers;
+
+    match protocol {
+        SwapProtocol::Rfc003(hash_function) => {
+            if let Some(valid_until) = valid_until {
+                if valid_until <= Timestamp::now() {
+                    log::info!("request for swap {} has already lapsed", swap_id);
+                    return Err(rfc003_decline_response(rfc003::Decline {
+                        swap_id,
+                        reason: Some(SwapDeclineReason::ProposalExpired),
+                    }));
+                }
+            }
+
+            let pending_proposals = response_channels.lock().unwrap().len();
+            if pending_proposals >= max_pending_proposals
+                && Retrieve::get(&db, &swap_id).await.is_err()
+            {
+                log::warn!(
+                    "declining request for swap {} from {}, {} proposals are pending already",
+                    swap_id,
+                    counterparty,
+                    pending_proposals
+                );
+                return Err(rfc003_decline_response(rfc003::Decline {
+                    swap_id,
+                    reason: None,
+                }));
+            }
+
+            match (alpha_ledger, beta_ledger, alpha_asset, beta_asset) {
+                (
+                    LedgerKind::Bitcoin(alpha_ledger),
+                    LedgerKind::Ethereum(beta_ledger),
+                    AssetKind::Bitcoin(alpha_asset),
+                    AssetKind::Ether(beta_asset),
+                ) => {
+                    let request = rfc003_swap_request(
+                        swap_id,
+                        alpha_ledger,
+                        beta_ledger,
+                        alpha_asset,
+                        beta_asset,
+                        hash_function,
+                        body!(serde_json::from_value(body)),
+                    );
+                    handle_swap_request(
+                        db.clone(),
+                        seed,
+                        state_store.clone(),
+                        response_channels.clone(),
+                        validation,
+                        counterparty,
+                        request,
+                        valid_until,
+                    )
+                    .await
+                }
+                (
+                    LedgerKind::Ethereum(alpha_ledger),
+                    LedgerKind::Bitcoin(beta_ledger),
+                    AssetKind::Ether(alpha_asset),
+                    AssetKind::Bitcoin(beta_asset),
+                ) => {
+                    let request = rfc003_swap_request(
+                        swap_id,
+                        alpha_ledger,
+                        beta_ledger,
+                        alpha_asset,
+                        beta_asset,
+                        hash_function,
+                        body!(serde_json::from_value(body)),
+                    );
+                    handle_swap_request(
+                        db.clone(),
+                        seed,
+                        state_store.clone(),
+                        response_channels.clone(),
+                        validation,
+                        counterparty,
+                        request,
+                        valid_until,
+                    )
+                    .await
+                }
+                (
+                    LedgerKind::Bitcoin(alpha_ledger),
+                    LedgerKind::Ethereum(beta_ledger),
+                    AssetKind::Bitcoin(alpha_asset),
+                    AssetKind::Erc20(beta_asset),
+                ) => {
+                    let request = rfc003_swap_request(
+                        swap_id,
+                        alpha_ledger,
+                        beta_ledger,
+                        alpha_asset,
+                        beta_asset,
+                        hash_function,
+                        body!(serde_json::from_value(body)),
+                    );
+                    handle_swap_request(
+                        db.clone(),
+                        seed,
+                        state_store.clone(),
+                        response_channels.clone(),
+                        validation,
+                        counterparty,
+                        request,
+                        valid_until,
+                    )
+                    .await
+                }
+                (
+                    LedgerKind::Ethereum(alpha_ledger),
+                    LedgerKind::Bitcoin(beta_ledger),
+                    AssetKind::Erc20(alpha_asset),
+                    AssetKind::Bitcoin(beta_asset),
+                ) => {
+                    let request = rfc003_swap_request(
+                        swap_id,
+                        alpha_ledger,
+                        beta_ledger,
+                        alpha_asset,
+                        beta_asset,
+                        hash_function,
+                        body!(serde_json::from_value(body)),
+                    );
+                    handle_swap_request(
+                        db.clone(),
+                        seed,
+                        state_store.clone(),
+                        response_channels.clone(),
+                        validation,
+                        counterparty,
+                        request,
+                        valid_until,
+                    )
+                    .await
+                }
+                (
+                    LedgerKind::Ethereum(alpha_ledger),
+                    LedgerKind::Ethereum(beta_ledger),
+                    AssetKind::Ether(alpha_asset),
+                    AssetKind::Erc20(beta_asset),
+                ) => {
+                    let request = rfc003_swap_request(
+                        swap_id,
+                        alpha_ledger,
+                        beta_ledger,
+                        alpha_asset,
+                        beta_asset,
+                        hash_function,
+                        body!(serde_json::from_value(body)),
+                    );
+                    handle_swap_request(
+                        db.clone(),
+                        seed,
+                        state_store.clone(),
+                        response_channels.clone(),
+                        validation,
+                        counterparty,
+                        request,
+                        valid_until,
+                    )
+                    .await
+                }
+                (
+                    LedgerKind::Ethereum(alpha_ledger),
+                    LedgerKind::Ethereum(beta_ledger),
+                    AssetKind::Erc20(alpha_asset),
+                    AssetKind::Ether(beta_asset),
+                ) => {
+                    let request = rfc003_swap_request(
+                        swap_id,
+                        alpha_ledger,
+                        beta_ledger,
+                        alpha_asset,
+                        beta_asset,
+                        hash_function,
+                        body!(serde_json::from_value(body)),
+                    );
+                    handle_swap_request(
+                        db.clone(),
+                        seed,
+                        state_store.clone(),
+                        response_channels.clone(),
+                        validation,
+                        counterparty,
+                        request,
+                        valid_until,
+                    )
+                    .await
+                }
+                (
+                    LedgerKind::Ethereum(alpha_ledger),
+                    LedgerKind::Ethereum(beta_ledger),
+                    AssetKind::Erc20(alpha_asset),
+                    AssetKind::Erc20(beta_asset),
+                ) => {
+                    let request = rfc003_swap_request(
+                        swap_id,
+                        alpha_ledger,
+                        beta_ledger,
+                        alpha_asset,
+                        beta_asset,
+                        hash_function,
+                        body!(serde_json::from_value(body)),
+                    );
+                    handle_swap_request(
+                        db.clone(),
+                        seed,
+                        state_store.clone(),
+                        response_channels.clone(),
+                        validation,
+                        counterparty,
+                        request,
+                        valid_until,
+                    )
+                    .await
+                }
+                (alpha_ledger, beta_ledger, alpha_asset, beta_asset) => {
+                    log::warn!(
+                        "swapping {:?} to {:?} from {:?} to {:?} is currently not supported",
+                        alpha_asset,
+                        beta_asset,
+                        alpha_ledger,
+                        beta_ledger
+                    );
+
+                    let decline_body = DeclineResponseBody {
+                        reason: Some(SwapDeclineReason::UnsupportedSwap),
+                    };
+
+                    Err(Response::empty()
+                        .with_header(
+                            "decision",
+                            Decision::Declined
+                                .to_header()
+                                .expect("Decision should not fail to serialize"),
+                        )
+                        .with_body(
+                            serde_json::to_value(decline_body).expect(
+                                "decline body should always serialize into serde_json::Value",
+                            ),
+                        ))
                 }
             }
         }
+    }
+}
+
+/// Handles a swap request of a supported ledger and asset combination.
+///
+/// New requests that violate the safety invariants of RFC003 are declined
+/// right away. Requests that carry a validity window are declined once the
+/// window lapsed without an answer.
+///
+/// Alice re-sends the request of a swap that was not answered yet when she
+/// restarts. If Bob already answered the swap, the saved decision is sent
+/// again. Proposals Bob loads from the database are declined, the channel to
+/// answer them was lost.
+///
+/// The expiry of the returned proposal must only be spawned once its response
+/// channel is stored, otherwise it may find no channel to decline through.
+#[allow(clippy::too_many_arguments)]
+async fn handle_swap_request<AL: Ledger, BL: Ledger, AA: Asset, BA: Asset>(
+    db: Sqlite,
+    seed: RootSeed,
+    state_store: Arc<InMemoryStateStore>,
+    response_channels: ResponseChannels,
+    validation: Validation,
+    counterparty: PeerId,
+    swap_request: Request<AL, BL, AA, BA>,
+    valid_until: Option<Timestamp>,
+) -> Result<PendingProposal, Response>
+where
+    Sqlite: Save<Request<AL, BL, AA, BA>> + LoadAcceptedSwap<AL, BL, AA, BA>,
+{
+    let id = swap_request.swap_id;
+
+    let swap = match Retrieve::get(&db, &id).await {
+        Ok(swap) => swap,
+        Err(_) => {
+            let violations = validate(&swap_request, &validation, Timestamp::now());
+            if let Some(violation) = violations.first() {
+                log::warn!(
+                    "declining request for swap {} from {}: {}",
+                    id,
+                    counterparty,
+                    violation
+                );
+                return Err(rfc003_decline_response(rfc003::Decline {
+                    swap_id: id,
+                    reason: Some(violation.decline_reason()),
+                }));
+            }
+
+            insert_state_for_bob(
+                db.clone(),
+                seed,
+                state_store.clone(),
+                counterparty,
+                swap_request.clone(),
+            )
+            .await
+            .map_err(|e| decline_after_db_failure(id, e))?;
+
+            return Ok(PendingProposal::new(
+                db,
+                seed,
+                state_store,
+                response_channels,
+                swap_request,
+                valid_until,
+            ));
+        }
+    };
+
+    if swap.role != Role::Bob || swap.counterparty != counterparty {
+        log::warn!(
+            "{} sent a request for swap {} which belongs to another peer",
+            counterparty,
+            id
+        );
+        return Err(rfc003_decline_response(rfc003::Decline {
+            swap_id: id,
+            reason: None,
+        }));
+    }
+
+    let communication_state = db
+        .determine_communication_state(&id)
+        .await
+        .map_err(|e| decline_after_db_failure(id, e))?;
 
-        // This case is just catered for, because of rust. It can only happen
-        // if there is a typo in the request_type within the program. The request
-        // type is checked on the messaging layer and will be handled there if
-        // an unknown request_type is passed in.
-        request_type => {
-            log::warn!("request type '{}' is unknown", request_type);
+    match communication_state {
+        CommunicationState::Proposed => {
+            log::info!("Received request for pending swap {} again", id);
 
-            Err(Response::empty().with_header(
-                "decision",
-                Decision::Declined
-                    .to_header()
-                    .expect("Decision should not fail to serialize"),
+            Ok(PendingProposal::new(
+                db,
+                seed,
+                state_store,
+                response_channels,
+                swap_request,
+                valid_until,
             ))
         }
+        CommunicationState::Accepted => {
+            let (_, accept, _) = LoadAcceptedSwap::<AL, BL, AA, BA>::load_accepted_swap(&db, &id)
+                .await
+                .map_err(|e| decline_after_db_failure(id, e))?;
+            Err(rfc003_accept_response(accept))
+        }
+        CommunicationState::Declined | CommunicationState::TimedOut => {
+            let decline = db
+                .load_decline(&id)
+                .await
+                .map_err(|e| decline_after_db_failure(id, e))?;
+            Err(rfc003_decline_response(decline))
+        }
+    }
+}
+
+/// Declines the request for swap `id` because its state could not be saved or
+/// loaded.
+fn decline_after_db_failure(id: SwapId, e: anyhow::Error) -> Response {
+    log::error!("declining request for swap {}: {:?}", id, e);
+
+    rfc003_decline_response(rfc003::Decline {
+        swap_id: id,
+        reason: None,
+    })
+}
+
+/// A swap request that waits for an answer through its response channel.
+struct PendingProposal {
+    id: SwapId,
+    valid_until: Option<Timestamp>,
+    /// Declines the request once its validity window lapsed.
+    expiry: Option<BoxFuture<'static, ()>>,
+}
+
+impl PendingProposal {
+    fn new<AL: Ledger, BL: Ledger, AA: Asset, BA: Asset>(
+        db: Sqlite,
+        seed: RootSeed,
+        state_store: Arc<InMemoryStateStore>,
+        response_channels: ResponseChannels,
+        swap_request: Request<AL, BL, AA, BA>,
+        valid_until: Option<Timestamp>,
+    ) -> Self {
+        let id = swap_request.swap_id;
+
+        // Futures are lazy, the timer only starts once the expiry is spawned
+        let expiry = valid_until.map(|valid_until| {
+            decline_lapsed_proposal(
+                db,
+                seed,
+                state_store,
+                response_channels,
+                swap_request,
+                valid_until,
+            )
+            .unwrap_or_else(move |e: anyhow::Error| {
+                log::error!("failed to decline lapsed request for swap {}: {:?}", id, e)
+            })
+            .boxed()
+        });
+
+        Self {
+            id,
+            valid_until,
+            expiry,
+        }
+    }
+}
+
+/// Declines the swap request once its validity window lapsed, unless it was
+/// answered in the meantime.
+async fn decline_lapsed_proposal<AL: Ledger, BL: Ledger, AA: Asset, BA: Asset>(
+    db: Sqlite,
+    seed: RootSeed,
+    state_store: Arc<InMemoryStateStore>,
+    response_channels: ResponseChannels,
+    swap_request: Request<AL, BL, AA, BA>,
+    valid_until: Timestamp,
+) -> anyhow::Result<()> {
+    let id = swap_request.swap_id;
+
+    tokio::time::delay_for(duration_until(valid_until)).await;
+
+    let channel = {
+        let mut response_channels = response_channels.lock().unwrap();
+
+        // Alice may have sent the request again with a new validity window
+        let is_pending = response_channels
+            .get(&id)
+            .map_or(false, |(_, deadline)| *deadline == Some(valid_until));
+        if !is_pending {
+            return Ok(());
+        }
+
+        response_channels.remove(&id)
+    };
+
+    if let Some((channel, _)) = channel {
+        log::info!("request for swap {} lapsed, declining", id);
+
+        let decline = rfc003::Decline {
+            swap_id: id,
+            reason: Some(SwapDeclineReason::ProposalExpired),
+        };
+        Save::save(&db, decline).await?;
+
+        let state = bob::State::declined(swap_request, decline, seed.derive_swap_seed(id));
+        state_store.insert(id, state);
+
+        channel
+            .send(rfc003_decline_response(decline))
+            .map_err(|_| anyhow::anyhow!("failed to send response through channel"))?;
     }
+
+    Ok(())
+}
+
+fn duration_until(timestamp: Timestamp) -> Duration {
+    let seconds = u32::from(timestamp).saturating_sub(u32::from(Timestamp::now()));
+    Duration::from_secs(u64::from(seconds))
 }
 
 #[allow(clippy::type_complexity)]
@@ -452,8 +1252,131 @@ impl ComitPeers for Swarm {
     async fn comit_peers(
         &self,
     ) -> Box<dyn Iterator<Item = (PeerId, Vec<Multiaddr>)> + Send + 'static> {
-        let mut swarm = self.swarm.lock().unwrap();
-        Box::new(swarm.comit.connected_peers())
+        let peers = self
+            .ask(Command::ComitPeers)
+            .compat()
+            .await
+            .unwrap_or_default();
+
+        Box::new(peers.into_iter())
+    }
+}
+
+/// Add and remove entries of the address book we dial peers from.
+#[async_trait]
+pub trait ManagePeers {
+    async fn add_peer_address(&self, peer_id: PeerId, address: Multiaddr) -> anyhow::Result<()>;
+    async fn remove_peer(&self, peer_id: &PeerId) -> anyhow::Result<()>;
+}
+
+#[async_trait]
+impl ManagePeers for Swarm {
+    async fn add_peer_address(&self, peer_id: PeerId, address: Multiaddr) -> anyhow::Result<()> {
+        self.db
+            .save(PeerAddress {
+                peer_id: peer_id.clone(),
+                address: address.clone(),
+            })
+            .await?;
+
+        self.send_command(Command::AddAddress { peer_id, address });
+
+        Ok(())
+    }
+
+    async fn remove_peer(&self, peer_id: &PeerId) -> anyhow::Result<()> {
+        let addresses = self.db.delete_peer(peer_id).await?;
+
+        self.send_command(Command::RemoveAddresses {
+            peer_id: peer_id.clone(),
+            addresses,
+        });
+
+        Ok(())
+    }
+}
+
+/// Ignore all requests of some peers. Bans are kept across restarts.
+#[async_trait]
+pub trait BanPeers {
+    async fn banned_peers(&self) -> Vec<PeerId>;
+    async fn ban_peer(&self, peer_id: PeerId) -> anyhow::Result<()>;
+    /// Returns `false` if the peer was not banned.
+    async fn unban_peer(&self, peer_id: &PeerId) -> anyhow::Result<bool>;
+}
+
+#[async_trait]
+impl BanPeers for Swarm {
+    async fn banned_peers(&self) -> Vec<PeerId> {
+        self.ask(Command::BannedPeers)
+            .compat()
+            .await
+            .unwrap_or_default()
+    }
+
+    async fn ban_peer(&self, peer_id: PeerId) -> anyhow::Result<()> {
+        BanList::ban_peer(&self.db, &peer_id).await?;
+
+        self.send_command(Command::BanPeer(peer_id));
+
+        Ok(())
+    }
+
+    async fn unban_peer(&self, peer_id: &PeerId) -> anyhow::Result<bool> {
+        BanList::unban_peer(&self.db, peer_id).await?;
+
+        let unbanned = self
+            .ask(|unbanned| Command::UnbanPeer {
+                peer_id: peer_id.clone(),
+                unbanned,
+            })
+            .compat()
+            .await
+            .unwrap_or(false);
+
+        Ok(unbanned)
+    }
+}
+
+/// Get `PeerId`s of nodes found through local peer discovery.
+#[async_trait]
+pub trait DiscoveredPeers {
+    async fn discovered_peers(&self) -> Vec<(PeerId, Vec<Multiaddr>)>;
+}
+
+#[async_trait]
+impl DiscoveredPeers for Swarm {
+    async fn discovered_peers(&self) -> Vec<(PeerId, Vec<Multiaddr>)> {
+        self.ask(Command::DiscoveredPeers)
+            .compat()
+            .await
+            .unwrap_or_default()
+    }
+}
+
+/// What the peers we connected to told us they can swap.
+#[async_trait]
+pub trait PeerCapabilities {
+    async fn peer_capabilities(&self) -> HashMap<PeerId, Capabilities>;
+}
+
+#[async_trait]
+impl PeerCapabilities for Swarm {
+    async fn peer_capabilities(&self) -> HashMap<PeerId, Capabilities> {
+        self.peer_capabilities.lock().unwrap().clone()
+    }
+}
+
+/// What the peers we are connected to told us through ping and identify.
+#[async_trait]
+pub trait PeerInformation {
+    async fn peer_info(&self) -> HashMap<PeerId, PeerInfo>;
+}
+
+#[async_trait]
+impl PeerInformation for Swarm {
+    async fn peer_info(&self) -> HashMap<PeerId, PeerInfo> {
+        self.peer_info.lock().unwrap().clone()
     }
 }
 
@@ -466,12 +1389,10 @@ pub trait ListenAddresses {
 #[async_trait]
 impl ListenAddresses for Swarm {
     async fn listen_addresses(&self) -> Vec<Multiaddr> {
-        let swarm = self.swarm.lock().unwrap();
-
-        libp2p::Swarm::listeners(&swarm)
-            .chain(libp2p::Swarm::external_addresses(&swarm))
-            .cloned()
-            .collect()
+        self.ask(Command::ListenAddresses)
+            .compat()
+            .await
+            .unwrap_or_default()
     }
 }
 
@@ -484,20 +1405,25 @@ pub trait PendingRequestFor {
 #[async_trait]
 impl PendingRequestFor for Swarm {
     async fn pending_request_for(&self, swap: SwapId) -> Option<Sender<Response>> {
-        let swarm = self.swarm.lock().unwrap();
-        let mut response_channels = swarm.response_channels.lock().unwrap();
-        response_channels.remove(&swap)
+        let mut response_channels = self.response_channels.lock().unwrap();
+        response_channels.remove(&swap).map(|(channel, _)| channel)
     }
 }
 
+/// The answer of a peer to a swap request that has been sent to it.
+pub type ResponseFuture<AL, BL> =
+    BoxFuture<'static, Result<rfc003::Response<AL, BL>, RequestError>>;
+
 /// Send swap request to connected peer.
 #[async_trait]
 pub trait SendRequest {
+    /// Resolves once the request has been sent to the peer, the returned
+    /// future resolves to the peer's answer.
     async fn send_request<AL: rfc003::Ledger, BL: rfc003::Ledger, AA: Asset, BA: Asset>(
         &self,
         peer_identity: DialInformation,
         request: rfc003::Request<AL, BL, AA, BA>,
-    ) -> Result<rfc003::Response<AL, BL>, RequestError>;
+    ) -> Result<ResponseFuture<AL, BL>, RequestError>;
 }
 
 #[async_trait]
@@ -506,80 +1432,114 @@ impl SendRequest for Swarm {
         &self,
         dial_information: DialInformation,
         request: rfc003::Request<AL, BL, AA, BA>,
-    ) -> Result<rfc003::Response<AL, BL>, RequestError> {
+    ) -> Result<ResponseFuture<AL, BL>, RequestError> {
         let id = request.swap_id;
-        let request = build_outbound_request(request)
+        let valid_until = Timestamp::now().plus(PROPOSAL_VALIDITY);
+        let request = build_outbound_request(request, valid_until)
             .expect("constructing a frame::OutoingRequest should never fail!");
 
-        let result = {
-            let mut guard = self.swarm.lock().unwrap();
-            let swarm = &mut *guard;
+        log::debug!(
+            "Making swap request to {}: {:?}",
+            dial_information.clone(),
+            id,
+        );
 
-            log::debug!(
-                "Making swap request to {}: {:?}",
-                dial_information.clone(),
-                id,
-            );
+        let (sent, answer) = self
+            .ask(|response| Command::SendRequest {
+                dial_information: dial_information.clone(),
+                request,
+                response,
+            })
+            .compat()
+            .await
+            .map_err(|_| RequestError::Connecting)?
+            .split();
 
-            swarm
-                .send_request(dial_information.clone(), request)
-                .compat()
+        match tokio::time::timeout(duration_until(valid_until), sent.compat()).await {
+            Ok(Ok(())) => {}
+            Ok(Err(e)) => {
+                log::error!("Unable to send request to {}: {}", dial_information, e);
+                return Err(e.into());
+            }
+            Err(_) => {
+                log::info!("request for swap {} could not be sent in time", id);
+                return Err(RequestError::Timeout);
+            }
         }
-        .await;
 
-        match result {
-            Ok(mut response) => {
-                let decision = response
-                    .take_header("decision")
-                    .map(Decision::from_header)
-                    .map_or(Ok(None), |x| x.map(Some))
-                    .map_err(|e| {
-                        log::error!(
-                            "Could not deserialize header in response {:?}: {}",
-                            response,
-                            e,
-                        );
-                        RequestError::InvalidResponse
-                    })?;
-
-                match decision {
-                    Some(Decision::Accepted) => {
-                        match serde_json::from_value::<rfc003::messages::AcceptResponseBody<AL, BL>>(
-                            response.body().clone(),
-                        ) {
-                            Ok(body) => Ok(Ok(rfc003::Accept {
-                                swap_id: id,
-                                beta_ledger_refund_identity: body.beta_ledger_refund_identity,
-                                alpha_ledger_redeem_identity: body.alpha_ledger_redeem_identity,
-                            })),
-                            Err(_e) => Err(RequestError::InvalidResponse),
-                        }
-                    }
-
-                    Some(Decision::Declined) => {
-                        match serde_json::from_value::<rfc003::messages::DeclineResponseBody>(
-                            response.body().clone(),
-                        ) {
-                            Ok(body) => Ok(Err(rfc003::Decline {
-                                swap_id: id,
-                                reason: body.reason,
-                            })),
-                            Err(_e) => Err(RequestError::InvalidResponse),
-                        }
-                    }
+        let response = async move {
+            let result =
+                match tokio::time::timeout(duration_until(valid_until), answer.compat()).await {
+                    Ok(result) => result,
+                    Err(_) => Err(libp2p_comit::RequestError::Timeout),
+                };
 
-                    None => Err(RequestError::InvalidResponse),
+            match result {
+                Ok(response) => decode_response(id, response),
+                Err(libp2p_comit::RequestError::Timeout) => {
+                    log::info!("request for swap {} was not answered in time", id);
+                    Err(RequestError::Timeout)
+                }
+                Err(e) => {
+                    log::error!(
+                        "No response from {} for swap {}: {}",
+                        dial_information,
+                        id,
+                        e
+                    );
+                    Err(e.into())
                 }
             }
-            Err(e) => {
-                log::error!(
-                    "Unable to request over connection {:?}:{:?}",
-                    dial_information,
-                    e
-                );
-                Err(RequestError::Connection)
+        };
+
+        Ok(response.boxed())
+    }
+}
+
+fn decode_response<AL: rfc003::Ledger, BL: rfc003::Ledger>(
+    id: SwapId,
+    mut response: Response,
+) -> Result<rfc003::Response<AL, BL>, RequestError> {
+    let decision = response
+        .take_header("decision")
+        .map(Decision::from_header)
+        .map_or(Ok(None), |x| x.map(Some))
+        .map_err(|e| {
+            log::error!(
+                "Could not deserialize header in response {:?}: {}",
+                response,
+                e,
+            );
+            RequestError::InvalidResponse
+        })?;
+
+    match decision {
+        Some(Decision::Accepted) => {
+            match serde_json::from_value::<rfc003::messages::AcceptResponseBody<AL, BL>>(
+                response.body().clone(),
+            ) {
+                Ok(body) => Ok(Ok(rfc003::Accept {
+                    swap_id: id,
+                    beta_ledger_refund_identity: body.beta_ledger_refund_identity,
+                    alpha_ledger_redeem_identity: body.alpha_ledger_redeem_identity,
+                })),
+                Err(_e) => Err(RequestError::InvalidResponse),
             }
         }
+
+        Some(Decision::Declined) => {
+            match serde_json::from_value::<rfc003::messages::DeclineResponseBody>(
+                response.body().clone(),
+            ) {
+                Ok(body) => Ok(Err(rfc003::Decline {
+                    swap_id: id,
+                    reason: body.reason,
+                })),
+                Err(_e) => Err(RequestError::InvalidResponse),
+            }
+        }
+
+        None => Err(RequestError::InvalidResponse),
     }
 }
 
@@ -587,26 +1547,67 @@ impl<TSubstream> NetworkBehaviourEventProcess<BehaviourOutEvent> for ComitNode<T
     fn inject_event(&mut self, event: BehaviourOutEvent) {
         match event {
             BehaviourOutEvent::PendingInboundRequest { request, peer_id } => {
-                let PendingInboundRequest { request, channel } = request;
+                if request.request.request_type() == CapabilitiesRequest::NAME {
+                    if let Ok(PendingTypedRequest { channel, .. }) =
+                        request.decode::<CapabilitiesRequest>()
+                    {
+                        channel
+                            .send(self.capabilities.to_response())
+                            .unwrap_or_else(|_| {
+                                log::debug!("failed to send response through channel")
+                            });
+                    }
+                    return;
+                }
+
+                // Malformed requests have been declined by `decode` already.
+                let PendingTypedRequest {
+                    headers,
+                    body,
+                    channel,
+                } = match request.decode::<SwapRequest>() {
+                    Ok(request) => request,
+                    Err(_) => return,
+                };
 
                 self.task_executor.spawn(
                     handle_request(
                         self.db.clone(),
                         self.seed,
                         self.state_store.clone(),
+                        self.response_channels.clone(),
+                        self.validation.clone(),
+                        self.max_pending_proposals,
                         peer_id,
-                        request,
+                        headers,
+                        body,
                     )
                     .boxed()
                     .compat()
                     .then({
                         let response_channels = self.response_channels.clone();
+                        let swap_requests = self.swap_requests.clone();
 
                         move |result| {
                             match result {
-                                Ok(id) => {
-                                    let mut response_channels = response_channels.lock().unwrap();
-                                    response_channels.insert(id, channel);
+                                Ok(PendingProposal {
+                                    id,
+                                    valid_until,
+                                    expiry,
+                                }) => {
+                                    {
+                                        let mut response_channels =
+                                            response_channels.lock().unwrap();
+                                        response_channels.insert(id, (channel, valid_until));
+                                    }
+                                    // the request can only be answered or declined once the
+                                    // channel is known
+                                    if let Some(expiry) = expiry {
+                                        tokio::task::spawn(expiry);
+                                    }
+                                    swap_requests.send(id).unwrap_or_else(|_| {
+                                        log::trace!("no policy to answer swap requests with")
+                                    });
                                 }
                                 Err(response) => channel.send(response).unwrap_or_else(|_| {
                                     log::debug!("failed to send response through channel")
@@ -617,12 +1618,213 @@ impl<TSubstream> NetworkBehaviourEventProcess<BehaviourOutEvent> for ComitNode<T
                     }),
                 );
             }
+            BehaviourOutEvent::InboundNotification {
+                notification,
+                peer_id,
+            } => {
+                // We do not register any notification types yet, so this is never emitted.
+                log::debug!("ignoring notification {:?} from {}", notification, peer_id);
+            }
+            BehaviourOutEvent::PeerConnected { peer_id, endpoint } => {
+                let address = dialable_address(&endpoint).cloned();
+
+                if let Some(address) = &address {
+                    self.remember_address(peer_id.clone(), address.clone());
+                }
+
+                let known = self
+                    .peer_capabilities
+                    .lock()
+                    .unwrap()
+                    .contains_key(&peer_id);
+                if !known {
+                    self.request_capabilities(peer_id, address);
+                }
+            }
+            BehaviourOutEvent::PeerDisconnected { peer_id } => {
+                log::debug!("forgetting the capabilities and info of {}", peer_id);
+
+                self.forget_peer(&peer_id);
+            }
+        }
+    }
+}
+
+/// The address a connection was established over, if it can be used to dial
+/// the peer again.
+///
+/// The source address of an inbound connection usually is an ephemeral port
+/// the peer does not listen on.
+fn dialable_address(endpoint: &ConnectedPoint) -> Option<&Multiaddr> {
+    match endpoint {
+        ConnectedPoint::Dialer { address } => Some(address),
+        ConnectedPoint::Listener { .. } => None,
+    }
+}
+
+impl<TSubstream> NetworkBehaviourEventProcess<MdnsEvent> for ComitNode<TSubstream> {
+    fn inject_event(&mut self, event: MdnsEvent) {
+        match event {
+            MdnsEvent::Discovered(addresses) => {
+                for (peer, address) in addresses {
+                    log::debug!("discovered {} at {} via mdns", peer, address);
+
+                    self.comit.add_address(peer.clone(), address.clone());
+                    self.remember_address(peer.clone(), address.clone());
+                    self.mdns_peers
+                        .entry(peer)
+                        .or_insert_with(HashSet::new)
+                        .insert(address);
+                }
+            }
+            MdnsEvent::Expired(addresses) => {
+                for (peer, address) in addresses {
+                    log::debug!("mdns record of {} at {} expired", peer, address);
+
+                    self.comit.remove_address(&peer, &address);
+                    if let Some(known_addresses) = self.mdns_peers.get_mut(&peer) {
+                        known_addresses.remove(&address);
+
+                        if known_addresses.is_empty() {
+                            self.mdns_peers.remove(&peer);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl<TSubstream> NetworkBehaviourEventProcess<PingEvent> for ComitNode<TSubstream> {
+    fn inject_event(&mut self, event: PingEvent) {
+        let PingEvent { peer, result } = event;
+        let mut peer_info = self.peer_info.lock().unwrap();
+
+        match result {
+            Ok(PingSuccess::Ping { rtt }) => {
+                peer_info.entry(peer).or_default().latency = Some(rtt);
+            }
+            Ok(PingSuccess::Pong) => {}
+            Err(e) => {
+                log::debug!("failed to ping {}: {:?}", peer, e);
+
+                if let Some(info) = peer_info.get_mut(&peer) {
+                    info.latency = None;
+                }
+            }
+        }
+    }
+}
+
+impl<TSubstream> NetworkBehaviourEventProcess<IdentifyEvent> for ComitNode<TSubstream> {
+    fn inject_event(&mut self, event: IdentifyEvent) {
+        match event {
+            IdentifyEvent::Received {
+                peer_id,
+                info,
+                observed_addr,
+            } => {
+                log::debug!("{} runs {}", peer_id, info.agent_version);
+
+                // Announced addresses are only dialed, they are saved in the address book once
+                // a connection to them succeeds.
+                for address in announced_addresses(info.listen_addrs) {
+                    self.comit.add_address(peer_id.clone(), address);
+                }
+
+                let mut peer_info = self.peer_info.lock().unwrap();
+                let peer_info = peer_info.entry(peer_id).or_default();
+                peer_info.agent_version = Some(info.agent_version);
+                peer_info.observed_address = Some(observed_addr);
+            }
+            IdentifyEvent::Sent { .. } => {}
+            IdentifyEvent::Error { peer_id, error } => {
+                log::debug!("failed to identify {}: {:?}", peer_id, error)
+            }
         }
     }
 }
 
-impl<TSubstream> NetworkBehaviourEventProcess<libp2p::mdns::MdnsEvent> for ComitNode<TSubstream> {
-    fn inject_event(&mut self, _event: libp2p::mdns::MdnsEvent) {}
+impl<TSubstream> NetworkBehaviourEventProcess<KademliaEvent> for ComitNode<TSubstream> {
+    fn inject_event(&mut self, event: KademliaEvent) {
+        match event {
+            KademliaEvent::BootstrapResult(Err(e)) => {
+                log::warn!("failed to bootstrap the DHT: {:?}", e)
+            }
+            // Records are not signed, but connecting to an address that does not belong to the
+            // peer fails because the peer's identity is checked during the handshake. The
+            // addresses are only saved once a connection to them succeeded.
+            KademliaEvent::GetRecordResult(Ok(GetRecordOk { records })) => {
+                for record in records {
+                    let peer_id = match peer_id_of_record_key(&record.key) {
+                        Some(peer_id) => peer_id,
+                        None => continue,
+                    };
+
+                    match addresses_of_record(&record) {
+                        Ok(addresses) => {
+                            for address in addresses {
+                                log::debug!("found {} at {} in the DHT", peer_id, address);
+                                self.comit.add_address(peer_id.clone(), address);
+                            }
+                        }
+                        Err(e) => {
+                            log::debug!("invalid address record for {}: {:#}", peer_id, e);
+                            self.comit.cancel_dial(&peer_id);
+                        }
+                    }
+                }
+            }
+            KademliaEvent::GetRecordResult(Err(e)) => {
+                log::debug!("failed to look up addresses in the DHT: {:?}", e);
+
+                // Requests to a peer we only looked up because we did not know its addresses
+                // cannot be sent.
+                if let Some(peer_id) = peer_id_of_record_key(e.key()) {
+                    self.comit.cancel_dial(&peer_id);
+                }
+            }
+            KademliaEvent::PutRecordResult(Err(e)) => {
+                log::debug!("failed to publish our addresses to the DHT: {:?}", e)
+            }
+            _ => {}
+        }
+    }
+}
+
+pub fn rfc003_accept_response<AL: rfc003::Ledger, BL: rfc003::Ledger>(
+    message: rfc003::messages::Accept<AL, BL>,
+) -> Response {
+    Response::empty()
+        .with_header(
+            "decision",
+            Decision::Accepted
+                .to_header()
+                .expect("Decision should not fail to serialize"),
+        )
+        .with_body(
+            serde_json::to_value(rfc003::messages::AcceptResponseBody::<AL, BL> {
+                beta_ledger_refund_identity: message.beta_ledger_refund_identity,
+                alpha_ledger_redeem_identity: message.alpha_ledger_redeem_identity,
+            })
+            .expect("body should always serialize into serde_json::Value"),
+        )
+}
+
+pub fn rfc003_decline_response(message: rfc003::messages::Decline) -> Response {
+    Response::empty()
+        .with_header(
+            "decision",
+            Decision::Declined
+                .to_header()
+                .expect("Decision shouldn't fail to serialize"),
+        )
+        .with_body(
+            serde_json::to_value(rfc003::messages::DeclineResponseBody {
+                reason: message.reason,
+            })
+            .expect("decline body should always serialize into serde_json::Value"),
+        )
 }
 
 fn rfc003_swap_request<AL: rfc003::Ledger, BL: rfc003::Ledger, AA: Asset, BA: Asset>(
@@ -651,6 +1853,7 @@ fn rfc003_swap_request<AL: rfc003::Ledger, BL: rfc003::Ledger, AA: Asset, BA: As
 
 fn build_outbound_request<AL: rfc003::Ledger, BL: rfc003::Ledger, AA: Asset, BA: Asset>(
     request: rfc003::Request<AL, BL, AA, BA>,
+    valid_until: Timestamp,
 ) -> Result<frame::OutboundRequest, serde_json::Error> {
     let alpha_ledger_refund_identity = request.alpha_ledger_refund_identity;
     let beta_ledger_redeem_identity = request.beta_ledger_redeem_identity;
@@ -666,6 +1869,8 @@ fn build_outbound_request<AL: rfc003::Ledger, BL: rfc003::Ledger, AA: Asset, BA:
         .with_header("alpha_asset", request.alpha_asset.into().to_header()?)
         .with_header("beta_asset", request.beta_asset.into().to_header()?)
         .with_header("protocol", protocol.to_header()?)
+        // optional so that peers which do not expire requests can ignore it
+        .with_header("_valid_until", valid_until.to_header()?)
         .with_body(serde_json::to_value(rfc003::messages::RequestBody::<
             AL,
             BL,
@@ -677,3 +1882,113 @@ fn build_outbound_request<AL: rfc003::Ledger, BL: rfc003::Ledger, AA: Asset, BA:
             secret_hash,
         })?))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use spectral::prelude::*;
+
+    #[test]
+    fn given_an_inbound_connection_does_not_remember_its_source_address() {
+        let endpoint = ConnectedPoint::Listener {
+            local_addr: "/ip4/127.0.0.1/tcp/9939".parse().unwrap(),
+            send_back_addr: "/ip4/127.0.0.1/tcp/52114".parse().unwrap(),
+        };
+
+        assert_that(&dialable_address(&endpoint)).is_none();
+    }
+
+    #[test]
+    fn given_an_outbound_connection_remembers_the_dialed_address() {
+        let address: Multiaddr = "/ip4/127.0.0.1/tcp/9939".parse().unwrap();
+        let endpoint = ConnectedPoint::Dialer {
+            address: address.clone(),
+        };
+
+        assert_that(&dialable_address(&endpoint).cloned())
+            .is_some()
+            .is_equal_to(address);
+    }
+
+    #[test]
+    fn given_announced_addresses_keeps_only_global_ones() {
+        let addresses = vec![
+            "/ip4/127.0.0.1/tcp/9939",
+            "/ip4/192.168.1.10/tcp/9939",
+            "/ip4/0.0.0.0/tcp/9939",
+            "/ip6/::1/tcp/9939",
+            "/ip6/fe80::1/tcp/9939",
+            "/memory/1",
+            "/ip4/1.2.3.4/tcp/9939",
+            "/ip6/2001:4860::1/tcp/9939",
+            "/dns4/example.com/tcp/9939",
+        ]
+        .into_iter()
+        .map(|address| address.parse().unwrap())
+        .collect();
+
+        let expected: Vec<Multiaddr> = vec![
+            "/ip4/1.2.3.4/tcp/9939".parse().unwrap(),
+            "/ip6/2001:4860::1/tcp/9939".parse().unwrap(),
+            "/dns4/example.com/tcp/9939".parse().unwrap(),
+        ];
+        assert_that(&announced_addresses(addresses)).is_equal_to(expected);
+    }
+
+    #[test]
+    fn given_many_announced_addresses_keeps_only_some() {
+        let addresses = (0..20)
+            .map(|port| format!("/ip4/1.2.3.4/tcp/{}", port).parse().unwrap())
+            .collect();
+
+        assert_that(&announced_addresses(addresses)).has_length(MAX_ANNOUNCED_ADDRESSES);
+    }
+
+    #[test]
+    fn given_an_address_ending_with_a_peer_id_splits_it_off() {
+        let peer_id = PeerId::random();
+        let address: Multiaddr = format!("/ip4/127.0.0.1/tcp/9939/p2p/{}", peer_id)
+            .parse()
+            .unwrap();
+
+        assert_that(&split_peer_id(address))
+            .is_ok()
+            .is_equal_to((peer_id, "/ip4/127.0.0.1/tcp/9939".parse().unwrap()));
+    }
+
+    #[test]
+    fn given_an_address_without_a_peer_id_fails_to_split_it() {
+        let address: Multiaddr = "/ip4/127.0.0.1/tcp/9939".parse().unwrap();
+
+        assert_that(&split_peer_id(address)).is_err();
+    }
+
+    #[test]
+    fn given_a_published_record_resolves_the_addresses_of_the_peer() {
+        let peer_id = PeerId::random();
+        let addresses: Vec<Multiaddr> = vec![
+            "/ip4/127.0.0.1/tcp/9939".parse().unwrap(),
+            "/dns4/example.com/tcp/9939".parse().unwrap(),
+        ];
+        let GetRecordOk { records } = GetRecordOk {
+            records: vec![addresses_record(&peer_id, &addresses)],
+        };
+
+        assert_that(&peer_id_of_record_key(&records[0].key))
+            .is_some()
+            .is_equal_to(peer_id);
+        assert_that(&addresses_of_record(&records[0]))
+            .is_ok()
+            .is_equal_to(addresses);
+    }
+
+    #[test]
+    fn given_a_record_without_addresses_fails_to_resolve_them() {
+        let peer_id = PeerId::random();
+        let empty = addresses_record(&peer_id, &[]);
+        let garbage = Record::new(addresses_record_key(&peer_id), b"garbage".to_vec());
+
+        assert_that(&addresses_of_record(&empty)).is_err();
+        assert_that(&addresses_of_record(&garbage)).is_err();
+    }
+}
@@ -0,0 +1,92 @@
+use crate::{
+    asset::AssetKind,
+    libp2p_comit_ext::{from_header, optional_from_header, ToHeader},
+    network::PROPOSAL_VALIDITY,
+    swap_protocols::{
+        rfc003::messages::{Decision, DeclineResponseBody, SwapDeclineReason},
+        LedgerKind, SwapId, SwapProtocol,
+    },
+    timestamp::Timestamp,
+};
+use libp2p_comit::{frame::Response, MalformedRequest, RequestType};
+use serde::Deserialize;
+use std::time::Duration;
+
+/// A request to start a swap, sent by Alice to Bob.
+#[derive(Clone, Copy, Debug)]
+pub struct SwapRequest;
+
+#[derive(Clone, Copy, Debug, Deserialize)]
+pub struct SwapHeaders {
+    #[serde(deserialize_with = "from_header")]
+    pub id: SwapId,
+    #[serde(deserialize_with = "from_header")]
+    pub protocol: SwapProtocol,
+    #[serde(deserialize_with = "from_header")]
+    pub alpha_ledger: LedgerKind,
+    #[serde(deserialize_with = "from_header")]
+    pub beta_ledger: LedgerKind,
+    #[serde(deserialize_with = "from_header")]
+    pub alpha_asset: AssetKind,
+    #[serde(deserialize_with = "from_header")]
+    pub beta_asset: AssetKind,
+    /// Sent as the optional header `_valid_until`.
+    #[serde(default, deserialize_with = "optional_from_header")]
+    pub valid_until: Option<Timestamp>,
+}
+
+impl RequestType for SwapRequest {
+    const NAME: &'static str = "SWAP";
+    const HEADERS: &'static [&'static str] = &[
+        "id",
+        "alpha_ledger",
+        "beta_ledger",
+        "alpha_asset",
+        "beta_asset",
+        "protocol",
+    ];
+    /// Swap requests are answered by the counterparty within the validity of
+    /// the proposal.
+    const TIMEOUT: Option<Duration> = Some(Duration::from_secs(PROPOSAL_VALIDITY as u64));
+
+    type Headers = SwapHeaders;
+    /// The shape of the body depends on the ledgers and assets in the headers.
+    type Body = serde_json::Value;
+
+    fn decline(error: &MalformedRequest) -> Response {
+        let reason = match error {
+            MalformedRequest::MissingHeader(_) => SwapDeclineReason::MissingMandatoryHeader,
+            _ => SwapDeclineReason::BadJsonField,
+        };
+        log::info!("declining malformed swap request: {}", error);
+
+        Response::empty()
+            .with_header(
+                "decision",
+                Decision::Declined
+                    .to_header()
+                    .expect("Decision should not fail to serialize"),
+            )
+            .with_body(
+                serde_json::to_value(DeclineResponseBody {
+                    reason: Some(reason),
+                })
+                .expect("decline body should always serialize into serde_json::Value"),
+            )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use spectral::prelude::*;
+
+    #[test]
+    fn given_a_missing_header_declines_with_missing_mandatory_header() {
+        let response = SwapRequest::decline(&MalformedRequest::MissingHeader("alpha_ledger"));
+
+        assert_that(response.body()).is_equal_to(&serde_json::json!({
+            "reason": "missing-mandatory-header"
+        }));
+    }
+}
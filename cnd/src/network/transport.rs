@@ -1,47 +1,172 @@
+use crate::config::Authentication;
 use libp2p::{
     core::{
-        either::EitherError,
+        either::EitherOutput,
         muxing::StreamMuxerBox,
-        transport::{boxed::Boxed, timeout::TransportTimeoutError},
-        upgrade::{SelectUpgrade, Version},
-        UpgradeError,
+        transport::boxed::Boxed,
+        upgrade::{InboundUpgradeExt, OutboundUpgradeExt, SelectUpgrade, Version},
     },
-    dns::{DnsConfig, DnsErr},
+    dns::DnsConfig,
     identity,
     mplex::MplexConfig,
-    secio::{SecioConfig, SecioError},
+    noise::{self, NoiseConfig, X25519},
+    secio::SecioConfig,
     tcp::TcpConfig,
+    websocket::WsConfig,
     yamux, PeerId, Transport,
 };
 use std::{io, time::Duration};
 
-pub type ComitTransport = Boxed<
-    (PeerId, StreamMuxerBox),
-    TransportTimeoutError<
-        EitherError<
-            EitherError<DnsErr<io::Error>, UpgradeError<SecioError>>,
-            UpgradeError<EitherError<io::Error, io::Error>>,
-        >,
-    >,
->;
+pub type ComitTransport = Boxed<(PeerId, StreamMuxerBox), io::Error>;
+
+/// Finishes a transport that is authenticated with the given upgrade.
+macro_rules! comit_transport {
+    ($authentication:expr) => {{
+        let dns = DnsConfig::new(TcpConfig::new().nodelay(true));
+
+        dns.clone()
+            .or_transport(WsConfig::new(dns))
+            .upgrade(Version::V1)
+            .authenticate($authentication)
+            .multiplex(SelectUpgrade::new(
+                yamux::Config::default(),
+                MplexConfig::new(),
+            ))
+            .map(|(peer, muxer), _| (peer, StreamMuxerBox::new(muxer)))
+            .timeout(Duration::from_secs(20))
+            .map_err(|e| io::Error::new(io::ErrorKind::Other, e))
+            .boxed()
+    }};
+}
 
 /// Builds a libp2p transport with the following features:
 /// - TcpConnection
+/// - WebSocket connections on top of TCP, for `/ws` addresses
 /// - DNS name resolution
-/// - authentication via secio
+/// - authentication via noise or secio, as configured
 /// - multiplexing via yamux or mplex
-pub fn build_comit_transport(keypair: identity::Keypair) -> ComitTransport {
-    let transport = TcpConfig::new().nodelay(true);
-    let transport = DnsConfig::new(transport);
-
-    transport
-        .upgrade(Version::V1)
-        .authenticate(SecioConfig::new(keypair))
-        .multiplex(SelectUpgrade::new(
-            yamux::Config::default(),
-            MplexConfig::new(),
+pub fn build_comit_transport(
+    keypair: identity::Keypair,
+    authentication: Authentication,
+) -> anyhow::Result<ComitTransport> {
+    let noise_keys = noise::Keypair::<X25519>::new()
+        .into_authentic(&keypair)
+        .map_err(|e| anyhow::anyhow!("failed to derive noise keys: {:?}", e))?;
+    let noise = NoiseConfig::xx(noise_keys).into_authenticated();
+    let secio = SecioConfig::new(keypair);
+
+    Ok(match authentication {
+        // The order of the protocols is the order of preference during the negotiation.
+        Authentication::NoiseOrSecio => comit_transport!(SelectUpgrade::new(noise, secio)
+            .map_inbound(merge_authenticated)
+            .map_outbound(merge_authenticated)),
+        Authentication::Noise => comit_transport!(noise),
+        Authentication::Secio => comit_transport!(secio),
+    })
+}
+
+fn merge_authenticated<A, B>(
+    output: EitherOutput<(PeerId, A), (PeerId, B)>,
+) -> (PeerId, EitherOutput<A, B>) {
+    match output {
+        EitherOutput::First((peer, connection)) => (peer, EitherOutput::First(connection)),
+        EitherOutput::Second((peer, connection)) => (peer, EitherOutput::Second(connection)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::{Future, Stream};
+    use libp2p::core::transport::ListenerEvent;
+    use spectral::prelude::*;
+    use tokio_compat::runtime::Runtime;
+
+    fn handshake(listener: Authentication, dialer: Authentication) -> Result<PeerId, io::Error> {
+        handshake_on(listener, dialer, "/ip4/127.0.0.1/tcp/0")
+    }
+
+    fn handshake_on(
+        listener: Authentication,
+        dialer: Authentication,
+        address: &str,
+    ) -> Result<PeerId, io::Error> {
+        let mut runtime = Runtime::new()?;
+
+        let listener_keypair = identity::Keypair::generate_ed25519();
+        let listener_peer_id = listener_keypair.public().into_peer_id();
+
+        let listener = build_comit_transport(listener_keypair, listener)
+            .unwrap()
+            .listen_on(address.parse().unwrap())
+            .unwrap();
+        let (event, listener) = runtime
+            .block_on(listener.into_future())
+            .map_err(|(e, _)| e)?;
+        let address = event
+            .and_then(ListenerEvent::into_new_address)
+            .expect("listener reports its address first");
+
+        let accept = listener
+            .filter_map(ListenerEvent::into_upgrade)
+            .into_future()
+            .map_err(|(e, _)| e)
+            .and_then(|(upgrade, _)| upgrade.expect("a connection is accepted").0);
+        let dial = build_comit_transport(identity::Keypair::generate_ed25519(), dialer)
+            .unwrap()
+            .dial(address)
+            .unwrap();
+
+        let ((peer_id, _), _) = runtime.block_on(dial.join(accept))?;
+        assert_eq!(peer_id, listener_peer_id);
+
+        Ok(peer_id)
+    }
+
+    #[test]
+    fn nodes_preferring_noise_connect_to_each_other() {
+        assert_that(&handshake(
+            Authentication::NoiseOrSecio,
+            Authentication::NoiseOrSecio,
+        ))
+        .is_ok();
+    }
+
+    #[test]
+    fn nodes_preferring_noise_fall_back_to_secio() {
+        assert_that(&handshake(
+            Authentication::NoiseOrSecio,
+            Authentication::Secio,
+        ))
+        .is_ok();
+        assert_that(&handshake(
+            Authentication::Secio,
+            Authentication::NoiseOrSecio,
+        ))
+        .is_ok();
+    }
+
+    #[test]
+    fn nodes_preferring_noise_connect_to_noise_only_nodes() {
+        assert_that(&handshake(
+            Authentication::NoiseOrSecio,
+            Authentication::Noise,
+        ))
+        .is_ok();
+    }
+
+    #[test]
+    fn noise_only_and_secio_only_nodes_cannot_connect() {
+        assert_that(&handshake(Authentication::Noise, Authentication::Secio)).is_err();
+    }
+
+    #[test]
+    fn nodes_connect_over_websockets() {
+        assert_that(&handshake_on(
+            Authentication::NoiseOrSecio,
+            Authentication::NoiseOrSecio,
+            "/ip4/127.0.0.1/tcp/0/ws",
         ))
-        .map(|(peer, muxer), _| (peer, StreamMuxerBox::new(muxer)))
-        .timeout(Duration::from_secs(20))
-        .boxed()
+        .is_ok();
+    }
 }
@@ -0,0 +1,265 @@
+use crate::{
+    asset::{Asset, AssetKind},
+    config::{Policy, TradingPair},
+    db::{self, DetermineTypes},
+    ethereum::U256,
+    http_api::{
+        action::ActionExecutionParameters,
+        routes::rfc003::{
+            decline::{DeclineBody, HttpApiSwapDeclineReason},
+            handlers::handle_action,
+        },
+    },
+    swap_protocols::{
+        rfc003::{actions::ActionKind, state_store::StateStore, Ledger, Request},
+        Facade, LedgerKind, SwapId,
+    },
+    timestamp::Timestamp,
+};
+use tokio::sync::mpsc::UnboundedReceiver;
+use warp::http;
+
+/// The answer cnd gives to a swap request on behalf of the user.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Verdict {
+    Accept,
+    Decline(HttpApiSwapDeclineReason),
+}
+
+/// Answers every swap request received through `swap_requests` according to
+/// the given policy.
+///
+/// The answer is executed as if the user invoked the accept or decline action
+/// of the swap through the HTTP API.
+pub async fn answer_swap_requests(
+    policy: Policy,
+    dependencies: Facade,
+    mut swap_requests: UnboundedReceiver<SwapId>,
+) {
+    while let Some(id) = swap_requests.recv().await {
+        if let Err(e) = answer_swap_request(&policy, dependencies.clone(), id).await {
+            log::error!("failed to answer request for swap {}: {:?}", id, e);
+        }
+    }
+}
+
+async fn answer_swap_request(
+    policy: &Policy,
+    dependencies: Facade,
+    id: SwapId,
+) -> anyhow::Result<()> {
+    let types = dependencies.determine_types(&id).await?;
+
+    let verdict = with_swap_types!(types, {
+        let state = StateStore::get::<ROLE>(&dependencies, &id)?
+            .ok_or_else(|| anyhow::anyhow!("state store did not contain an entry for {}", id))?;
+
+        evaluate(policy, &state.request(), Timestamp::now())
+    });
+
+    log::info!("Answering request for swap {} with {:?}", id, verdict);
+
+    let (action_kind, body) = match verdict {
+        Verdict::Accept => (
+            ActionKind::Accept,
+            serde_json::json!({
+                "alpha_ledger_redeem_identity": policy.ethereum_identity,
+                "beta_ledger_refund_identity": policy.ethereum_identity,
+            }),
+        ),
+        Verdict::Decline(reason) => (
+            ActionKind::Decline,
+            serde_json::to_value(DeclineBody {
+                reason: Some(reason),
+            })?,
+        ),
+    };
+
+    handle_action(
+        http::Method::POST,
+        id,
+        action_kind,
+        body,
+        ActionExecutionParameters::None {},
+        dependencies,
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Decides whether the given request should be accepted.
+///
+/// The request is checked against the first trading pair that matches its
+/// ledgers and assets, requests without a matching pair are declined.
+pub fn evaluate<AL: Ledger, BL: Ledger, AA: Asset, BA: Asset>(
+    policy: &Policy,
+    request: &Request<AL, BL, AA, BA>,
+    now: Timestamp,
+) -> Verdict {
+    let alpha_ledger: LedgerKind = request.alpha_ledger.into();
+    let beta_ledger: LedgerKind = request.beta_ledger.into();
+    let alpha_asset: AssetKind = request.alpha_asset.into();
+    let beta_asset: AssetKind = request.beta_asset.into();
+
+    let pair = policy.pairs.iter().find(|pair| {
+        pair.alpha_ledger == db::LedgerKind::from(alpha_ledger)
+            && pair.beta_ledger == db::LedgerKind::from(beta_ledger)
+            && pair.alpha_asset == db::AssetKind::from(alpha_asset)
+            && pair.beta_asset == db::AssetKind::from(beta_asset)
+    });
+
+    let pair = match pair {
+        Some(pair) => pair,
+        None => return Verdict::Decline(HttpApiSwapDeclineReason::UnsupportedSwap),
+    };
+
+    let alpha_quantity = quantity(alpha_asset);
+    let beta_quantity = quantity(beta_asset);
+
+    if !is_within_limits(pair, alpha_quantity) {
+        return Verdict::Decline(HttpApiSwapDeclineReason::UnsatisfactoryQuantity);
+    }
+
+    if let Some(min_rate) = pair.min_rate {
+        let rate = to_f64(beta_quantity) / to_f64(alpha_quantity);
+
+        if rate < min_rate {
+            return Verdict::Decline(HttpApiSwapDeclineReason::UnsatisfactoryRate);
+        }
+    }
+
+    let alpha_expiry = u32::from(request.alpha_expiry);
+    let beta_expiry = u32::from(request.beta_expiry);
+
+    let expiry_gap = alpha_expiry.saturating_sub(beta_expiry);
+    if alpha_expiry <= beta_expiry || expiry_gap < pair.min_expiry_gap.unwrap_or(0) {
+        return Verdict::Decline(HttpApiSwapDeclineReason::UnsatisfactoryExpiry);
+    }
+
+    let time_to_beta_expiry = beta_expiry.saturating_sub(u32::from(now));
+    if time_to_beta_expiry < pair.min_time_to_beta_expiry.unwrap_or(0) {
+        return Verdict::Decline(HttpApiSwapDeclineReason::UnsatisfactoryExpiry);
+    }
+
+    Verdict::Accept
+}
+
+fn is_within_limits(pair: &TradingPair, alpha_quantity: U256) -> bool {
+    let above_min = pair
+        .min_alpha_quantity
+        .map_or(true, |min| alpha_quantity >= min);
+    let below_max = pair
+        .max_alpha_quantity
+        .map_or(true, |max| alpha_quantity <= max);
+
+    above_min && below_max
+}
+
+/// The quantity of an asset in its smallest unit.
+fn quantity(asset: AssetKind) -> U256 {
+    match asset {
+        AssetKind::Bitcoin(bitcoin) => U256::from(bitcoin.as_sat()),
+        AssetKind::Ether(ether) => ether.wei(),
+        AssetKind::Erc20(erc20) => erc20.quantity.0,
+    }
+}
+
+fn to_f64(quantity: U256) -> f64 {
+    quantity
+        .to_string()
+        .parse()
+        .expect("decimal representation of U256 is a valid f64")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        db::{AssetKind, LedgerKind},
+        ethereum::Address,
+    };
+
+    fn policy() -> Policy {
+        Policy {
+            ethereum_identity: Address::zero(),
+            pairs: vec![TradingPair {
+                alpha_ledger: LedgerKind::Bitcoin,
+                beta_ledger: LedgerKind::Ethereum,
+                alpha_asset: AssetKind::Bitcoin,
+                beta_asset: AssetKind::Ether,
+                min_alpha_quantity: Some(U256::from(1_000)),
+                max_alpha_quantity: Some(U256::from(100_000_000)),
+                min_rate: Some(10.0),
+                min_expiry_gap: Some(3600),
+                min_time_to_beta_expiry: Some(600),
+            }],
+        }
+    }
+
+    const NOW: u32 = 1_000_000;
+
+    #[test]
+    fn accepts_request_that_satisfies_policy() {
+        let request = Request::bitcoin_for_ether(10_000, 100_000, NOW + 7200, NOW + 3600);
+
+        assert_eq!(
+            evaluate(&policy(), &request, Timestamp::from(NOW)),
+            Verdict::Accept
+        );
+    }
+
+    #[test]
+    fn declines_request_without_matching_pair() {
+        let policy = Policy {
+            pairs: vec![],
+            ..policy()
+        };
+        let request = Request::bitcoin_for_ether(10_000, 100_000, NOW + 7200, NOW + 3600);
+
+        assert_eq!(
+            evaluate(&policy, &request, Timestamp::from(NOW)),
+            Verdict::Decline(HttpApiSwapDeclineReason::UnsupportedSwap)
+        );
+    }
+
+    #[test]
+    fn declines_request_with_too_small_quantity() {
+        let request = Request::bitcoin_for_ether(999, 100_000, NOW + 7200, NOW + 3600);
+
+        assert_eq!(
+            evaluate(&policy(), &request, Timestamp::from(NOW)),
+            Verdict::Decline(HttpApiSwapDeclineReason::UnsatisfactoryQuantity)
+        );
+    }
+
+    #[test]
+    fn declines_request_with_too_low_rate() {
+        let request = Request::bitcoin_for_ether(10_000, 99_999, NOW + 7200, NOW + 3600);
+
+        assert_eq!(
+            evaluate(&policy(), &request, Timestamp::from(NOW)),
+            Verdict::Decline(HttpApiSwapDeclineReason::UnsatisfactoryRate)
+        );
+    }
+
+    #[test]
+    fn declines_request_with_too_small_expiry_gap() {
+        let request = Request::bitcoin_for_ether(10_000, 100_000, NOW + 7199, NOW + 3600);
+
+        assert_eq!(
+            evaluate(&policy(), &request, Timestamp::from(NOW)),
+            Verdict::Decline(HttpApiSwapDeclineReason::UnsatisfactoryExpiry)
+        );
+    }
+
+    #[test]
+    fn declines_request_with_beta_expiry_too_close() {
+        let request = Request::bitcoin_for_ether(10_000, 100_000, NOW + 4200, NOW + 599);
+
+        assert_eq!(
+            evaluate(&policy(), &request, Timestamp::from(NOW)),
+            Verdict::Decline(HttpApiSwapDeclineReason::UnsatisfactoryExpiry)
+        );
+    }
+}
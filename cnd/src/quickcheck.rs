@@ -307,6 +307,84 @@ impl Arbitrary
     }
 }
 
+impl Arbitrary
+    for Quickcheck<
+        Request<ledger::Ethereum, ledger::Ethereum, crate::asset::Ether, crate::asset::Erc20>,
+    >
+{
+    fn arbitrary<G: Gen>(g: &mut G) -> Self {
+        Quickcheck(Request {
+            swap_id: *Quickcheck::<SwapId>::arbitrary(g),
+            alpha_ledger: ledger::Ethereum {
+                chain_id: *Quickcheck::<ChainId>::arbitrary(g),
+            },
+            beta_ledger: ledger::Ethereum {
+                chain_id: *Quickcheck::<ChainId>::arbitrary(g),
+            },
+            alpha_asset: *Quickcheck::<crate::asset::Ether>::arbitrary(g),
+            beta_asset: *Quickcheck::<crate::asset::Erc20>::arbitrary(g),
+            hash_function: *Quickcheck::<HashFunction>::arbitrary(g),
+            alpha_ledger_refund_identity: *Quickcheck::<crate::ethereum::Address>::arbitrary(g),
+            beta_ledger_redeem_identity: *Quickcheck::<crate::ethereum::Address>::arbitrary(g),
+            alpha_expiry: *Quickcheck::<Timestamp>::arbitrary(g),
+            beta_expiry: *Quickcheck::<Timestamp>::arbitrary(g),
+            secret_hash: *Quickcheck::<SecretHash>::arbitrary(g),
+        })
+    }
+}
+
+impl Arbitrary
+    for Quickcheck<
+        Request<ledger::Ethereum, ledger::Ethereum, crate::asset::Erc20, crate::asset::Ether>,
+    >
+{
+    fn arbitrary<G: Gen>(g: &mut G) -> Self {
+        Quickcheck(Request {
+            swap_id: *Quickcheck::<SwapId>::arbitrary(g),
+            alpha_ledger: ledger::Ethereum {
+                chain_id: *Quickcheck::<ChainId>::arbitrary(g),
+            },
+            beta_ledger: ledger::Ethereum {
+                chain_id: *Quickcheck::<ChainId>::arbitrary(g),
+            },
+            alpha_asset: *Quickcheck::<crate::asset::Erc20>::arbitrary(g),
+            beta_asset: *Quickcheck::<crate::asset::Ether>::arbitrary(g),
+            hash_function: *Quickcheck::<HashFunction>::arbitrary(g),
+            alpha_ledger_refund_identity: *Quickcheck::<crate::ethereum::Address>::arbitrary(g),
+            beta_ledger_redeem_identity: *Quickcheck::<crate::ethereum::Address>::arbitrary(g),
+            alpha_expiry: *Quickcheck::<Timestamp>::arbitrary(g),
+            beta_expiry: *Quickcheck::<Timestamp>::arbitrary(g),
+            secret_hash: *Quickcheck::<SecretHash>::arbitrary(g),
+        })
+    }
+}
+
+impl Arbitrary
+    for Quickcheck<
+        Request<ledger::Ethereum, ledger::Ethereum, crate::asset::Erc20, crate::asset::Erc20>,
+    >
+{
+    fn arbitrary<G: Gen>(g: &mut G) -> Self {
+        Quickcheck(Request {
+            swap_id: *Quickcheck::<SwapId>::arbitrary(g),
+            alpha_ledger: ledger::Ethereum {
+                chain_id: *Quickcheck::<ChainId>::arbitrary(g),
+            },
+            beta_ledger: ledger::Ethereum {
+                chain_id: *Quickcheck::<ChainId>::arbitrary(g),
+            },
+            alpha_asset: *Quickcheck::<crate::asset::Erc20>::arbitrary(g),
+            beta_asset: *Quickcheck::<crate::asset::Erc20>::arbitrary(g),
+            hash_function: *Quickcheck::<HashFunction>::arbitrary(g),
+            alpha_ledger_refund_identity: *Quickcheck::<crate::ethereum::Address>::arbitrary(g),
+            beta_ledger_redeem_identity: *Quickcheck::<crate::ethereum::Address>::arbitrary(g),
+            alpha_expiry: *Quickcheck::<Timestamp>::arbitrary(g),
+            beta_expiry: *Quickcheck::<Timestamp>::arbitrary(g),
+            secret_hash: *Quickcheck::<SecretHash>::arbitrary(g),
+        })
+    }
+}
+
 impl Arbitrary for Quickcheck<Accept<ledger::Bitcoin, ledger::Ethereum>> {
     fn arbitrary<G: Gen>(g: &mut G) -> Self {
         Quickcheck(Accept {
@@ -327,6 +405,16 @@ impl Arbitrary for Quickcheck<Accept<ledger::Ethereum, ledger::Bitcoin>> {
     }
 }
 
+impl Arbitrary for Quickcheck<Accept<ledger::Ethereum, ledger::Ethereum>> {
+    fn arbitrary<G: Gen>(g: &mut G) -> Self {
+        Quickcheck(Accept {
+            swap_id: *Quickcheck::<SwapId>::arbitrary(g),
+            alpha_ledger_redeem_identity: *Quickcheck::<crate::ethereum::Address>::arbitrary(g),
+            beta_ledger_refund_identity: *Quickcheck::<crate::ethereum::Address>::arbitrary(g),
+        })
+    }
+}
+
 impl Arbitrary for Quickcheck<Role> {
     fn arbitrary<G: Gen>(g: &mut G) -> Self {
         let role = match g.next_u32() % 2 {
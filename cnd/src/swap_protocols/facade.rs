@@ -1,10 +1,19 @@
 use crate::{
     asset::{self, Asset},
-    btsieve::{bitcoin::BitcoindConnector, ethereum::Web3Connector},
-    db::{AcceptedSwap, DetermineTypes, LoadAcceptedSwap, Retrieve, Save, Sqlite, Swap, SwapTypes},
+    btsieve::{
+        bitcoin::BitcoindConnector,
+        ethereum::{Web3Connector, Web3Connectors},
+    },
+    config::Validation,
+    db::{
+        AcceptedSwap, CommunicationState, DetermineCommunicationState, DetermineTypes,
+        LoadAcceptedSwap, LoadDecline, LoadRequest, LoadSwapEvents, PeerAddressBook, Retrieve,
+        Save, Sqlite, Swap, SwapTypes,
+    },
     network::{
-        ComitPeers, DialInformation, ListenAddresses, LocalPeerId, PendingRequestFor, RequestError,
-        SendRequest, Swarm,
+        BanPeers, Capabilities, ComitPeers, DialInformation, DiscoveredPeers, ListenAddresses,
+        LocalPeerId, ManagePeers, PeerCapabilities, PeerInfo, PeerInformation, PendingRequestFor,
+        RequestError, ResponseFuture, SendRequest, Swarm,
     },
     seed::{DeriveSwapSeed, RootSeed, SwapSeed},
     swap_protocols::{
@@ -14,17 +23,18 @@ use crate::{
             create_swap::{HtlcParams, SwapEvent},
             events::{Deployed, Funded, HtlcEvents, Redeemed, Refunded},
             state_store::{self, InMemoryStateStore, StateStore},
-            ActorState, Ledger,
+            ActorState, Ledger, Request,
         },
         SwapId,
     },
+    timestamp::Timestamp,
 };
 use async_trait::async_trait;
 use futures::sync::oneshot::Sender;
 use futures_core::future::Either;
 use libp2p::{Multiaddr, PeerId};
 use libp2p_comit::frame::Response;
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc};
 
 /// This is a facade that implements all the required traits and forwards them
 /// to another implementation. This allows us to keep the number of arguments to
@@ -32,11 +42,12 @@ use std::sync::Arc;
 #[derive(Clone, Debug)]
 pub struct Facade {
     pub bitcoin_connector: BitcoindConnector,
-    pub ethereum_connector: Web3Connector,
+    pub ethereum_connectors: Web3Connectors,
     pub state_store: Arc<InMemoryStateStore>,
     pub seed: RootSeed,
     pub swarm: Swarm,
     pub db: Sqlite,
+    pub validation: Validation,
 }
 
 impl StateStore for Facade {
@@ -51,6 +62,14 @@ impl StateStore for Facade {
     fn update<A: ActorState>(&self, key: &SwapId, update: SwapEvent<A::AL, A::BL, A::AA, A::BA>) {
         self.state_store.update::<A>(key, update)
     }
+
+    fn set_degraded(&self, key: &SwapId, degraded: bool) {
+        self.state_store.set_degraded(key, degraded)
+    }
+
+    fn is_degraded(&self, key: &SwapId) -> bool {
+        self.state_store.is_degraded(key)
+    }
 }
 
 impl LocalPeerId for Facade {
@@ -68,6 +87,53 @@ impl ComitPeers for Facade {
     }
 }
 
+#[async_trait]
+impl DiscoveredPeers for Facade {
+    async fn discovered_peers(&self) -> Vec<(PeerId, Vec<Multiaddr>)> {
+        self.swarm.discovered_peers().await
+    }
+}
+
+#[async_trait]
+impl PeerCapabilities for Facade {
+    async fn peer_capabilities(&self) -> HashMap<PeerId, Capabilities> {
+        self.swarm.peer_capabilities().await
+    }
+}
+
+#[async_trait]
+impl PeerInformation for Facade {
+    async fn peer_info(&self) -> HashMap<PeerId, PeerInfo> {
+        self.swarm.peer_info().await
+    }
+}
+
+#[async_trait]
+impl BanPeers for Facade {
+    async fn banned_peers(&self) -> Vec<PeerId> {
+        self.swarm.banned_peers().await
+    }
+
+    async fn ban_peer(&self, peer_id: PeerId) -> anyhow::Result<()> {
+        self.swarm.ban_peer(peer_id).await
+    }
+
+    async fn unban_peer(&self, peer_id: &PeerId) -> anyhow::Result<bool> {
+        self.swarm.unban_peer(peer_id).await
+    }
+}
+
+#[async_trait]
+impl ManagePeers for Facade {
+    async fn add_peer_address(&self, peer_id: PeerId, address: Multiaddr) -> anyhow::Result<()> {
+        self.swarm.add_peer_address(peer_id, address).await
+    }
+
+    async fn remove_peer(&self, peer_id: &PeerId) -> anyhow::Result<()> {
+        self.swarm.remove_peer(peer_id).await
+    }
+}
+
 #[async_trait]
 impl ListenAddresses for Facade {
     async fn listen_addresses(&self) -> Vec<Multiaddr> {
@@ -88,7 +154,7 @@ impl SendRequest for Facade {
         &self,
         peer_identity: DialInformation,
         request: rfc003::Request<AL, BL, AA, BA>,
-    ) -> Result<rfc003::Response<AL, BL>, RequestError> {
+    ) -> Result<ResponseFuture<AL, BL>, RequestError> {
         self.swarm.send_request(peer_identity, request).await
     }
 }
@@ -110,6 +176,17 @@ impl Retrieve for Facade {
     }
 }
 
+#[async_trait]
+impl PeerAddressBook for Facade {
+    async fn peer_addresses(&self) -> anyhow::Result<Vec<(PeerId, Vec<Multiaddr>)>> {
+        self.db.peer_addresses().await
+    }
+
+    async fn delete_peer(&self, peer_id: &PeerId) -> anyhow::Result<Vec<Multiaddr>> {
+        self.db.delete_peer(peer_id).await
+    }
+}
+
 #[async_trait]
 impl<AL, BL, AA, BA> LoadAcceptedSwap<AL, BL, AA, BA> for Facade
 where
@@ -127,6 +204,44 @@ where
     }
 }
 
+#[async_trait]
+impl<AL, BL, AA, BA> LoadRequest<AL, BL, AA, BA> for Facade
+where
+    AL: Ledger + Send + 'static,
+    BL: Ledger + Send + 'static,
+    AA: Asset + Send + 'static,
+    BA: Asset + Send + 'static,
+    Sqlite: LoadRequest<AL, BL, AA, BA>,
+{
+    async fn load_request(&self, swap_id: &SwapId) -> anyhow::Result<Request<AL, BL, AA, BA>> {
+        self.db.load_request(swap_id).await
+    }
+}
+
+#[async_trait]
+impl LoadDecline for Facade {
+    async fn load_decline(&self, swap_id: &SwapId) -> anyhow::Result<rfc003::Decline> {
+        self.db.load_decline(swap_id).await
+    }
+}
+
+#[async_trait]
+impl<AL, BL, AA, BA> LoadSwapEvents<AL, BL, AA, BA> for Facade
+where
+    AL: Ledger + Send + 'static,
+    BL: Ledger + Send + 'static,
+    AA: Asset + Send + 'static,
+    BA: Asset + Send + 'static,
+    Sqlite: LoadSwapEvents<AL, BL, AA, BA>,
+{
+    async fn load_swap_events(
+        &self,
+        swap_id: &SwapId,
+    ) -> anyhow::Result<Vec<SwapEvent<AL, BL, AA, BA>>> {
+        self.db.load_swap_events(swap_id).await
+    }
+}
+
 #[async_trait]
 impl DetermineTypes for Facade {
     async fn determine_types(&self, key: &SwapId) -> anyhow::Result<SwapTypes> {
@@ -134,6 +249,16 @@ impl DetermineTypes for Facade {
     }
 }
 
+#[async_trait]
+impl DetermineCommunicationState for Facade {
+    async fn determine_communication_state(
+        &self,
+        key: &SwapId,
+    ) -> anyhow::Result<CommunicationState> {
+        self.db.determine_communication_state(key).await
+    }
+}
+
 #[async_trait]
 impl<T> Save<T> for Facade
 where
@@ -150,17 +275,21 @@ impl HtlcEvents<Bitcoin, asset::Bitcoin> for Facade {
     async fn htlc_deployed(
         &self,
         htlc_params: HtlcParams<Bitcoin, asset::Bitcoin>,
+        start_of_swap: Timestamp,
     ) -> anyhow::Result<Deployed<Bitcoin>> {
-        self.bitcoin_connector.htlc_deployed(htlc_params).await
+        self.bitcoin_connector
+            .htlc_deployed(htlc_params, start_of_swap)
+            .await
     }
 
     async fn htlc_funded(
         &self,
         htlc_params: HtlcParams<Bitcoin, asset::Bitcoin>,
         htlc_deployment: &Deployed<Bitcoin>,
+        start_of_swap: Timestamp,
     ) -> anyhow::Result<Funded<Bitcoin, asset::Bitcoin>> {
         self.bitcoin_connector
-            .htlc_funded(htlc_params, htlc_deployment)
+            .htlc_funded(htlc_params, htlc_deployment, start_of_swap)
             .await
     }
 
@@ -169,9 +298,10 @@ impl HtlcEvents<Bitcoin, asset::Bitcoin> for Facade {
         htlc_params: HtlcParams<Bitcoin, asset::Bitcoin>,
         htlc_deployment: &Deployed<Bitcoin>,
         htlc_funding: &Funded<Bitcoin, asset::Bitcoin>,
+        start_of_swap: Timestamp,
     ) -> anyhow::Result<Either<Redeemed<Bitcoin>, Refunded<Bitcoin>>> {
         self.bitcoin_connector
-            .htlc_redeemed_or_refunded(htlc_params, htlc_deployment, htlc_funding)
+            .htlc_redeemed_or_refunded(htlc_params, htlc_deployment, htlc_funding, start_of_swap)
             .await
     }
 }
@@ -185,17 +315,23 @@ where
     async fn htlc_deployed(
         &self,
         htlc_params: HtlcParams<Ethereum, A>,
+        start_of_swap: Timestamp,
     ) -> anyhow::Result<Deployed<Ethereum>> {
-        self.ethereum_connector.htlc_deployed(htlc_params).await
+        self.ethereum_connectors
+            .get(htlc_params.ledger.chain_id)?
+            .htlc_deployed(htlc_params, start_of_swap)
+            .await
     }
 
     async fn htlc_funded(
         &self,
         htlc_params: HtlcParams<Ethereum, A>,
         htlc_deployment: &Deployed<Ethereum>,
+        start_of_swap: Timestamp,
     ) -> anyhow::Result<Funded<Ethereum, A>> {
-        self.ethereum_connector
-            .htlc_funded(htlc_params, htlc_deployment)
+        self.ethereum_connectors
+            .get(htlc_params.ledger.chain_id)?
+            .htlc_funded(htlc_params, htlc_deployment, start_of_swap)
             .await
     }
 
@@ -204,9 +340,11 @@ where
         htlc_params: HtlcParams<Ethereum, A>,
         htlc_deployment: &Deployed<Ethereum>,
         htlc_funding: &Funded<Ethereum, A>,
+        start_of_swap: Timestamp,
     ) -> anyhow::Result<Either<Redeemed<Ethereum>, Refunded<Ethereum>>> {
-        self.ethereum_connector
-            .htlc_redeemed_or_refunded(htlc_params, htlc_deployment, htlc_funding)
+        self.ethereum_connectors
+            .get(htlc_params.ledger.chain_id)?
+            .htlc_redeemed_or_refunded(htlc_params, htlc_deployment, htlc_funding, start_of_swap)
             .await
     }
 }
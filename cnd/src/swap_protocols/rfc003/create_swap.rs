@@ -1,5 +1,6 @@
 use crate::{
     asset::Asset,
+    db::{Save, SwapEventRecord},
     swap_protocols::{
         rfc003::{
             self,
@@ -12,61 +13,171 @@ use crate::{
     },
     timestamp::Timestamp,
 };
+use chrono::NaiveDateTime;
 use futures_core::future::{self, Either};
 use genawaiter::{
     sync::{Co, Gen},
     GeneratorState,
 };
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::{mem, time::Duration};
+
+/// The time we give the ledger watchers to find events in the history of the
+/// ledger before we consider an HTLC to be expired.
+///
+/// Without this, an HTLC whose expiry passed while cnd was not running would be
+/// declared expired on startup even if it was funded in time.
+const HISTORY_SCAN_GRACE_PERIOD: u32 = 5 * 60;
+
+/// The time we wait before watching the ledgers of a swap again after the first
+/// failure, the delay doubles with every consecutive failure.
+const INITIAL_RETRY_DELAY: Duration = Duration::from_secs(1);
+
+/// The maximum time we wait before watching the ledgers of a swap again.
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(5 * 60);
 
 /// Returns a future that tracks the swap negotiated from the given request and
 /// accept response on both ledgers.
 ///
-/// The current implementation is naive in the sense that it does not take into
-/// account situations where it is clear that no more events will happen even
-/// though in theory, there could. For example:
-/// - alpha funded
-/// - alpha refunded
+/// Both ledgers are scanned back until `accepted_at`, the time the swap was
+/// accepted, so events that happened while cnd was not running are found.
+///
+/// Every event that is observed is applied to the state store and saved to the
+/// database, except for expiries. Events in `known_events` were already loaded
+/// from the database and applied to the state store, if they are observed
+/// again they are skipped.
 ///
-/// It is highly unlikely for Bob to fund the HTLC now, yet the current
-/// implementation is still waiting for that.
+/// If watching one of the ledgers fails, e.g. because the connector is not
+/// reachable, the swap is marked as degraded and the ledgers are watched again
+/// after a backoff delay, resuming from the events observed so far. The swap
+/// stays degraded until watching the ledgers yields a new event.
+///
+/// An HTLC that is not funded before its expiry is considered expired. Each
+/// ledger is watched until its HTLC is redeemed, refunded or expired, the swap
+/// terminates once this happened on both ledgers.
 pub async fn create_swap<D, A: ActorState>(
     dependencies: D,
     request: Request<A::AL, A::BL, A::AA, A::BA>,
     accept: Accept<A::AL, A::BL>,
+    accepted_at: NaiveDateTime,
+    known_events: Vec<SwapEvent<A::AL, A::BL, A::AA, A::BA>>,
 ) where
-    D: HtlcEvents<A::AL, A::AA> + HtlcEvents<A::BL, A::BA> + StateStore + Clone,
+    D: HtlcEvents<A::AL, A::AA>
+        + HtlcEvents<A::BL, A::BA>
+        + StateStore
+        + Save<SwapEventRecord<A::AL, A::BL, A::AA, A::BA>>
+        + Clone,
 {
     let id = request.swap_id;
     let swap = OngoingSwap::new(request, accept);
+    let alpha_htlc_params = swap.alpha_htlc_params();
+    let beta_htlc_params = swap.beta_htlc_params();
+    let start_of_swap = Timestamp::from(accepted_at);
 
-    // construct a generator that watches alpha and beta ledger concurrently
-    let mut generator = Gen::new({
-        let dependencies = dependencies.clone();
-        |co| async move {
-            future::try_join(
-                watch_alpha_ledger(&dependencies, &co, swap.alpha_htlc_params()),
-                watch_beta_ledger(&dependencies, &co, swap.beta_htlc_params()),
-            )
-            .await
-        }
-    });
+    let mut seen_events = known_events;
+    if is_settled(&seen_events) {
+        log::info!("Swap {} already finished", id);
+        return;
+    }
+
+    let mut retry_delay = INITIAL_RETRY_DELAY;
+    let mut degraded = false;
 
     loop {
-        // wait for events to be emitted as the generator executes
-        match generator.async_resume().await {
-            // every event that is yielded is passed on
-            GeneratorState::Yielded(event) => {
-                dependencies.update::<A>(&id, event);
+        let resume_from = seen_events.clone();
+
+        // construct a generator that watches alpha and beta ledger concurrently
+        let mut generator = Gen::new({
+            let dependencies = dependencies.clone();
+            |co| async move {
+                future::try_join(
+                    watch_alpha_ledger(
+                        &dependencies,
+                        &co,
+                        alpha_htlc_params,
+                        start_of_swap,
+                        &resume_from,
+                    ),
+                    watch_beta_ledger(
+                        &dependencies,
+                        &co,
+                        beta_htlc_params,
+                        start_of_swap,
+                        &resume_from,
+                    ),
+                )
+                .await
             }
-            // the generator stopped executing, this means there are no more events that can be
-            // watched.
-            GeneratorState::Complete(Ok(_)) => {
+        });
+
+        let result = loop {
+            // wait for events to be emitted as the generator executes
+            match generator.async_resume().await {
+                // every event that is yielded is passed on
+                GeneratorState::Yielded(event) => {
+                    let is_known = seen_events
+                        .iter()
+                        .any(|seen| mem::discriminant(seen) == mem::discriminant(&event));
+                    if is_known {
+                        log::trace!("Swap {} skipping already known event {:?}", id, event);
+                        continue;
+                    }
+
+                    // the ledgers can be watched again, the swap recovered
+                    if degraded {
+                        log::info!("Swap {} recovered", id);
+                        dependencies.set_degraded(&id, false);
+                        degraded = false;
+                    }
+                    retry_delay = INITIAL_RETRY_DELAY;
+
+                    dependencies.update::<A>(&id, event.clone());
+
+                    // Expiries are taken from the wall clock while the history of the ledger
+                    // might still be scanned, they are determined again after a restart.
+                    let is_expiry = match event {
+                        SwapEvent::AlphaExpired | SwapEvent::BetaExpired => true,
+                        _ => false,
+                    };
+                    if !is_expiry {
+                        let record = SwapEventRecord {
+                            swap_id: id,
+                            event: event.clone(),
+                        };
+                        if let Err(e) = dependencies.save(record).await {
+                            log::error!("Swap {} failed to save event: {:?}", id, e);
+                        }
+                    }
+
+                    seen_events.push(event);
+                    if is_settled(&seen_events) {
+                        log::info!("Swap {} finished", id);
+                        return;
+                    }
+                }
+                // the generator stopped executing, either there are no more events that can be
+                // watched or one of the ledgers could not be watched.
+                GeneratorState::Complete(result) => break result,
+            }
+        };
+
+        match result {
+            Ok(()) => {
                 log::info!("Swap {} finished", id);
                 return;
             }
-            GeneratorState::Complete(Err(e)) => {
-                log::error!("Swap {} failed with {:?}", id, e);
-                return;
+            Err(e) => {
+                log::warn!(
+                    "Swap {} failed with {:?}, watching ledgers again in {:?}",
+                    id,
+                    e,
+                    retry_delay
+                );
+                dependencies.set_degraded(&id, true);
+                degraded = true;
+
+                tokio::time::delay_for(retry_delay).await;
+                retry_delay = std::cmp::min(retry_delay * 2, MAX_RETRY_DELAY);
             }
         }
     }
@@ -75,10 +186,14 @@ pub async fn create_swap<D, A: ActorState>(
 /// Returns a future that waits for events on alpha ledger to happen.
 ///
 /// Each event is yielded through the controller handle (co) of the coroutine.
+/// Events in `seen_events` are not waited for again, this allows to resume
+/// watching the ledger after a failure.
 async fn watch_alpha_ledger<D, AL, AA, BL, BA>(
     dependencies: &D,
     co: &Co<SwapEvent<AL, BL, AA, BA>>,
     htlc_params: HtlcParams<AL, AA>,
+    start_of_swap: Timestamp,
+    seen_events: &[SwapEvent<AL, BL, AA, BA>],
 ) -> anyhow::Result<()>
 where
     AL: Ledger,
@@ -87,14 +202,61 @@ where
     BA: Asset,
     D: HtlcEvents<AL, AA>,
 {
-    let deployed = dependencies.htlc_deployed(htlc_params).await?;
-    co.yield_(SwapEvent::AlphaDeployed(deployed.clone())).await;
+    let mut seen_deployed = None;
+    let mut seen_funded = None;
+    for event in seen_events {
+        match event {
+            SwapEvent::AlphaDeployed(deployed) => seen_deployed = Some(deployed.clone()),
+            SwapEvent::AlphaFunded(funded) => seen_funded = Some(funded.clone()),
+            SwapEvent::AlphaRedeemed(_) | SwapEvent::AlphaRefunded(_) | SwapEvent::AlphaExpired => {
+                return Ok(())
+            }
+            _ => {}
+        }
+    }
+
+    let deployed_and_funded = async move {
+        let deployed = match seen_deployed {
+            Some(deployed) => deployed,
+            None => {
+                let deployed = dependencies
+                    .htlc_deployed(htlc_params, start_of_swap)
+                    .await?;
+                co.yield_(SwapEvent::AlphaDeployed(deployed.clone())).await;
+                deployed
+            }
+        };
+
+        let funded = match seen_funded {
+            Some(funded) => funded,
+            None => {
+                let funded = dependencies
+                    .htlc_funded(htlc_params, &deployed, start_of_swap)
+                    .await?;
+                co.yield_(SwapEvent::AlphaFunded(funded.clone())).await;
+                funded
+            }
+        };
 
-    let funded = dependencies.htlc_funded(htlc_params, &deployed).await?;
-    co.yield_(SwapEvent::AlphaFunded(funded.clone())).await;
+        Ok::<_, anyhow::Error>((deployed, funded))
+    };
+
+    let (deployed, funded) = match before_expiry(
+        deployed_and_funded,
+        htlc_params.expiry,
+        HISTORY_SCAN_GRACE_PERIOD,
+    )
+    .await?
+    {
+        Some(deployed_and_funded) => deployed_and_funded,
+        None => {
+            co.yield_(SwapEvent::AlphaExpired).await;
+            return Ok(());
+        }
+    };
 
     let redeemed_or_refunded = dependencies
-        .htlc_redeemed_or_refunded(htlc_params, &deployed, &funded)
+        .htlc_redeemed_or_refunded(htlc_params, &deployed, &funded, start_of_swap)
         .await?;
 
     match redeemed_or_refunded {
@@ -112,10 +274,14 @@ where
 /// Returns a future that waits for events on beta ledger to happen.
 ///
 /// Each event is yielded through the controller handle (co) of the coroutine.
+/// Events in `seen_events` are not waited for again, this allows to resume
+/// watching the ledger after a failure.
 async fn watch_beta_ledger<D, AL, AA, BL, BA>(
     dependencies: &D,
     co: &Co<SwapEvent<AL, BL, AA, BA>>,
     htlc_params: HtlcParams<BL, BA>,
+    start_of_swap: Timestamp,
+    seen_events: &[SwapEvent<AL, BL, AA, BA>],
 ) -> anyhow::Result<()>
 where
     AL: Ledger,
@@ -124,14 +290,61 @@ where
     BA: Asset,
     D: HtlcEvents<BL, BA>,
 {
-    let deployed = dependencies.htlc_deployed(htlc_params).await?;
-    co.yield_(SwapEvent::BetaDeployed(deployed.clone())).await;
+    let mut seen_deployed = None;
+    let mut seen_funded = None;
+    for event in seen_events {
+        match event {
+            SwapEvent::BetaDeployed(deployed) => seen_deployed = Some(deployed.clone()),
+            SwapEvent::BetaFunded(funded) => seen_funded = Some(funded.clone()),
+            SwapEvent::BetaRedeemed(_) | SwapEvent::BetaRefunded(_) | SwapEvent::BetaExpired => {
+                return Ok(())
+            }
+            _ => {}
+        }
+    }
+
+    let deployed_and_funded = async move {
+        let deployed = match seen_deployed {
+            Some(deployed) => deployed,
+            None => {
+                let deployed = dependencies
+                    .htlc_deployed(htlc_params, start_of_swap)
+                    .await?;
+                co.yield_(SwapEvent::BetaDeployed(deployed.clone())).await;
+                deployed
+            }
+        };
 
-    let funded = dependencies.htlc_funded(htlc_params, &deployed).await?;
-    co.yield_(SwapEvent::BetaFunded(funded.clone())).await;
+        let funded = match seen_funded {
+            Some(funded) => funded,
+            None => {
+                let funded = dependencies
+                    .htlc_funded(htlc_params, &deployed, start_of_swap)
+                    .await?;
+                co.yield_(SwapEvent::BetaFunded(funded.clone())).await;
+                funded
+            }
+        };
+
+        Ok::<_, anyhow::Error>((deployed, funded))
+    };
+
+    let (deployed, funded) = match before_expiry(
+        deployed_and_funded,
+        htlc_params.expiry,
+        HISTORY_SCAN_GRACE_PERIOD,
+    )
+    .await?
+    {
+        Some(deployed_and_funded) => deployed_and_funded,
+        None => {
+            co.yield_(SwapEvent::BetaExpired).await;
+            return Ok(());
+        }
+    };
 
     let redeemed_or_refunded = dependencies
-        .htlc_redeemed_or_refunded(htlc_params, &deployed, &funded)
+        .htlc_redeemed_or_refunded(htlc_params, &deployed, &funded, start_of_swap)
         .await?;
 
     match redeemed_or_refunded {
@@ -146,6 +359,55 @@ where
     Ok(())
 }
 
+/// Resolves the given future unless the expiry passes before it does.
+///
+/// Returns `None` if the expiry passed first. To give the ledger watchers a
+/// chance to find past events, the expiry is never reached earlier than
+/// `grace_period` seconds from now.
+async fn before_expiry<F, T>(
+    future: F,
+    expiry: Timestamp,
+    grace_period: u32,
+) -> anyhow::Result<Option<T>>
+where
+    F: std::future::Future<Output = anyhow::Result<T>>,
+{
+    let deadline = std::cmp::max(expiry, Timestamp::now().plus(grace_period));
+    let remaining = u32::from(deadline).saturating_sub(u32::from(Timestamp::now()));
+    let expired = tokio::time::delay_for(Duration::from_secs(u64::from(remaining)));
+
+    futures_core::pin_mut!(future);
+    futures_core::pin_mut!(expired);
+
+    match future::select(future, expired).await {
+        Either::Left((result, _)) => result.map(Some),
+        Either::Right(((), _)) => Ok(None),
+    }
+}
+
+/// Returns whether no more events relevant to the swap can happen.
+///
+/// This is the case once the HTLCs on both ledgers were redeemed, refunded or
+/// expired without being funded.
+fn is_settled<AL, BL, AA, BA>(events: &[SwapEvent<AL, BL, AA, BA>]) -> bool
+where
+    AL: Ledger,
+    BL: Ledger,
+    AA: Asset,
+    BA: Asset,
+{
+    let alpha_closed = events.iter().any(|event| match event {
+        SwapEvent::AlphaRedeemed(_) | SwapEvent::AlphaRefunded(_) | SwapEvent::AlphaExpired => true,
+        _ => false,
+    });
+    let beta_closed = events.iter().any(|event| match event {
+        SwapEvent::BetaRedeemed(_) | SwapEvent::BetaRefunded(_) | SwapEvent::BetaExpired => true,
+        _ => false,
+    });
+
+    alpha_closed && beta_closed
+}
+
 #[derive(Clone, Copy, Debug)]
 pub struct HtlcParams<L: Ledger, A: Asset> {
     pub asset: A,
@@ -249,7 +511,11 @@ impl<AL: Ledger, BL: Ledger, AA: Asset, BA: Asset> OngoingSwap<AL, BL, AA, BA> {
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(bound(
+    serialize = "AA: Serialize, BA: Serialize",
+    deserialize = "AA: DeserializeOwned, BA: DeserializeOwned"
+))]
 pub enum SwapEvent<AL, BL, AA, BA>
 where
     AL: Ledger,
@@ -261,9 +527,392 @@ where
     AlphaFunded(Funded<AL, AA>),
     AlphaRedeemed(Redeemed<AL>),
     AlphaRefunded(Refunded<AL>),
+    AlphaExpired,
 
     BetaDeployed(Deployed<BL>),
     BetaFunded(Funded<BL, BA>),
     BetaRedeemed(Redeemed<BL>),
     BetaRefunded(Refunded<BL>),
+    BetaExpired,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        asset,
+        ethereum::{Address, Bytes, Transaction, H256, U256},
+        seed::{DeriveSwapSeed, RootSeed},
+        swap_protocols::{
+            ledger::{Bitcoin, Ethereum},
+            rfc003::{alice, state_store::InMemoryStateStore, Secret},
+            SwapId,
+        },
+    };
+    use futures_core::{
+        channel::{mpsc, oneshot},
+        StreamExt,
+    };
+    use spectral::prelude::*;
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    };
+
+    type AliceState = alice::State<Bitcoin, Ethereum, asset::Bitcoin, asset::Ether>;
+
+    /// Fails the first attempt to watch for the deployment of the beta HTLC
+    /// and finds it in the next attempt once it is released through
+    /// `Controls::deploy_beta`, all other events never happen.
+    #[derive(Clone)]
+    struct FlakyConnector {
+        state_store: Arc<InMemoryStateStore>,
+        beta_deployed_attempts: Arc<AtomicUsize>,
+        retry_started: Arc<Mutex<Option<oneshot::Sender<()>>>>,
+        beta_deployment: Arc<Mutex<Option<oneshot::Receiver<()>>>>,
+        saved_events: mpsc::UnboundedSender<Event>,
+    }
+
+    /// The other ends of the channels a `FlakyConnector` is driven by.
+    struct Controls {
+        retry_started: oneshot::Receiver<()>,
+        deploy_beta: oneshot::Sender<()>,
+        saved_events: mpsc::UnboundedReceiver<Event>,
+    }
+
+    impl FlakyConnector {
+        fn new() -> (Self, Controls) {
+            let (retry_started_sender, retry_started) = oneshot::channel();
+            let (deploy_beta, beta_deployment) = oneshot::channel();
+            let (saved_events_sender, saved_events) = mpsc::unbounded();
+
+            let connector = FlakyConnector {
+                state_store: Arc::new(InMemoryStateStore::default()),
+                beta_deployed_attempts: Arc::new(AtomicUsize::new(0)),
+                retry_started: Arc::new(Mutex::new(Some(retry_started_sender))),
+                beta_deployment: Arc::new(Mutex::new(Some(beta_deployment))),
+                saved_events: saved_events_sender,
+            };
+            let controls = Controls {
+                retry_started,
+                deploy_beta,
+                saved_events,
+            };
+
+            (connector, controls)
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl HtlcEvents<Bitcoin, asset::Bitcoin> for FlakyConnector {
+        async fn htlc_deployed(
+            &self,
+            _: HtlcParams<Bitcoin, asset::Bitcoin>,
+            _: Timestamp,
+        ) -> anyhow::Result<Deployed<Bitcoin>> {
+            future::pending().await
+        }
+
+        async fn htlc_funded(
+            &self,
+            _: HtlcParams<Bitcoin, asset::Bitcoin>,
+            _: &Deployed<Bitcoin>,
+            _: Timestamp,
+        ) -> anyhow::Result<Funded<Bitcoin, asset::Bitcoin>> {
+            future::pending().await
+        }
+
+        async fn htlc_redeemed_or_refunded(
+            &self,
+            _: HtlcParams<Bitcoin, asset::Bitcoin>,
+            _: &Deployed<Bitcoin>,
+            _: &Funded<Bitcoin, asset::Bitcoin>,
+            _: Timestamp,
+        ) -> anyhow::Result<Either<Redeemed<Bitcoin>, Refunded<Bitcoin>>> {
+            future::pending().await
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl HtlcEvents<Ethereum, asset::Ether> for FlakyConnector {
+        async fn htlc_deployed(
+            &self,
+            _: HtlcParams<Ethereum, asset::Ether>,
+            _: Timestamp,
+        ) -> anyhow::Result<Deployed<Ethereum>> {
+            if self.beta_deployed_attempts.fetch_add(1, Ordering::SeqCst) == 0 {
+                return Err(anyhow::anyhow!("connector is not reachable"));
+            }
+
+            let retry_started = self.retry_started.lock().unwrap().take();
+            if let Some(retry_started) = retry_started {
+                let _ = retry_started.send(());
+            }
+
+            let beta_deployment = self.beta_deployment.lock().unwrap().take();
+            match beta_deployment {
+                Some(beta_deployment) => {
+                    let _ = beta_deployment.await;
+                }
+                None => future::pending().await,
+            }
+
+            Ok(Deployed {
+                transaction: transaction(),
+                location: Address::zero(),
+            })
+        }
+
+        async fn htlc_funded(
+            &self,
+            _: HtlcParams<Ethereum, asset::Ether>,
+            _: &Deployed<Ethereum>,
+            _: Timestamp,
+        ) -> anyhow::Result<Funded<Ethereum, asset::Ether>> {
+            future::pending().await
+        }
+
+        async fn htlc_redeemed_or_refunded(
+            &self,
+            _: HtlcParams<Ethereum, asset::Ether>,
+            _: &Deployed<Ethereum>,
+            _: &Funded<Ethereum, asset::Ether>,
+            _: Timestamp,
+        ) -> anyhow::Result<Either<Redeemed<Ethereum>, Refunded<Ethereum>>> {
+            future::pending().await
+        }
+    }
+
+    impl StateStore for FlakyConnector {
+        fn insert<A: ActorState>(&self, key: SwapId, value: A) {
+            self.state_store.insert(key, value)
+        }
+
+        fn get<A: ActorState>(
+            &self,
+            key: &SwapId,
+        ) -> Result<Option<A>, rfc003::state_store::Error> {
+            self.state_store.get(key)
+        }
+
+        fn update<A: ActorState>(
+            &self,
+            key: &SwapId,
+            update: SwapEvent<A::AL, A::BL, A::AA, A::BA>,
+        ) {
+            self.state_store.update::<A>(key, update)
+        }
+
+        fn set_degraded(&self, key: &SwapId, degraded: bool) {
+            self.state_store.set_degraded(key, degraded)
+        }
+
+        fn is_degraded(&self, key: &SwapId) -> bool {
+            self.state_store.is_degraded(key)
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl Save<SwapEventRecord<Bitcoin, Ethereum, asset::Bitcoin, asset::Ether>> for FlakyConnector {
+        async fn save(
+            &self,
+            record: SwapEventRecord<Bitcoin, Ethereum, asset::Bitcoin, asset::Ether>,
+        ) -> anyhow::Result<()> {
+            let _ = self.saved_events.unbounded_send(record.event);
+
+            Ok(())
+        }
+    }
+
+    fn transaction() -> Transaction {
+        Transaction {
+            hash: H256::zero(),
+            nonce: U256::zero(),
+            block_hash: None,
+            block_number: None,
+            transaction_index: None,
+            from: Address::zero(),
+            to: None,
+            value: U256::zero(),
+            gas_price: U256::zero(),
+            gas: U256::zero(),
+            input: Bytes(vec![]),
+        }
+    }
+
+    fn request_and_accept() -> (
+        Request<Bitcoin, Ethereum, asset::Bitcoin, asset::Ether>,
+        Accept<Bitcoin, Ethereum>,
+    ) {
+        let bitcoin_pub_key = crate::bitcoin::PublicKey::new(
+            "02c2a8efce029526d364c2cf39d89e3cdda05e5df7b2cbfc098b4e3d02b70b5275"
+                .parse()
+                .unwrap(),
+        );
+        let ethereum_address: Address = "8457037fcd80a8650c4692d7fcfc1d0a96b92867".parse().unwrap();
+
+        let request = Request {
+            swap_id: SwapId::default(),
+            alpha_ledger: Bitcoin::default(),
+            beta_ledger: Ethereum::default(),
+            alpha_asset: asset::Bitcoin::from_sat(100_000_000),
+            beta_asset: asset::Ether::from_eth(10.0),
+            hash_function: HashFunction::Sha256,
+            alpha_ledger_refund_identity: bitcoin_pub_key,
+            beta_ledger_redeem_identity: ethereum_address,
+            alpha_expiry: Timestamp::from(2_000_000_000),
+            beta_expiry: Timestamp::from(2_000_000_000),
+            secret_hash: Secret::from(*b"hello world, you are beautiful!!").hash(),
+        };
+        let accept = Accept {
+            swap_id: request.swap_id,
+            beta_ledger_refund_identity: ethereum_address,
+            alpha_ledger_redeem_identity: bitcoin_pub_key,
+        };
+
+        (request, accept)
+    }
+
+    #[test]
+    fn swap_stays_degraded_until_the_retried_watch_yields_an_event() {
+        let mut runtime = tokio::runtime::Runtime::new().unwrap();
+        let (connector, controls) = FlakyConnector::new();
+        let Controls {
+            retry_started,
+            deploy_beta,
+            mut saved_events,
+        } = controls;
+        let (request, accept) = request_and_accept();
+        let id = request.swap_id;
+
+        let secret_source =
+            RootSeed::from(*b"hello world, you are beautiful!!").derive_swap_seed(id);
+        connector.insert(
+            id,
+            AliceState::accepted(request.clone(), accept, secret_source),
+        );
+
+        runtime.spawn(create_swap::<_, AliceState>(
+            connector.clone(),
+            request,
+            accept,
+            NaiveDateTime::from_timestamp(1_000_000, 0),
+            vec![],
+        ));
+
+        let (retrying, saved, recovered) = runtime.block_on(async {
+            // the swap is marked as degraded before the ledgers are watched again
+            retry_started.await.unwrap();
+            let retrying = connector.is_degraded(&id);
+
+            // the event is saved after the swap recovered
+            deploy_beta.send(()).unwrap();
+            let saved = saved_events.next().await;
+            let recovered = !connector.is_degraded(&id);
+
+            (retrying, saved, recovered)
+        });
+
+        assert_that(&retrying).is_true();
+        assert_that(&saved).is_equal_to(Some(SwapEvent::BetaDeployed(Deployed {
+            transaction: transaction(),
+            location: Address::zero(),
+        })));
+        assert_that(&recovered).is_true();
+        assert_that(&connector.beta_deployed_attempts.load(Ordering::SeqCst)).is_equal_to(2);
+    }
+
+    #[test]
+    fn future_resolving_before_expiry_yields_its_item() {
+        let mut runtime = tokio::runtime::Runtime::new().unwrap();
+
+        let result = runtime.block_on(before_expiry(
+            async { Ok(42) },
+            Timestamp::now().plus(60),
+            0,
+        ));
+
+        assert_that(&result).is_ok().is_equal_to(Some(42));
+    }
+
+    #[test]
+    fn future_not_resolving_before_expiry_yields_none() {
+        let mut runtime = tokio::runtime::Runtime::new().unwrap();
+
+        let result = runtime.block_on(before_expiry(
+            future::pending::<anyhow::Result<()>>(),
+            Timestamp::from(0),
+            0,
+        ));
+
+        assert_that(&result).is_ok().is_none();
+    }
+
+    #[test]
+    fn expiry_is_not_reached_within_the_grace_period() {
+        let mut runtime = tokio::runtime::Runtime::new().unwrap();
+
+        let result = runtime.block_on(before_expiry(
+            async {
+                tokio::time::delay_for(Duration::from_millis(500)).await;
+                Ok(42)
+            },
+            Timestamp::from(0),
+            2,
+        ));
+
+        assert_that(&result).is_ok().is_equal_to(Some(42));
+    }
+
+    type Event = SwapEvent<Bitcoin, Ethereum, asset::Bitcoin, asset::Ether>;
+
+    fn beta_funded() -> Event {
+        SwapEvent::BetaFunded(Funded {
+            transaction: transaction(),
+            asset: asset::Ether::from_eth(10.0),
+        })
+    }
+
+    #[test]
+    fn swap_without_events_is_not_settled() {
+        assert_that(&is_settled::<Bitcoin, Ethereum, asset::Bitcoin, asset::Ether>(&[])).is_false();
+    }
+
+    #[test]
+    fn beta_expired_while_alpha_can_still_be_funded_is_not_settled() {
+        let events: Vec<Event> = vec![SwapEvent::BetaExpired];
+
+        assert_that(&is_settled(&events)).is_false();
+    }
+
+    #[test]
+    fn alpha_expired_and_beta_funded_is_not_settled() {
+        let events = vec![beta_funded(), SwapEvent::AlphaExpired];
+
+        assert_that(&is_settled(&events)).is_false();
+    }
+
+    #[test]
+    fn both_expired_is_settled() {
+        let events: Vec<Event> = vec![SwapEvent::BetaExpired, SwapEvent::AlphaExpired];
+
+        assert_that(&is_settled(&events)).is_true();
+    }
+
+    #[test]
+    fn alpha_refunded_and_beta_expired_is_settled() {
+        let events: Vec<Event> = vec![
+            SwapEvent::BetaExpired,
+            SwapEvent::AlphaRefunded(Refunded {
+                transaction: bitcoin::Transaction {
+                    version: 1,
+                    lock_time: 0,
+                    input: vec![],
+                    output: vec![],
+                },
+            }),
+        ];
+
+        assert_that(&is_settled(&events)).is_true();
+    }
 }
@@ -10,6 +10,7 @@ use crate::{
             Secret,
         },
     },
+    timestamp::Timestamp,
 };
 use anyhow::Context;
 use futures_core::future::{self, Either};
@@ -28,6 +29,7 @@ impl HtlcEvents<Ethereum, asset::Ether> for Web3Connector {
     async fn htlc_deployed(
         &self,
         htlc_params: HtlcParams<Ethereum, asset::Ether>,
+        start_of_swap: Timestamp,
     ) -> anyhow::Result<Deployed<Ethereum>> {
         let connector = self.clone();
         let pattern = TransactionPattern {
@@ -39,7 +41,7 @@ impl HtlcEvents<Ethereum, asset::Ether> for Web3Connector {
             events: None,
         };
         let TransactionAndReceipt { transaction, .. } =
-            matching_transaction(connector, pattern, None)
+            matching_transaction(connector, pattern, Some(start_of_swap.into()))
                 .await
                 .context("failed to find transaction for htlc deployment")?;
 
@@ -53,6 +55,7 @@ impl HtlcEvents<Ethereum, asset::Ether> for Web3Connector {
         &self,
         _htlc_params: HtlcParams<Ethereum, asset::Ether>,
         deploy_transaction: &Deployed<Ethereum>,
+        _start_of_swap: Timestamp,
     ) -> anyhow::Result<Funded<Ethereum, asset::Ether>> {
         Ok(Funded {
             transaction: deploy_transaction.transaction.clone(),
@@ -65,8 +68,16 @@ impl HtlcEvents<Ethereum, asset::Ether> for Web3Connector {
         htlc_params: HtlcParams<Ethereum, asset::Ether>,
         htlc_deployment: &Deployed<Ethereum>,
         htlc_funding: &Funded<Ethereum, asset::Ether>,
+        start_of_swap: Timestamp,
     ) -> anyhow::Result<Either<Redeemed<Ethereum>, Refunded<Ethereum>>> {
-        htlc_redeemed_or_refunded(self.clone(), htlc_params, htlc_deployment, htlc_funding).await
+        htlc_redeemed_or_refunded(
+            self.clone(),
+            htlc_params,
+            htlc_deployment,
+            htlc_funding,
+            start_of_swap,
+        )
+        .await
     }
 }
 
@@ -79,6 +90,7 @@ async fn htlc_redeemed_or_refunded<A: Asset>(
     _htlc_params: HtlcParams<Ethereum, A>,
     htlc_deployment: &Deployed<Ethereum>,
     _: &Funded<Ethereum, A>,
+    start_of_swap: Timestamp,
 ) -> anyhow::Result<Either<Redeemed<Ethereum>, Refunded<Ethereum>>> {
     let redeemed = {
         let connector = connector.clone();
@@ -99,7 +111,7 @@ async fn htlc_redeemed_or_refunded<A: Asset>(
             let TransactionAndReceipt {
                 transaction,
                 receipt,
-            } = matching_transaction(connector, pattern, None)
+            } = matching_transaction(connector, pattern, Some(start_of_swap.into()))
                 .await
                 .context("failed to find transaction to redeem from htlc")?;
             let log = receipt
@@ -133,7 +145,7 @@ async fn htlc_redeemed_or_refunded<A: Asset>(
         };
 
         let TransactionAndReceipt { transaction, .. } =
-            matching_transaction(connector, pattern, None)
+            matching_transaction(connector, pattern, Some(start_of_swap.into()))
                 .await
                 .context("failed to find transaction to refund from htlc")?;
 
@@ -163,6 +175,7 @@ mod erc20 {
         async fn htlc_deployed(
             &self,
             htlc_params: HtlcParams<Ethereum, asset::Erc20>,
+            start_of_swap: Timestamp,
         ) -> anyhow::Result<Deployed<Ethereum>> {
             let connector = self.clone();
             let pattern = TransactionPattern {
@@ -174,7 +187,7 @@ mod erc20 {
                 events: None,
             };
             let TransactionAndReceipt { transaction, .. } =
-                matching_transaction(connector, pattern, None)
+                matching_transaction(connector, pattern, Some(start_of_swap.into()))
                     .await
                     .context("failed to find transaction to deploy htlc")?;
 
@@ -188,6 +201,7 @@ mod erc20 {
             &self,
             htlc_params: HtlcParams<Ethereum, asset::Erc20>,
             htlc_deployment: &Deployed<Ethereum>,
+            start_of_swap: Timestamp,
         ) -> anyhow::Result<Funded<Ethereum, asset::Erc20>> {
             let connector = self.clone();
             let events = Some(vec![Event {
@@ -212,7 +226,7 @@ mod erc20 {
                     transaction_data_length: None,
                     events,
                 },
-                None,
+                Some(start_of_swap.into()),
             )
             .await
             .context("failed to find transaction to fund htlc")?;
@@ -233,9 +247,16 @@ mod erc20 {
             htlc_params: HtlcParams<Ethereum, asset::Erc20>,
             htlc_deployment: &Deployed<Ethereum>,
             htlc_funding: &Funded<Ethereum, asset::Erc20>,
+            start_of_swap: Timestamp,
         ) -> anyhow::Result<Either<Redeemed<Ethereum>, Refunded<Ethereum>>> {
-            htlc_redeemed_or_refunded(self.clone(), htlc_params, htlc_deployment, htlc_funding)
-                .await
+            htlc_redeemed_or_refunded(
+                self.clone(),
+                htlc_params,
+                htlc_deployment,
+                htlc_funding,
+                start_of_swap,
+            )
+            .await
         }
     }
 }
@@ -5,6 +5,7 @@
 use crate::{
     asset::Asset,
     swap_protocols::rfc003::{create_swap::HtlcParams, ledger::Ledger, Secret},
+    timestamp::Timestamp,
 };
 use futures_core::future::Either;
 use serde::{Deserialize, Serialize};
@@ -32,18 +33,29 @@ pub struct Refunded<L: Ledger> {
     pub transaction: L::Transaction,
 }
 
+/// Watches a ledger for the events of an HTLC.
+///
+/// `start_of_swap` is the time the swap was accepted, the ledger is scanned
+/// back until this time so events that happened while cnd was not running
+/// are found as well.
 #[async_trait::async_trait]
 pub trait HtlcEvents<L: Ledger, A: Asset>: Send + Sync + 'static {
-    async fn htlc_deployed(&self, htlc_params: HtlcParams<L, A>) -> anyhow::Result<Deployed<L>>;
+    async fn htlc_deployed(
+        &self,
+        htlc_params: HtlcParams<L, A>,
+        start_of_swap: Timestamp,
+    ) -> anyhow::Result<Deployed<L>>;
     async fn htlc_funded(
         &self,
         htlc_params: HtlcParams<L, A>,
         htlc_deployment: &Deployed<L>,
+        start_of_swap: Timestamp,
     ) -> anyhow::Result<Funded<L, A>>;
     async fn htlc_redeemed_or_refunded(
         &self,
         htlc_params: HtlcParams<L, A>,
         htlc_deployment: &Deployed<L>,
         htlc_funding: &Funded<L, A>,
+        start_of_swap: Timestamp,
     ) -> anyhow::Result<Either<Redeemed<L>, Refunded<L>>>;
 }
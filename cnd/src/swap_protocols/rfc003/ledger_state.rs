@@ -48,6 +48,11 @@ pub enum LedgerState<L: Ledger, A: Asset> {
         fund_transaction: L::Transaction,
         asset: A,
     },
+    /// The HTLC was not funded before its expiry.
+    Expired {
+        htlc_location: Option<L::HtlcLocation>,
+        deploy_transaction: Option<L::Transaction>,
+    },
 }
 
 impl<L: Ledger, A: Asset> LedgerState<L, A> {
@@ -162,6 +167,30 @@ impl<L: Ledger, A: Asset> LedgerState<L, A> {
             ),
         }
     }
+
+    /// Marks the HTLC as expired if it was not funded, any other state is left
+    /// untouched.
+    pub fn transition_to_expired(&mut self) {
+        match std::mem::replace(self, LedgerState::NotDeployed) {
+            LedgerState::NotDeployed => {
+                *self = LedgerState::Expired {
+                    htlc_location: None,
+                    deploy_transaction: None,
+                }
+            }
+            LedgerState::Deployed {
+                deploy_transaction,
+                htlc_location,
+            } => {
+                *self = LedgerState::Expired {
+                    htlc_location: Some(htlc_location),
+                    deploy_transaction: Some(deploy_transaction),
+                }
+            }
+            // an HTLC that was funded in time is not affected by its expiry
+            other => *self = other,
+        }
+    }
 }
 
 impl Default for HtlcState {
@@ -173,13 +202,14 @@ impl Default for HtlcState {
 #[cfg(test)]
 impl quickcheck::Arbitrary for HtlcState {
     fn arbitrary<G: quickcheck::Gen>(g: &mut G) -> Self {
-        match g.next_u32() % 6 {
+        match g.next_u32() % 7 {
             0 => HtlcState::NotDeployed,
             1 => HtlcState::Deployed,
             2 => HtlcState::Funded,
             3 => HtlcState::Redeemed,
             4 => HtlcState::Refunded,
             5 => HtlcState::IncorrectlyFunded,
+            6 => HtlcState::Expired,
             _ => unreachable!(),
         }
     }
@@ -188,10 +218,67 @@ impl quickcheck::Arbitrary for HtlcState {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{asset, swap_protocols::ledger::Bitcoin};
+
+    fn transaction() -> bitcoin::Transaction {
+        bitcoin::Transaction {
+            version: 1,
+            lock_time: 0,
+            input: vec![],
+            output: vec![],
+        }
+    }
+
     #[test]
     fn not_deployed_serializes_correctly_to_json() {
         let state = HtlcState::NotDeployed;
         let serialized = serde_json::to_string(&state).unwrap();
         assert_eq!(serialized, r#""NOT_DEPLOYED""#);
     }
+
+    #[test]
+    fn deployed_htlc_transitions_to_expired() {
+        let mut state = LedgerState::<Bitcoin, asset::Bitcoin>::Deployed {
+            htlc_location: bitcoin::OutPoint::null(),
+            deploy_transaction: transaction(),
+        };
+
+        state.transition_to_expired();
+
+        assert_eq!(HtlcState::from(state), HtlcState::Expired);
+    }
+
+    #[test]
+    fn not_deployed_htlc_transitions_to_expired() {
+        let mut state = LedgerState::<Bitcoin, asset::Bitcoin>::NotDeployed;
+
+        state.transition_to_expired();
+
+        assert_eq!(state, LedgerState::Expired {
+            htlc_location: None,
+            deploy_transaction: None,
+        });
+    }
+
+    #[test]
+    fn funded_htlc_is_not_affected_by_expiry() {
+        let funded = LedgerState::<Bitcoin, asset::Bitcoin>::Funded {
+            htlc_location: bitcoin::OutPoint::null(),
+            deploy_transaction: transaction(),
+            fund_transaction: transaction(),
+            asset: asset::Bitcoin::from_sat(100_000_000),
+        };
+        let mut state = funded.clone();
+
+        state.transition_to_expired();
+
+        assert_eq!(state, funded);
+    }
+
+    #[test]
+    fn expired_serializes_correctly_to_json() {
+        let state = HtlcState::Expired;
+        let serialized = serde_json::to_string(&state).unwrap();
+        assert_eq!(serialized, r#""EXPIRED""#);
+    }
 }
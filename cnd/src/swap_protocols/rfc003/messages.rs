@@ -78,14 +78,37 @@ pub struct DeclineResponseBody {
     pub reason: Option<SwapDeclineReason>,
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    Eq,
+    Hash,
+    Serialize,
+    Deserialize,
+    strum_macros::Display,
+    strum_macros::EnumString,
+)]
 #[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "kebab_case")]
 pub enum SwapDeclineReason {
     UnsatisfactoryRate,
+    UnsatisfactoryQuantity,
+    UnsatisfactoryExpiry,
+    ExpiryTooSoon,
+    InsufficientExpiryGap,
+    ZeroQuantity,
+    UnknownChainId,
+    UnwatchedChain,
+    ProposalExpired,
     UnsupportedProtocol,
     UnsupportedSwap,
     MissingMandatoryHeader,
     BadJsonField,
+    /// A reason sent by a peer that this version of cnd does not know.
+    #[serde(other)]
+    Unknown,
 }
 
 pub trait IntoAcceptMessage<AL: Ledger, BL: Ledger> {
@@ -96,6 +119,40 @@ pub trait IntoAcceptMessage<AL: Ledger, BL: Ledger> {
     ) -> Accept<AL, BL>;
 }
 
+#[cfg(test)]
+impl
+    Request<
+        crate::swap_protocols::ledger::Bitcoin,
+        crate::swap_protocols::ledger::Ethereum,
+        crate::asset::Bitcoin,
+        crate::asset::Ether,
+    >
+{
+    /// A request to swap `sat` satoshi for `wei` wei on the default ledgers.
+    pub fn bitcoin_for_ether(sat: u64, wei: u64, alpha_expiry: u32, beta_expiry: u32) -> Self {
+        Request {
+            swap_id: SwapId::default(),
+            alpha_ledger: Default::default(),
+            beta_ledger: Default::default(),
+            alpha_asset: crate::asset::Bitcoin::from_sat(sat),
+            beta_asset: crate::asset::Ether::from_wei(crate::ethereum::U256::from(wei)),
+            hash_function: HashFunction::Sha256,
+            alpha_ledger_refund_identity: crate::bitcoin::PublicKey::new(
+                "02c2a8efce029526d364c2cf39d89e3cdda05e5df7b2cbfc098b4e3d02b70b5275"
+                    .parse()
+                    .unwrap(),
+            ),
+            beta_ledger_redeem_identity: crate::ethereum::Address::zero(),
+            alpha_expiry: Timestamp::from(alpha_expiry),
+            beta_expiry: Timestamp::from(beta_expiry),
+            secret_hash: crate::swap_protocols::rfc003::Secret::from(
+                *b"hello world, you are beautiful!!",
+            )
+            .hash(),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -146,6 +203,43 @@ mod tests {
         assert_eq!(response, expected_response);
     }
 
+    #[test]
+    fn serialize_decline_body_with_validation_and_policy_reasons() {
+        use SwapDeclineReason::*;
+
+        let cases = vec![
+            (UnsatisfactoryQuantity, "unsatisfactory-quantity"),
+            (UnsatisfactoryExpiry, "unsatisfactory-expiry"),
+            (ExpiryTooSoon, "expiry-too-soon"),
+            (InsufficientExpiryGap, "insufficient-expiry-gap"),
+            (ZeroQuantity, "zero-quantity"),
+            (UnknownChainId, "unknown-chain-id"),
+            (UnwatchedChain, "unwatched-chain"),
+            (ProposalExpired, "proposal-expired"),
+        ];
+
+        for (reason, expected) in cases {
+            let decline_response_body = DeclineResponseBody {
+                reason: Some(reason),
+            };
+
+            let response = serde_json::to_string(&decline_response_body).unwrap();
+            let expected_response = format!(r#"{{"reason":"{}"}}"#, expected);
+
+            assert_eq!(response, expected_response, "{:?}", reason);
+        }
+    }
+
+    #[test]
+    fn deserialize_decline_body_with_unknown_reason() {
+        let decline_response_body: DeclineResponseBody =
+            serde_json::from_str(r#"{"reason":"out-of-office"}"#).unwrap();
+
+        assert_eq!(decline_response_body, DeclineResponseBody {
+            reason: Some(SwapDeclineReason::Unknown),
+        });
+    }
+
     #[test]
     fn serialize_decline_body_bad_json_field() {
         let decline_response_body = DeclineResponseBody {
@@ -10,6 +10,7 @@ pub mod events;
 pub mod ledger_state;
 pub mod messages;
 pub mod state_store;
+pub mod validation;
 
 pub mod actions;
 mod actor_state;
@@ -2,7 +2,12 @@ use crate::swap_protocols::{
     rfc003::{create_swap::SwapEvent, ActorState},
     swap_id::SwapId,
 };
-use std::{any::Any, cmp::Ordering, collections::HashMap, sync::Mutex};
+use std::{
+    any::Any,
+    cmp::Ordering,
+    collections::{HashMap, HashSet},
+    sync::Mutex,
+};
 
 #[derive(Debug, Clone, Copy, thiserror::Error)]
 pub enum Error {
@@ -14,11 +19,15 @@ pub trait StateStore: Send + Sync + 'static {
     fn insert<A: ActorState>(&self, key: SwapId, value: A);
     fn get<A: ActorState>(&self, key: &SwapId) -> Result<Option<A>, Error>;
     fn update<A: ActorState>(&self, key: &SwapId, update: SwapEvent<A::AL, A::BL, A::AA, A::BA>);
+    /// Marks a swap as degraded while its ledgers cannot be watched.
+    fn set_degraded(&self, key: &SwapId, degraded: bool);
+    fn is_degraded(&self, key: &SwapId) -> bool;
 }
 
 #[derive(Default, Debug)]
 pub struct InMemoryStateStore {
     states: Mutex<HashMap<SwapId, Box<dyn Any + Send + Sync>>>,
+    degraded: Mutex<HashSet<SwapId>>,
 }
 
 impl StateStore for InMemoryStateStore {
@@ -75,6 +84,7 @@ impl StateStore for InMemoryStateStore {
             SwapEvent::AlphaRefunded(refunded) => actor_state
                 .alpha_ledger_mut()
                 .transition_to_refunded(refunded),
+            SwapEvent::AlphaExpired => actor_state.alpha_ledger_mut().transition_to_expired(),
             SwapEvent::BetaDeployed(deployed) => actor_state
                 .beta_ledger_mut()
                 .transition_to_deployed(deployed),
@@ -96,10 +106,25 @@ impl StateStore for InMemoryStateStore {
             SwapEvent::BetaRefunded(refunded) => actor_state
                 .beta_ledger_mut()
                 .transition_to_refunded(refunded),
+            SwapEvent::BetaExpired => actor_state.beta_ledger_mut().transition_to_expired(),
         }
 
         self.insert(key.clone(), actor_state)
     }
+
+    fn set_degraded(&self, key: &SwapId, degraded: bool) {
+        let mut swaps = self.degraded.lock().unwrap();
+        if degraded {
+            swaps.insert(*key);
+        } else {
+            swaps.remove(key);
+        }
+    }
+
+    fn is_degraded(&self, key: &SwapId) -> bool {
+        let swaps = self.degraded.lock().unwrap();
+        swaps.contains(key)
+    }
 }
 
 #[cfg(test)]
@@ -163,4 +188,16 @@ mod tests {
             .unwrap();
         assert_that(&res).contains_value(state);
     }
+
+    #[test]
+    fn degraded_swap_can_recover() {
+        let state_store = InMemoryStateStore::default();
+        let id = SwapId::default();
+
+        state_store.set_degraded(&id, true);
+        assert_that(&state_store.is_degraded(&id)).is_true();
+
+        state_store.set_degraded(&id, false);
+        assert_that(&state_store.is_degraded(&id)).is_false();
+    }
 }
@@ -0,0 +1,268 @@
+use crate::{
+    asset::{Asset, AssetKind},
+    config::Validation,
+    swap_protocols::{
+        ledger::LedgerKind,
+        rfc003::{messages::SwapDeclineReason, Ledger, Request},
+    },
+    timestamp::Timestamp,
+};
+
+/// A request parameter that makes the swap unsafe or impossible to execute.
+#[derive(Debug, Clone, Copy, PartialEq, thiserror::Error)]
+pub enum Violation {
+    #[error("the {0} expiry is only {1} seconds away")]
+    ExpiryTooSoon(&'static str, u32),
+    #[error("the alpha expiry is {0} seconds after the beta expiry")]
+    InsufficientExpiryGap(i64),
+    #[error("the {0} quantity is zero")]
+    ZeroQuantity(&'static str),
+    #[error("the {0} ledger has the unknown chain id {1}")]
+    UnknownChainId(&'static str, u32),
+    #[error("the {0} ledger is on chain {1} which no configured node watches")]
+    UnwatchedChain(&'static str, u32),
+}
+
+impl Violation {
+    pub fn decline_reason(self) -> SwapDeclineReason {
+        match self {
+            Violation::ExpiryTooSoon(..) => SwapDeclineReason::ExpiryTooSoon,
+            Violation::InsufficientExpiryGap(_) => SwapDeclineReason::InsufficientExpiryGap,
+            Violation::ZeroQuantity(_) => SwapDeclineReason::ZeroQuantity,
+            Violation::UnknownChainId(..) => SwapDeclineReason::UnknownChainId,
+            Violation::UnwatchedChain(..) => SwapDeclineReason::UnwatchedChain,
+        }
+    }
+}
+
+/// Checks the safety invariants of RFC003 on the given request.
+///
+/// The alpha HTLC must expire after the beta HTLC, otherwise Alice could
+/// refund the alpha asset after redeeming the beta asset. Both HTLCs must
+/// not have expired yet. Both ledgers must be on a chain cnd has a node for,
+/// otherwise the HTLCs could not be watched.
+pub fn validate<AL: Ledger, BL: Ledger, AA: Asset, BA: Asset>(
+    request: &Request<AL, BL, AA, BA>,
+    config: &Validation,
+    now: Timestamp,
+) -> Vec<Violation> {
+    let mut violations = Vec::new();
+
+    let alpha_expiry = i64::from(request.alpha_expiry);
+    let beta_expiry = i64::from(request.beta_expiry);
+    let now = i64::from(now);
+
+    let time_to_alpha_expiry = alpha_expiry - now;
+    if time_to_alpha_expiry <= 0 {
+        violations.push(Violation::ExpiryTooSoon(
+            "alpha",
+            clamp(time_to_alpha_expiry),
+        ));
+    }
+
+    let time_to_beta_expiry = beta_expiry - now;
+    if time_to_beta_expiry <= 0 || time_to_beta_expiry < i64::from(config.min_time_to_beta_expiry) {
+        violations.push(Violation::ExpiryTooSoon("beta", clamp(time_to_beta_expiry)));
+    }
+
+    let gap = alpha_expiry - beta_expiry;
+    if gap <= 0 || gap < i64::from(config.min_expiry_gap) {
+        violations.push(Violation::InsufficientExpiryGap(gap));
+    }
+
+    if is_zero(request.alpha_asset.into()) {
+        violations.push(Violation::ZeroQuantity("alpha"));
+    }
+    if is_zero(request.beta_asset.into()) {
+        violations.push(Violation::ZeroQuantity("beta"));
+    }
+
+    if let Some(violation) = chain_violation("alpha", request.alpha_ledger.into(), config) {
+        violations.push(violation);
+    }
+    if let Some(violation) = chain_violation("beta", request.beta_ledger.into(), config) {
+        violations.push(violation);
+    }
+
+    violations
+}
+
+/// An Ethereum ledger must be on a known chain that one of the configured
+/// nodes is connected to.
+fn chain_violation(
+    name: &'static str,
+    ledger: LedgerKind,
+    config: &Validation,
+) -> Option<Violation> {
+    let chain_id = ethereum_chain_id(ledger)?;
+
+    if !config.known_chain_ids.contains(&chain_id) {
+        Some(Violation::UnknownChainId(name, chain_id))
+    } else if !config.watched_chain_ids.contains(&chain_id) {
+        Some(Violation::UnwatchedChain(name, chain_id))
+    } else {
+        None
+    }
+}
+
+fn ethereum_chain_id(ledger: LedgerKind) -> Option<u32> {
+    match ledger {
+        LedgerKind::Ethereum(ethereum) => Some(ethereum.chain_id.into()),
+        LedgerKind::Bitcoin(_) => None,
+    }
+}
+
+fn is_zero(asset: AssetKind) -> bool {
+    match asset {
+        AssetKind::Bitcoin(bitcoin) => bitcoin.as_sat() == 0,
+        AssetKind::Ether(ether) => ether.wei().is_zero(),
+        AssetKind::Erc20(erc20) => erc20.quantity.0.is_zero(),
+    }
+}
+
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn clamp(seconds: i64) -> u32 {
+    std::cmp::max(seconds, 0) as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        asset,
+        ethereum::{Address, U256},
+        swap_protocols::{
+            ledger::{ethereum::ChainId, Bitcoin, Ethereum},
+            rfc003::Secret,
+            HashFunction, SwapId,
+        },
+    };
+
+    const NOW: u32 = 1_000_000;
+
+    fn request() -> Request<Bitcoin, Ethereum, asset::Bitcoin, asset::Ether> {
+        Request::bitcoin_for_ether(100_000_000, 1_000_000, NOW + 7200, NOW + 3600)
+    }
+
+    #[test]
+    fn valid_request_has_no_violations() {
+        let violations = validate(&request(), &Validation::default(), Timestamp::from(NOW));
+
+        assert_eq!(violations, vec![]);
+    }
+
+    #[test]
+    fn beta_expiry_after_alpha_expiry_is_a_violation() {
+        let request = Request {
+            alpha_expiry: Timestamp::from(NOW + 3600),
+            beta_expiry: Timestamp::from(NOW + 7200),
+            ..request()
+        };
+
+        let violations = validate(&request, &Validation::default(), Timestamp::from(NOW));
+
+        assert_eq!(violations, vec![Violation::InsufficientExpiryGap(-3600)]);
+    }
+
+    #[test]
+    fn expiry_gap_below_configured_minimum_is_a_violation() {
+        let config = Validation {
+            min_expiry_gap: 3601,
+            ..Validation::default()
+        };
+
+        let violations = validate(&request(), &config, Timestamp::from(NOW));
+
+        assert_eq!(violations, vec![Violation::InsufficientExpiryGap(3600)]);
+    }
+
+    #[test]
+    fn expired_beta_expiry_is_a_violation() {
+        let violations = validate(
+            &request(),
+            &Validation::default(),
+            Timestamp::from(NOW + 3600),
+        );
+
+        assert_eq!(violations, vec![Violation::ExpiryTooSoon("beta", 0)]);
+    }
+
+    #[test]
+    fn zero_quantity_is_a_violation() {
+        let request = Request {
+            beta_asset: asset::Ether::zero(),
+            ..request()
+        };
+
+        let violations = validate(&request, &Validation::default(), Timestamp::from(NOW));
+
+        assert_eq!(violations, vec![Violation::ZeroQuantity("beta")]);
+    }
+
+    #[test]
+    fn unknown_chain_id_is_a_violation() {
+        let request = Request {
+            beta_ledger: Ethereum::new(ChainId::new(1337)),
+            ..request()
+        };
+
+        let violations = validate(&request, &Validation::default(), Timestamp::from(NOW));
+
+        assert_eq!(violations, vec![Violation::UnknownChainId("beta", 1337)]);
+    }
+
+    #[test]
+    fn configured_chain_id_is_known() {
+        let request = Request {
+            beta_ledger: Ethereum::new(ChainId::new(1337)),
+            ..request()
+        };
+        let config = Validation {
+            known_chain_ids: vec![1337],
+            watched_chain_ids: vec![1337],
+            ..Validation::default()
+        };
+
+        let violations = validate(&request, &config, Timestamp::from(NOW));
+
+        assert_eq!(violations, vec![]);
+    }
+
+    #[test]
+    fn ethereum_ledgers_on_different_watched_chains_are_valid() {
+        let request = Request {
+            swap_id: SwapId::default(),
+            alpha_ledger: Ethereum::new(ChainId::mainnet()),
+            beta_ledger: Ethereum::new(ChainId::ropsten()),
+            alpha_asset: asset::Ether::from_wei(U256::from(1_000_000)),
+            beta_asset: asset::Ether::from_wei(U256::from(1_000_000)),
+            hash_function: HashFunction::Sha256,
+            alpha_ledger_refund_identity: Address::zero(),
+            beta_ledger_redeem_identity: Address::zero(),
+            alpha_expiry: Timestamp::from(NOW + 7200),
+            beta_expiry: Timestamp::from(NOW + 3600),
+            secret_hash: Secret::from(*b"hello world, you are beautiful!!").hash(),
+        };
+
+        let config = Validation {
+            watched_chain_ids: vec![1, 3],
+            ..Validation::default()
+        };
+
+        let violations = validate(&request, &config, Timestamp::from(NOW));
+
+        assert_eq!(violations, vec![]);
+    }
+
+    #[test]
+    fn unwatched_chain_is_a_violation() {
+        let request = Request {
+            beta_ledger: Ethereum::new(ChainId::ropsten()),
+            ..request()
+        };
+
+        let violations = validate(&request, &Validation::default(), Timestamp::from(NOW));
+
+        assert_eq!(violations, vec![Violation::UnwatchedChain("beta", 3)]);
+    }
+}
@@ -1,3 +1,4 @@
+use chrono::NaiveDateTime;
 use serde::{Deserialize, Serialize};
 use std::time::SystemTime;
 
@@ -28,6 +29,14 @@ impl From<u32> for Timestamp {
     }
 }
 
+impl From<NaiveDateTime> for Timestamp {
+    // This will work for the next 20 years
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn from(item: NaiveDateTime) -> Self {
+        Self(item.timestamp() as u32)
+    }
+}
+
 impl From<Timestamp> for u32 {
     fn from(item: Timestamp) -> Self {
         item.0
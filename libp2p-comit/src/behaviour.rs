@@ -1,21 +1,22 @@
 use crate::{
-    frame::{OutboundRequest, Response},
+    frame::{FrameLimits, OutboundNotification, OutboundRequest, ValidatedInboundNotification},
     handler::{
-        self, InboundMessage, OutboundMessage, PendingInboundResponse, ProtocolInEvent,
-        ProtocolOutEvent,
+        self, CloseReason, InboundMessage, OutboundMessage, PendingInboundResponse,
+        PendingResponse, ProtocolInEvent, ProtocolOutEvent, RequestError, DEFAULT_REQUEST_TIMEOUT,
     },
-    ComitHandler, PendingInboundRequest, PendingOutboundRequest,
+    ComitHandler, PendingInboundRequest, PendingOutboundRequest, RequestType,
 };
 use futures::{
     stream::Stream,
     sync::mpsc::{self, UnboundedReceiver, UnboundedSender},
-    Async, Future,
+    Async,
 };
 use libp2p_core::{ConnectedPoint, Multiaddr, PeerId};
 use libp2p_swarm::{NetworkBehaviour, NetworkBehaviourAction, PollParameters};
 use std::{
     collections::{hash_map::Entry, HashMap, HashSet},
     marker::PhantomData,
+    time::{Duration, Instant},
 };
 use tokio::prelude::{AsyncRead, AsyncWrite};
 
@@ -38,6 +39,26 @@ pub enum BehaviourOutEvent {
         request: PendingInboundRequest,
         peer_id: PeerId,
     },
+    /// `peer_id` sent us a notification, which is not answered.
+    InboundNotification {
+        notification: ValidatedInboundNotification,
+        peer_id: PeerId,
+    },
+    /// A connection to `peer_id` has been established, `endpoint` tells
+    /// whether we dialed the peer or the peer dialed us.
+    PeerConnected {
+        peer_id: PeerId,
+        endpoint: ConnectedPoint,
+    },
+    /// The last connection to `peer_id` has been closed.
+    PeerDisconnected { peer_id: PeerId },
+}
+
+/// How many requests a single peer can send within a period of time.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RequestLimit {
+    pub max_requests: u32,
+    pub period: Duration,
 }
 
 /// Network behaviour that handles the COMIT messaging protocol.
@@ -49,7 +70,22 @@ pub struct Comit<TSubstream> {
     events: UnboundedReceiver<NetworkBehaviourAction<ProtocolInEvent, BehaviourOutEvent>>,
 
     known_request_headers: HashMap<String, HashSet<String>>,
+    known_notification_headers: HashMap<String, HashSet<String>>,
     connections: HashMap<PeerId, ConnectionState>,
+    known_addresses: HashMap<PeerId, HashSet<Multiaddr>>,
+    request_timeout: Duration,
+    /// Request types that are answered within their own timeout.
+    request_timeouts: HashMap<String, Duration>,
+    frame_limits: FrameLimits,
+
+    request_limit: Option<RequestLimit>,
+    /// The start of the current period and the number of requests received
+    /// within it for each peer.
+    request_counts: HashMap<PeerId, (Instant, u32)>,
+    /// Request types that do not count towards the `RequestLimit`.
+    unlimited_request_types: HashSet<String>,
+    banned_peers: HashSet<PeerId>,
+    max_connections: Option<usize>,
 }
 
 impl<TSubstream> Comit<TSubstream> {
@@ -61,37 +97,231 @@ impl<TSubstream> Comit<TSubstream> {
             events_sender: sender,
             events: receiver,
             known_request_headers,
+            known_notification_headers: HashMap::new(),
             connections: HashMap::new(),
+            known_addresses: HashMap::new(),
+            request_timeout: DEFAULT_REQUEST_TIMEOUT,
+            request_timeouts: HashMap::new(),
+            frame_limits: FrameLimits::default(),
+            request_limit: None,
+            request_counts: HashMap::new(),
+            unlimited_request_types: HashSet::new(),
+            banned_peers: HashSet::new(),
+            max_connections: None,
+        }
+    }
+
+    /// Accept requests of type `R`, which can be decoded through
+    /// `PendingInboundRequest::decode`.
+    pub fn with_request_type<R: RequestType>(mut self) -> Self {
+        let headers = R::HEADERS
+            .iter()
+            .map(|header| (*header).to_owned())
+            .collect();
+        self.known_request_headers
+            .insert(R::NAME.to_owned(), headers);
+
+        if !R::RATE_LIMITED {
+            self.unlimited_request_types.insert(R::NAME.to_owned());
+        }
+        if let Some(timeout) = R::TIMEOUT {
+            self.request_timeouts.insert(R::NAME.to_owned(), timeout);
+        }
+
+        self
+    }
+
+    /// Accept notifications of the given types together with the headers we
+    /// understand for each of them. Without it, all notifications are
+    /// rejected.
+    pub fn with_known_notification_headers(
+        self,
+        known_notification_headers: HashMap<String, HashSet<String>>,
+    ) -> Self {
+        Self {
+            known_notification_headers,
+            ..self
+        }
+    }
+
+    /// Fail requests with `RequestError::Timeout` if the peer does not answer
+    /// within `request_timeout` once we are connected, unless their type
+    /// comes with its own `RequestType::TIMEOUT`.
+    pub fn with_request_timeout(self, request_timeout: Duration) -> Self {
+        Self {
+            request_timeout,
+            ..self
+        }
+    }
+
+    /// Reject frames and requests of peers that exceed `frame_limits` with an
+    /// error response and disconnect from them.
+    pub fn with_frame_limits(self, frame_limits: FrameLimits) -> Self {
+        Self {
+            frame_limits,
+            ..self
+        }
+    }
+
+    /// Ignore requests of peers that send more than the given number of
+    /// requests within a period of time.
+    pub fn with_request_limit(self, request_limit: RequestLimit) -> Self {
+        Self {
+            request_limit: Some(request_limit),
+            ..self
+        }
+    }
+
+    /// Close inbound connections while connections to `max_connections` other
+    /// peers are established.
+    pub fn with_connection_limit(self, max_connections: usize) -> Self {
+        Self {
+            max_connections: Some(max_connections),
+            ..self
+        }
+    }
+
+    /// Ignore all requests from `peer_id`, returns `false` if the peer was
+    /// already banned.
+    pub fn ban_peer(&mut self, peer_id: PeerId) -> bool {
+        self.banned_peers.insert(peer_id)
+    }
+
+    /// Accept requests from `peer_id` again, returns `false` if the peer was
+    /// not banned.
+    pub fn unban_peer(&mut self, peer_id: &PeerId) -> bool {
+        self.banned_peers.remove(peer_id)
+    }
+
+    pub fn banned_peers(&self) -> impl Iterator<Item = &PeerId> {
+        self.banned_peers.iter()
+    }
+
+    /// Remember an address under which `peer_id` can be reached.
+    ///
+    /// Known addresses are tried after any address hint whenever we need to
+    /// dial `peer_id`, which allows requests to be sent to peers that were
+    /// learned about through other means, e.g. local peer discovery. If
+    /// requests to `peer_id` are waiting for a connection, it is dialed again.
+    pub fn add_address(&mut self, peer_id: PeerId, address: Multiaddr) {
+        let is_new = self
+            .known_addresses
+            .entry(peer_id.clone())
+            .or_insert_with(HashSet::new)
+            .insert(address);
+
+        if let Some(ConnectionState::Connecting { .. }) = self.connections.get(&peer_id) {
+            if is_new {
+                self.events_sender
+                    .unbounded_send(NetworkBehaviourAction::DialPeer { peer_id })
+                    .expect("we own the receiver");
+            }
         }
     }
 
+    /// Connect to `peer_id` at its known addresses without sending anything,
+    /// unless we are connected to it already or do not know how to reach it.
+    pub fn dial(&mut self, peer_id: PeerId) {
+        if self.connections.contains_key(&peer_id) || !self.known_addresses.contains_key(&peer_id) {
+            return;
+        }
+
+        self.events_sender
+            .unbounded_send(NetworkBehaviourAction::DialPeer { peer_id })
+            .expect("we own the receiver");
+    }
+
+    /// Whether we are connected to `peer_id` or know of any address to dial it.
+    pub fn knows_peer(&self, peer_id: &PeerId) -> bool {
+        self.connections.contains_key(peer_id) || self.known_addresses.contains_key(peer_id)
+    }
+
+    /// Fail all requests that wait for a connection to `peer_id` with
+    /// `RequestError::DialFailure`, e.g. because no address of it could be
+    /// found.
+    pub fn cancel_dial(&mut self, peer_id: &PeerId) {
+        match self.connections.remove(peer_id) {
+            Some(ConnectionState::Connecting { pending_events, .. }) => {
+                fail_requests(pending_events, RequestError::DialFailure)
+            }
+            Some(connected) => {
+                self.connections.insert(peer_id.clone(), connected);
+            }
+            None => {}
+        }
+    }
+
+    /// Forget an address previously added through `add_address`.
+    pub fn remove_address(&mut self, peer_id: &PeerId, address: &Multiaddr) {
+        if let Entry::Occupied(mut entry) = self.known_addresses.entry(peer_id.clone()) {
+            entry.get_mut().remove(address);
+
+            if entry.get().is_empty() {
+                entry.remove();
+            }
+        }
+    }
+
+    /// Send `request` to a peer, dialing it first if we are not connected.
+    ///
+    /// Requests to peers without any address hint or known address wait until
+    /// an address is added through `add_address` or the dial is cancelled
+    /// through `cancel_dial`.
     pub fn send_request(
         &mut self,
         dial_information: (PeerId, Option<Multiaddr>),
         request: OutboundRequest,
-    ) -> Box<dyn Future<Item = Response, Error = ()> + Send> {
-        let (peer_id, address_hint) = dial_information;
-        let (sender, receiver) = futures::oneshot();
+    ) -> PendingResponse {
+        let (sender, pending_response) = handler::response_channel();
+
+        self.send_message(
+            dial_information,
+            OutboundMessage::Request(PendingOutboundRequest {
+                request,
+                channel: sender,
+            }),
+        );
+
+        pending_response
+    }
 
-        let request = PendingOutboundRequest {
-            request,
-            channel: sender,
-        };
+    /// Send `notification` to a peer, dialing it first if we are not
+    /// connected.
+    ///
+    /// The peer does not answer notifications, they are dropped if no
+    /// connection can be established.
+    pub fn send_notification(
+        &mut self,
+        dial_information: (PeerId, Option<Multiaddr>),
+        notification: OutboundNotification,
+    ) {
+        self.send_message(
+            dial_information,
+            OutboundMessage::Notification(notification),
+        )
+    }
+
+    fn send_message(
+        &mut self,
+        dial_information: (PeerId, Option<Multiaddr>),
+        message: OutboundMessage,
+    ) {
+        let (peer_id, address_hint) = dial_information;
 
         match self.connections.entry(peer_id.clone()) {
             Entry::Vacant(entry) => {
-                self.events_sender
-                    .unbounded_send(NetworkBehaviourAction::DialPeer { peer_id })
-                    .expect("we own the receiver");
+                if address_hint.is_some() || self.known_addresses.contains_key(&peer_id) {
+                    self.events_sender
+                        .unbounded_send(NetworkBehaviourAction::DialPeer { peer_id })
+                        .expect("we own the receiver");
+                }
 
                 let address_hints = address_hint
                     .map(|address| vec![address])
                     .unwrap_or_else(Vec::new);
 
                 entry.insert(ConnectionState::Connecting {
-                    pending_events: vec![ProtocolInEvent::Message(OutboundMessage::Request(
-                        request,
-                    ))],
+                    pending_events: vec![ProtocolInEvent::Message(message)],
                     address_hints,
                 });
             }
@@ -103,10 +333,20 @@ impl<TSubstream> Comit<TSubstream> {
                         pending_events,
                         address_hints,
                     } => {
-                        pending_events
-                            .push(ProtocolInEvent::Message(OutboundMessage::Request(request)));
+                        pending_events.push(ProtocolInEvent::Message(message));
 
                         if let Some(address) = address_hint {
+                            if address_hints.is_empty()
+                                && !self.known_addresses.contains_key(&peer_id)
+                            {
+                                // We did not know how to dial the peer until now.
+                                self.events_sender
+                                    .unbounded_send(NetworkBehaviourAction::DialPeer {
+                                        peer_id: peer_id.clone(),
+                                    })
+                                    .expect("we own the receiver");
+                            }
+
                             // We insert at the front because we consider the new address to be the
                             // most likely one to succeed. The order of this vector is important
                             // when returning it from `addresses_of_peer` because it will be tried
@@ -118,19 +358,13 @@ impl<TSubstream> Comit<TSubstream> {
                         self.events_sender
                             .unbounded_send(NetworkBehaviourAction::SendEvent {
                                 peer_id,
-                                event: ProtocolInEvent::Message(OutboundMessage::Request(request)),
+                                event: ProtocolInEvent::Message(message),
                             })
                             .expect("we own the receiver");
                     }
                 }
             }
         }
-
-        Box::new(receiver.map_err(|_| {
-            log::warn!(
-                "Sender of response future was unexpectedly dropped before response was received."
-            )
-        }))
     }
 
     pub fn connected_peers(&mut self) -> impl Iterator<Item = (PeerId, Vec<Multiaddr>)> {
@@ -147,6 +381,93 @@ impl<TSubstream> Comit<TSubstream> {
 
         addresses.into_iter()
     }
+
+    /// Whether a request received from `peer_id` at `now` should be handed to
+    /// the application.
+    fn admit_request(&mut self, peer_id: &PeerId, now: Instant) -> bool {
+        if self.banned_peers.contains(peer_id) {
+            return false;
+        }
+
+        let limit = match self.request_limit {
+            Some(limit) => limit,
+            None => return true,
+        };
+
+        // Only peers that sent requests within the current period need to be
+        // remembered.
+        if !self.request_counts.contains_key(peer_id) {
+            self.request_counts.retain(|_, (period_start, _)| {
+                now.saturating_duration_since(*period_start) < limit.period
+            });
+        }
+
+        let (period_start, count) = self
+            .request_counts
+            .entry(peer_id.clone())
+            .or_insert((now, 0));
+
+        if now.duration_since(*period_start) >= limit.period {
+            *period_start = now;
+            *count = 0;
+        }
+
+        *count += 1;
+        *count <= limit.max_requests
+    }
+
+    /// Whether a request of type `request_type` received from `peer_id` at
+    /// `now` should be handed to the application.
+    fn admit_request_of_type(
+        &mut self,
+        peer_id: &PeerId,
+        request_type: &str,
+        now: Instant,
+    ) -> bool {
+        if self.unlimited_request_types.contains(request_type) {
+            !self.banned_peers.contains(peer_id)
+        } else {
+            self.admit_request(peer_id, now)
+        }
+    }
+
+    /// Whether the connection to `peer_id` over `endpoint` has to be closed
+    /// because too many connections are established already.
+    fn exceeds_connection_limit(&self, peer_id: &PeerId, endpoint: &ConnectedPoint) -> bool {
+        let max_connections = match (self.max_connections, endpoint) {
+            (Some(max_connections), ConnectedPoint::Listener { .. }) => max_connections,
+            _ => return false,
+        };
+
+        let established = self
+            .connections
+            .iter()
+            .filter(|(peer, state)| {
+                if let ConnectionState::Connected { .. } = state {
+                    *peer != peer_id
+                } else {
+                    false
+                }
+            })
+            .count();
+
+        established >= max_connections
+    }
+}
+
+fn fail_requests(pending_events: Vec<ProtocolInEvent>, error: RequestError) {
+    for event in pending_events {
+        match event {
+            ProtocolInEvent::Message(OutboundMessage::Request(PendingOutboundRequest {
+                channel,
+                ..
+            })) => channel.fail(error),
+            ProtocolInEvent::Message(OutboundMessage::Notification(notification)) => {
+                log::debug!("dropping notification {:?}: {}", notification, error)
+            }
+            ProtocolInEvent::Close(_) => {}
+        }
+    }
 }
 
 impl<TSubstream> NetworkBehaviour for Comit<TSubstream>
@@ -158,28 +479,58 @@ where
 
     fn new_handler(&mut self) -> Self::ProtocolsHandler {
         ComitHandler::new(self.known_request_headers.clone())
+            .with_known_notification_headers(self.known_notification_headers.clone())
+            .with_request_timeout(self.request_timeout)
+            .with_request_timeouts(self.request_timeouts.clone())
+            .with_frame_limits(self.frame_limits)
     }
 
     fn addresses_of_peer(&mut self, peer_id: &PeerId) -> Vec<Multiaddr> {
-        self.connections
-            .iter()
-            .find_map(|(candidate, addresses)| {
-                if candidate == peer_id {
-                    Some(addresses)
-                } else {
-                    None
-                }
-            })
+        let mut addresses = self
+            .connections
+            .get(peer_id)
             .map(|connection_state| match connection_state {
                 ConnectionState::Connecting { address_hints, .. } => address_hints.clone(),
                 ConnectionState::Connected { addresses } => addresses.iter().cloned().collect(),
             })
-            .unwrap_or_else(Vec::new)
+            .unwrap_or_else(Vec::new);
+
+        // Known addresses go last because the ones above are more specific to the
+        // current connection attempt.
+        if let Some(known_addresses) = self.known_addresses.get(peer_id) {
+            for address in known_addresses {
+                if !addresses.contains(address) {
+                    addresses.push(address.clone());
+                }
+            }
+        }
+
+        addresses
     }
 
     fn inject_connected(&mut self, peer_id: PeerId, endpoint: ConnectedPoint) {
         log::debug!("connected to {} at {:?}", peer_id, endpoint);
 
+        let action = if self.exceeds_connection_limit(&peer_id, &endpoint) {
+            log::info!(
+                "closing the connection to {}: connection limit reached",
+                peer_id
+            );
+
+            NetworkBehaviourAction::SendEvent {
+                peer_id: peer_id.clone(),
+                event: ProtocolInEvent::Close(CloseReason::ConnectionLimit),
+            }
+        } else {
+            NetworkBehaviourAction::GenerateEvent(BehaviourOutEvent::PeerConnected {
+                peer_id: peer_id.clone(),
+                endpoint: endpoint.clone(),
+            })
+        };
+        self.events_sender
+            .unbounded_send(action)
+            .expect("we own the receiver");
+
         let address = match endpoint {
             ConnectedPoint::Dialer { address } => address,
             ConnectedPoint::Listener { send_back_addr, .. } => send_back_addr,
@@ -224,6 +575,12 @@ where
         }
     }
 
+    fn inject_dial_failure(&mut self, peer_id: &PeerId) {
+        log::debug!("failed to dial {}", peer_id);
+
+        self.cancel_dial(peer_id);
+    }
+
     fn inject_disconnected(&mut self, peer_id: &PeerId, endpoint: ConnectedPoint) {
         log::debug!("disconnected from {} at {:?}", peer_id, endpoint);
 
@@ -239,13 +596,33 @@ where
             if !addresses.is_empty() {
                 self.connections
                     .insert(peer_id.clone(), ConnectionState::Connected { addresses });
+                return;
             }
+
+            self.events_sender
+                .unbounded_send(NetworkBehaviourAction::GenerateEvent(
+                    BehaviourOutEvent::PeerDisconnected {
+                        peer_id: peer_id.clone(),
+                    },
+                ))
+                .expect("we own the receiver");
         }
     }
 
     fn inject_node_event(&mut self, peer: PeerId, event: ProtocolOutEvent) {
         match event {
             ProtocolOutEvent::Message(InboundMessage::Request(request)) => {
+                if !self.admit_request_of_type(
+                    &peer,
+                    request.request.request_type(),
+                    Instant::now(),
+                ) {
+                    // Dropping the request drops its response channel, which closes the
+                    // substream without an answer.
+                    log::warn!("ignoring request from banned or rate limited peer {}", peer);
+                    return;
+                }
+
                 self.events_sender
                     .unbounded_send(NetworkBehaviourAction::GenerateEvent(
                         BehaviourOutEvent::PendingInboundRequest {
@@ -255,12 +632,28 @@ where
                     ))
                     .expect("we own the receiver");
             }
+            ProtocolOutEvent::Message(InboundMessage::Notification(notification)) => {
+                if !self.admit_request(&peer, Instant::now()) {
+                    log::warn!(
+                        "ignoring notification from banned or rate limited peer {}",
+                        peer
+                    );
+                    return;
+                }
+
+                self.events_sender
+                    .unbounded_send(NetworkBehaviourAction::GenerateEvent(
+                        BehaviourOutEvent::InboundNotification {
+                            notification,
+                            peer_id: peer,
+                        },
+                    ))
+                    .expect("we own the receiver");
+            }
             ProtocolOutEvent::Message(InboundMessage::Response(PendingInboundResponse {
                 response,
                 channel,
-            })) => {
-                let _ = channel.send(response);
-            }
+            })) => channel.send(response),
             ProtocolOutEvent::Error(handler::Error::MalformedJson(error)) => {
                 log::error!("failure in communication with {}: {:?}", peer, error);
             }
@@ -289,6 +682,13 @@ where
                     error
                 );
             }
+            ProtocolOutEvent::Error(handler::Error::UnknownNotificationType(error)) => {
+                log::error!(
+                    "received frame with unknown notification type from {}, {:?}",
+                    peer,
+                    error
+                );
+            }
             ProtocolOutEvent::Error(handler::Error::UnknownFrameType) => {
                 log::error!("received frame with unknown type from {}", peer);
             }
@@ -299,6 +699,9 @@ where
                     frame
                 );
             }
+            ProtocolOutEvent::Error(handler::Error::LimitExceeded(limit)) => {
+                log::warn!("disconnecting from {}, {}", peer, limit);
+            }
             ProtocolOutEvent::Error(handler::Error::MalformedFrame(error)) => {
                 log::error!("received malformed frame from {}, {:?}", peer, error);
             }
@@ -321,3 +724,127 @@ where
             .map(|item| item.expect("unbounded channel never ends"))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::Future;
+    use spectral::prelude::*;
+
+    #[derive(Debug)]
+    struct Unlimited;
+
+    impl RequestType for Unlimited {
+        const NAME: &'static str = "UNLIMITED";
+        const HEADERS: &'static [&'static str] = &[];
+        const RATE_LIMITED: bool = false;
+
+        type Headers = crate::NoHeaders;
+        type Body = serde::de::IgnoredAny;
+
+        fn decline(_: &crate::MalformedRequest) -> crate::frame::Response {
+            crate::frame::Response::empty()
+        }
+    }
+
+    fn comit() -> Comit<()> {
+        Comit::new(HashMap::new()).with_request_limit(RequestLimit {
+            max_requests: 2,
+            period: Duration::from_secs(60),
+        })
+    }
+
+    #[test]
+    fn requests_above_the_limit_are_not_admitted_until_the_period_ends() {
+        let mut comit = comit();
+        let peer_id = PeerId::random();
+        let other_peer_id = PeerId::random();
+        let start = Instant::now();
+
+        assert_that(&comit.admit_request(&peer_id, start)).is_true();
+        assert_that(&comit.admit_request(&peer_id, start)).is_true();
+        assert_that(&comit.admit_request(&peer_id, start + Duration::from_secs(59))).is_false();
+        assert_that(&comit.admit_request(&other_peer_id, start)).is_true();
+        assert_that(&comit.admit_request(&peer_id, start + Duration::from_secs(60))).is_true();
+    }
+
+    #[test]
+    fn request_counts_of_peers_whose_period_ended_are_dropped() {
+        let mut comit = comit();
+        let peer_id = PeerId::random();
+        let other_peer_id = PeerId::random();
+        let start = Instant::now();
+
+        comit.admit_request(&peer_id, start);
+        comit.admit_request(&other_peer_id, start + Duration::from_secs(60));
+
+        assert_that(&comit.request_counts.contains_key(&peer_id)).is_false();
+        assert_that(&comit.request_counts.contains_key(&other_peer_id)).is_true();
+    }
+
+    #[test]
+    fn inbound_connections_above_the_limit_are_closed() {
+        let mut comit = comit().with_connection_limit(1);
+        let peer_id = PeerId::random();
+        let other_peer_id = PeerId::random();
+        let address: Multiaddr = "/ip4/127.0.0.1/tcp/9939".parse().unwrap();
+        let inbound = ConnectedPoint::Listener {
+            local_addr: address.clone(),
+            send_back_addr: "/ip4/127.0.0.1/tcp/52114".parse().unwrap(),
+        };
+        let outbound = ConnectedPoint::Dialer { address };
+
+        assert_that(&comit.exceeds_connection_limit(&peer_id, &inbound)).is_false();
+
+        comit
+            .connections
+            .insert(peer_id.clone(), ConnectionState::Connected {
+                addresses: HashSet::new(),
+            });
+
+        assert_that(&comit.exceeds_connection_limit(&peer_id, &inbound)).is_false();
+        assert_that(&comit.exceeds_connection_limit(&other_peer_id, &inbound)).is_true();
+        assert_that(&comit.exceeds_connection_limit(&other_peer_id, &outbound)).is_false();
+    }
+
+    #[test]
+    fn requests_of_unlimited_types_are_admitted_above_the_limit() {
+        let mut comit = comit().with_request_type::<Unlimited>();
+        let peer_id = PeerId::random();
+        let now = Instant::now();
+
+        assert_that(&comit.admit_request_of_type(&peer_id, "PING", now)).is_true();
+        assert_that(&comit.admit_request_of_type(&peer_id, "PING", now)).is_true();
+        assert_that(&comit.admit_request_of_type(&peer_id, "PING", now)).is_false();
+        assert_that(&comit.admit_request_of_type(&peer_id, "UNLIMITED", now)).is_true();
+
+        assert_that(&comit.ban_peer(peer_id.clone())).is_true();
+        assert_that(&comit.admit_request_of_type(&peer_id, "UNLIMITED", now)).is_false();
+    }
+
+    #[test]
+    fn requests_of_banned_peers_are_not_admitted() {
+        let mut comit = comit();
+        let peer_id = PeerId::random();
+        let now = Instant::now();
+
+        assert_that(&comit.ban_peer(peer_id.clone())).is_true();
+        assert_that(&comit.admit_request(&peer_id, now)).is_false();
+
+        assert_that(&comit.unban_peer(&peer_id)).is_true();
+        assert_that(&comit.admit_request(&peer_id, now)).is_true();
+    }
+
+    #[test]
+    fn pending_requests_fail_when_the_dial_is_cancelled() {
+        let mut comit = comit();
+        let peer_id = PeerId::random();
+
+        let response = comit.send_request((peer_id.clone(), None), OutboundRequest::new("PING"));
+        comit.cancel_dial(&peer_id);
+
+        assert_that(&response.wait())
+            .is_err()
+            .is_equal_to(RequestError::DialFailure);
+    }
+}
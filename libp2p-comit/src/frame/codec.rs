@@ -1,22 +1,69 @@
 use crate::Frame;
-use bytes::BytesMut;
-use std::io;
+use bytes::{BufMut, BytesMut};
+use serde_json::Value as JsonValue;
+use std::{convert::TryFrom, io};
 use tokio_codec::{Decoder, Encoder};
 
 #[derive(Debug, thiserror::Error)]
 pub enum CodecError {
     #[error("serde JSON: ")]
     Json(#[from] serde_json::Error),
+    #[error("serde CBOR: ")]
+    Cbor(#[from] serde_cbor::Error),
     #[error("io: ")]
     IO(#[from] io::Error),
+    #[error("limit exceeded: {0}")]
+    LimitExceeded(#[from] LimitExceeded),
 }
 
-#[derive(Debug)]
-pub struct JsonFrameCodec;
+/// The limits frames received from a peer have to stay within. Frames we send
+/// are held to the same `max_frame_length`, the peer would reject them
+/// otherwise.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FrameLimits {
+    /// The maximum number of bytes of a frame, excluding the newline or the
+    /// length prefix.
+    pub max_frame_length: usize,
+    /// The maximum number of headers of a request.
+    pub max_headers: usize,
+    /// The maximum number of nested arrays and objects in the payload of a
+    /// frame.
+    ///
+    /// The depth is checked once the frame has been parsed. While parsing,
+    /// the stack is protected by the recursion limit of `serde_json` and
+    /// `serde_cbor`, which refuse to parse anything nested deeper than 128
+    /// levels.
+    pub max_nesting_depth: usize,
+}
 
-impl Default for JsonFrameCodec {
+impl Default for FrameLimits {
     fn default() -> Self {
-        Self {}
+        Self {
+            max_frame_length: 64 * 1024,
+            max_headers: 32,
+            max_nesting_depth: 16,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, thiserror::Error)]
+pub enum LimitExceeded {
+    #[error("frame is longer than {0} bytes")]
+    FrameLength(usize),
+    #[error("request has more than {0} headers")]
+    HeaderCount(usize),
+    #[error("frame is nested deeper than {0} levels")]
+    NestingDepth(usize),
+}
+
+#[derive(Debug, Default)]
+pub struct JsonFrameCodec {
+    limits: FrameLimits,
+}
+
+impl JsonFrameCodec {
+    pub fn new(limits: FrameLimits) -> Self {
+        Self { limits }
     }
 }
 
@@ -26,6 +73,7 @@ impl Encoder for JsonFrameCodec {
 
     fn encode(&mut self, item: Frame, dst: &mut BytesMut) -> Result<(), CodecError> {
         let mut bytes = serde_json::to_vec(&item)?;
+        ensure_frame_length(&bytes, &self.limits)?;
         bytes.push(b'\n');
 
         dst.extend(bytes);
@@ -39,22 +87,175 @@ impl Decoder for JsonFrameCodec {
     type Error = CodecError;
 
     fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Frame>, CodecError> {
+        let max_frame_length = self.limits.max_frame_length;
+
         match src.iter().position(|b| *b == b'\n') {
+            Some(position) if position > max_frame_length => {
+                Err(LimitExceeded::FrameLength(max_frame_length).into())
+            }
             Some(position) => {
                 let frame_bytes = src.split_to(position + 1);
-                let frame = serde_json::from_slice(frame_bytes.as_ref())?;
+                let frame: Frame = serde_json::from_slice(frame_bytes.as_ref())?;
+
+                if nesting_depth(&frame.payload) > self.limits.max_nesting_depth {
+                    return Err(LimitExceeded::NestingDepth(self.limits.max_nesting_depth).into());
+                }
+
                 Ok(Some(frame))
             }
+            // Without a newline in sight, buffering more bytes cannot produce a valid frame.
+            None if src.len() > max_frame_length => {
+                Err(LimitExceeded::FrameLength(max_frame_length).into())
+            }
             None => Ok(None),
         }
     }
 }
 
+/// Length of the prefix of CBOR frames, a big-endian `u32`.
+const LENGTH_PREFIX: usize = 4;
+
+/// Encodes frames as CBOR, prefixed with their length.
+///
+/// Frames are more compact than with the `JsonFrameCodec` but carry exactly
+/// the same content.
+#[derive(Debug, Default)]
+pub struct CborFrameCodec {
+    limits: FrameLimits,
+}
+
+impl CborFrameCodec {
+    pub fn new(limits: FrameLimits) -> Self {
+        Self { limits }
+    }
+}
+
+impl Encoder for CborFrameCodec {
+    type Item = Frame;
+    type Error = CodecError;
+
+    fn encode(&mut self, item: Frame, dst: &mut BytesMut) -> Result<(), CodecError> {
+        let bytes = serde_cbor::to_vec(&item)?;
+        ensure_frame_length(&bytes, &self.limits)?;
+        let length = u32::try_from(bytes.len())
+            .map_err(|_| LimitExceeded::FrameLength(u32::max_value() as usize))?;
+
+        dst.reserve(LENGTH_PREFIX + bytes.len());
+        dst.put_u32_be(length);
+        dst.extend(bytes);
+
+        Ok(())
+    }
+}
+
+impl Decoder for CborFrameCodec {
+    type Item = Frame;
+    type Error = CodecError;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Frame>, CodecError> {
+        if src.len() < LENGTH_PREFIX {
+            return Ok(None);
+        }
+
+        let length = u32::from_be_bytes([src[0], src[1], src[2], src[3]]) as usize;
+        let max_frame_length = self.limits.max_frame_length;
+
+        if length > max_frame_length {
+            return Err(LimitExceeded::FrameLength(max_frame_length).into());
+        }
+
+        if src.len() < LENGTH_PREFIX + length {
+            src.reserve(LENGTH_PREFIX + length - src.len());
+            return Ok(None);
+        }
+
+        src.split_to(LENGTH_PREFIX);
+        let frame_bytes = src.split_to(length);
+        let frame: Frame = serde_cbor::from_slice(frame_bytes.as_ref())?;
+
+        if nesting_depth(&frame.payload) > self.limits.max_nesting_depth {
+            return Err(LimitExceeded::NestingDepth(self.limits.max_nesting_depth).into());
+        }
+
+        Ok(Some(frame))
+    }
+}
+
+/// The codec of a substream, depending on the protocol negotiated for it.
+#[derive(Debug)]
+pub enum FrameCodec {
+    Json(JsonFrameCodec),
+    Cbor(CborFrameCodec),
+}
+
+impl Default for FrameCodec {
+    fn default() -> Self {
+        FrameCodec::Json(JsonFrameCodec::default())
+    }
+}
+
+impl From<JsonFrameCodec> for FrameCodec {
+    fn from(codec: JsonFrameCodec) -> Self {
+        FrameCodec::Json(codec)
+    }
+}
+
+impl From<CborFrameCodec> for FrameCodec {
+    fn from(codec: CborFrameCodec) -> Self {
+        FrameCodec::Cbor(codec)
+    }
+}
+
+impl Encoder for FrameCodec {
+    type Item = Frame;
+    type Error = CodecError;
+
+    fn encode(&mut self, item: Frame, dst: &mut BytesMut) -> Result<(), CodecError> {
+        match self {
+            FrameCodec::Json(codec) => codec.encode(item, dst),
+            FrameCodec::Cbor(codec) => codec.encode(item, dst),
+        }
+    }
+}
+
+impl Decoder for FrameCodec {
+    type Item = Frame;
+    type Error = CodecError;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Frame>, CodecError> {
+        match self {
+            FrameCodec::Json(codec) => codec.decode(src),
+            FrameCodec::Cbor(codec) => codec.decode(src),
+        }
+    }
+}
+
+fn ensure_frame_length(frame_bytes: &[u8], limits: &FrameLimits) -> Result<(), LimitExceeded> {
+    if frame_bytes.len() > limits.max_frame_length {
+        return Err(LimitExceeded::FrameLength(limits.max_frame_length));
+    }
+
+    Ok(())
+}
+
+/// The nesting depth of a parsed value. Parsing already limits it to 128, the
+/// recursion cannot overflow the stack.
+pub(crate) fn nesting_depth(value: &JsonValue) -> usize {
+    match value {
+        JsonValue::Array(values) => 1 + values.iter().map(nesting_depth).max().unwrap_or(0),
+        JsonValue::Object(values) => 1 + values.values().map(nesting_depth).max().unwrap_or(0),
+        _ => 0,
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
     use super::*;
-    use crate::FrameType;
+    use crate::{
+        frame::{Header, OutboundRequest, Response},
+        FrameType, IntoFrame,
+    };
     use spectral::prelude::*;
 
     #[test]
@@ -136,4 +337,195 @@ mod tests {
             .is_some()
             .is_equal_to(&expected_frame);
     }
+
+    #[test]
+    fn given_a_line_longer_than_the_limit_should_fail_without_waiting_for_the_newline() {
+        let mut codec = JsonFrameCodec::new(FrameLimits {
+            max_frame_length: 16,
+            ..FrameLimits::default()
+        });
+
+        let mut bytes = BytesMut::new();
+        bytes.extend(br#"{"type":"REQUEST","#.as_ref());
+
+        assert_that(&codec.decode(&mut bytes)).is_err();
+    }
+
+    #[test]
+    fn given_a_frame_nested_deeper_than_the_limit_should_fail() {
+        let mut codec = JsonFrameCodec::new(FrameLimits {
+            max_nesting_depth: 2,
+            ..FrameLimits::default()
+        });
+
+        let mut bytes = BytesMut::new();
+        bytes.extend(br#"{"type":"REQUEST","payload":{"body":[[1]]}}"#.as_ref());
+        bytes.extend(b"\n");
+
+        assert_that(&codec.decode(&mut bytes)).is_err();
+
+        bytes.extend(br#"{"type":"REQUEST","payload":{"body":[1]}}"#.as_ref());
+        bytes.extend(b"\n");
+
+        assert_that(&codec.decode(&mut bytes)).is_ok().is_some();
+    }
+
+    #[test]
+    fn given_a_frame_longer_than_the_limit_should_fail_to_encode_it() {
+        let limits = FrameLimits {
+            max_frame_length: 16,
+            ..FrameLimits::default()
+        };
+        let frame = || Frame::new(FrameType::Request, serde_json::json!("a long payload"));
+
+        let mut bytes = BytesMut::new();
+
+        assert_that(&JsonFrameCodec::new(limits).encode(frame(), &mut bytes)).is_err();
+        assert_that(&CborFrameCodec::new(limits).encode(frame(), &mut bytes)).is_err();
+        assert_that(&bytes.is_empty()).is_true();
+    }
+
+    #[test]
+    fn given_not_enough_bytes_cbor_codec_should_wait_for_more() {
+        let mut encoded = BytesMut::new();
+        CborFrameCodec::default()
+            .encode(
+                Frame::new(FrameType::Request, serde_json::Value::Null),
+                &mut encoded,
+            )
+            .unwrap();
+
+        let mut codec = CborFrameCodec::default();
+        let mut bytes = BytesMut::new();
+
+        for byte in encoded.iter().take(encoded.len() - 1) {
+            bytes.extend(&[*byte]);
+            assert_that(&codec.decode(&mut bytes)).is_ok().is_none();
+        }
+
+        bytes.extend(&encoded[encoded.len() - 1..]);
+
+        assert_that(&codec.decode(&mut bytes)).is_ok().is_some();
+        assert_that(&bytes.is_empty()).is_true();
+    }
+
+    #[test]
+    fn given_a_length_prefix_above_the_limit_cbor_codec_should_fail_without_waiting_for_the_frame()
+    {
+        let mut codec = CborFrameCodec::new(FrameLimits {
+            max_frame_length: 16,
+            ..FrameLimits::default()
+        });
+
+        let mut bytes = BytesMut::new();
+        bytes.extend(&17u32.to_be_bytes());
+
+        assert_that(&codec.decode(&mut bytes)).is_err();
+    }
+
+    #[test]
+    fn given_a_frame_nested_deeper_than_the_limit_cbor_codec_should_fail() {
+        let mut bytes = BytesMut::new();
+        CborFrameCodec::default()
+            .encode(
+                Frame::new(FrameType::Request, serde_json::json!({"body": [[1]]})),
+                &mut bytes,
+            )
+            .unwrap();
+
+        let mut codec = CborFrameCodec::new(FrameLimits {
+            max_nesting_depth: 2,
+            ..FrameLimits::default()
+        });
+
+        assert_that(&codec.decode(&mut bytes)).is_err();
+    }
+
+    fn frames() -> Vec<Frame> {
+        vec![
+            OutboundRequest::new("SWAP")
+                .with_header("id", Header::with_str_value("0a1b2c"))
+                .with_header(
+                    "alpha_ledger",
+                    Header::with_str_value("bitcoin")
+                        .with_parameter("network", "regtest")
+                        .unwrap(),
+                )
+                .with_body(serde_json::json!({
+                    "alpha_expiry": 2_000_000_000u32,
+                    "quantity": "100000000",
+                    "nested": [{"negative": -1, "fraction": 0.5, "flag": true, "none": null}]
+                }))
+                .into_frame(),
+            OutboundRequest::new("PING").into_frame(),
+            Response::empty()
+                .with_header("decision", Header::with_str_value("declined"))
+                .with_body(serde_json::json!({ "reason": { "value": "unsatisfactory-rate" } }))
+                .into_frame(),
+            Response::empty().into_frame(),
+            Frame::new(FrameType::Unknown, serde_json::json!("payload")),
+        ]
+    }
+
+    fn roundtrip<
+        C: Encoder<Item = Frame, Error = CodecError> + Decoder<Item = Frame, Error = CodecError>,
+    >(
+        mut codec: C,
+        frame: Frame,
+    ) -> Frame {
+        let mut bytes = BytesMut::new();
+        codec.encode(frame, &mut bytes).unwrap();
+
+        let decoded = codec.decode(&mut bytes).unwrap().unwrap();
+        assert_that(&bytes.is_empty()).is_true();
+
+        decoded
+    }
+
+    #[test]
+    fn frames_roundtrip_identically_through_both_codecs() {
+        for ((frame, json), cbor) in frames().into_iter().zip(frames()).zip(frames()) {
+            let json = roundtrip(JsonFrameCodec::default(), json);
+            let cbor = roundtrip(CborFrameCodec::default(), cbor);
+
+            assert_that(&json).is_equal_to(&frame);
+            assert_that(&cbor).is_equal_to(&frame);
+        }
+    }
+
+    #[test]
+    fn responses_decode_identically_from_both_codecs() {
+        let response = Response::empty()
+            .with_header(
+                "decision",
+                Header::with_str_value("accepted")
+                    .with_parameter("at", 42)
+                    .unwrap(),
+            )
+            .with_body(serde_json::json!({ "beta_ledger_refund_identity": "0x00" }));
+
+        let json = roundtrip(JsonFrameCodec::default(), response.clone().into_frame());
+        let cbor = roundtrip(CborFrameCodec::default(), response.clone().into_frame());
+
+        let from_json: Response = serde_json::from_value(json.payload).unwrap();
+        let from_cbor: Response = serde_json::from_value(cbor.payload).unwrap();
+
+        assert_that(&from_json).is_equal_to(&response);
+        assert_that(&from_cbor).is_equal_to(&response);
+    }
+
+    #[test]
+    fn cbor_frames_are_smaller_than_json_frames() {
+        let mut json = BytesMut::new();
+        let mut cbor = BytesMut::new();
+
+        JsonFrameCodec::default()
+            .encode(frames().remove(0), &mut json)
+            .unwrap();
+        CborFrameCodec::default()
+            .encode(frames().remove(0), &mut cbor)
+            .unwrap();
+
+        assert_that(&cbor.len()).is_less_than(json.len());
+    }
 }
@@ -155,6 +155,10 @@ impl Headers {
         self.inner.is_empty()
     }
 
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
     pub fn with_header(mut self, key: &str, header: Header) -> Self {
         self.inner.insert(key.to_string(), header);
 
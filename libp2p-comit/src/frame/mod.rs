@@ -1,6 +1,7 @@
 mod codec;
 mod header;
+mod notification;
 mod request;
 mod response;
 
-pub use self::{codec::*, header::Header, request::*, response::*};
+pub use self::{codec::*, header::Header, notification::*, request::*, response::*};
@@ -0,0 +1,136 @@
+use crate::{
+    frame::{
+        codec::{FrameLimits, LimitExceeded},
+        header::Header,
+        request::{Request, UnknownMandatoryHeaders},
+    },
+    Frame, FrameType, IntoFrame,
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::{self, Value as JsonValue};
+use std::collections::HashSet;
+
+/// A one-way message, the receiver does not answer it.
+///
+/// Notifications look exactly like requests on the wire except for their
+/// frame type.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct OutboundNotification {
+    #[serde(flatten)]
+    inner: Request,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct UnvalidatedInboundNotification {
+    #[serde(flatten)]
+    inner: Request,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct ValidatedInboundNotification {
+    #[serde(flatten)]
+    inner: Request,
+}
+
+impl OutboundNotification {
+    pub fn new<T: Into<String>>(notification_type: T) -> Self {
+        Self {
+            inner: Request::new(notification_type.into()),
+        }
+    }
+
+    pub fn with_header(self, key: &str, header: Header) -> Self {
+        Self {
+            inner: self.inner.with_header(key, header),
+        }
+    }
+
+    pub fn with_body(self, body: JsonValue) -> Self {
+        Self {
+            inner: self.inner.with_body(body),
+        }
+    }
+}
+
+impl UnvalidatedInboundNotification {
+    pub fn notification_type(&self) -> &str {
+        self.inner.request_type()
+    }
+
+    pub fn ensure_within_limits(&self, limits: &FrameLimits) -> Result<(), LimitExceeded> {
+        self.inner.ensure_within_limits(limits)
+    }
+
+    pub fn ensure_no_unknown_mandatory_headers(
+        self,
+        known_headers: &HashSet<String>,
+    ) -> Result<ValidatedInboundNotification, UnknownMandatoryHeaders> {
+        self.inner
+            .ensure_no_unknown_mandatory_headers(known_headers)
+            .map(|inner| ValidatedInboundNotification { inner })
+    }
+}
+
+impl ValidatedInboundNotification {
+    pub fn notification_type(&self) -> &str {
+        self.inner.request_type()
+    }
+
+    pub fn header(&self, key: &str) -> Option<&Header> {
+        self.inner.header(key)
+    }
+
+    pub fn take_header(&mut self, key: &str) -> Option<Header> {
+        self.inner.take_header(key)
+    }
+
+    pub fn take_body_as<B: DeserializeOwned>(self) -> Result<B, serde_json::Error> {
+        self.inner.take_body_as()
+    }
+}
+
+impl IntoFrame<Frame> for OutboundNotification {
+    fn into_frame(self) -> Frame {
+        // Serializing a notification should never fail because its members are just
+        // Strings and JsonValues
+        let payload = serde_json::to_value(self).unwrap();
+
+        Frame::new(FrameType::Notification, payload)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use spectral::prelude::*;
+
+    #[test]
+    fn notifications_are_serialized_like_requests() {
+        let frame = OutboundNotification::new("FUNDED")
+            .with_header("ledger", Header::with_str_value("bitcoin"))
+            .with_body(serde_json::json!({ "transaction": "0xabc" }))
+            .into_frame();
+
+        assert_that(&serde_json::to_value(&frame).unwrap()).is_equal_to(serde_json::json!({
+            "type": "NOTIFICATION",
+            "payload": {
+                "type": "FUNDED",
+                "headers": { "ledger": "bitcoin" },
+                "body": { "transaction": "0xabc" }
+            }
+        }));
+    }
+
+    #[test]
+    fn unknown_mandatory_headers_are_rejected() {
+        let notification: UnvalidatedInboundNotification = serde_json::from_value(
+            serde_json::json!({ "type": "ABORT", "headers": { "reason": "bored", "at": 42 } }),
+        )
+        .unwrap();
+
+        let mut known_headers = HashSet::new();
+        known_headers.insert("at".to_owned());
+
+        assert_that(&notification.ensure_no_unknown_mandatory_headers(&known_headers)).is_err();
+    }
+}
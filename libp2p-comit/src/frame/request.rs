@@ -1,5 +1,9 @@
 use crate::{
-    frame::header::{Header, Headers},
+    frame::{
+        codec::{FrameLimits, LimitExceeded},
+        header::{Header, Headers},
+    },
+    request_type::MalformedRequest,
     Frame, FrameType, IntoFrame,
 };
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
@@ -26,86 +30,78 @@ pub struct OutboundRequest {
 
 impl ValidatedInboundRequest {
     pub fn request_type(&self) -> &str {
-        self.inner.request_type.as_str()
+        self.inner.request_type()
     }
 
     pub fn header(&self, key: &str) -> Option<&Header> {
-        self.inner.headers.get(key)
+        self.inner.header(key)
     }
 
     pub fn take_header(&mut self, key: &str) -> Option<Header> {
-        self.inner.headers.take(key)
+        self.inner.take_header(key)
     }
 
     pub fn take_body_as<B: DeserializeOwned>(self) -> Result<B, serde_json::Error> {
         self.inner.take_body_as()
     }
+
+    /// Deserialize the headers and the body of the request.
+    ///
+    /// The headers are deserialized from a map of their names to `Header`s.
+    pub fn decode<H: DeserializeOwned, B: DeserializeOwned>(
+        self,
+    ) -> Result<(H, B), MalformedRequest> {
+        let Request { headers, body, .. } = self.inner;
+
+        let headers = serde_json::to_value(headers)
+            .and_then(serde_json::from_value)
+            .map_err(MalformedRequest::Headers)?;
+        let body = B::deserialize(body).map_err(MalformedRequest::Body)?;
+
+        Ok((headers, body))
+    }
 }
 
 impl OutboundRequest {
     pub fn new<T: Into<String>>(request_type: T) -> Self {
         Self {
-            inner: Request {
-                request_type: request_type.into(),
-                headers: Headers::default(),
-                body: serde_json::Value::Null,
-            },
+            inner: Request::new(request_type.into()),
         }
     }
 
-    pub fn with_header(self, key: &str, header: Header) -> Self {
-        let request = self.inner;
+    pub fn request_type(&self) -> &str {
+        self.inner.request_type()
+    }
 
+    pub fn with_header(self, key: &str, header: Header) -> Self {
         Self {
-            inner: Request {
-                headers: request.headers.with_header(key, header),
-                ..request
-            },
+            inner: self.inner.with_header(key, header),
         }
     }
 
     pub fn with_body(self, body: JsonValue) -> Self {
         Self {
-            inner: Request { body, ..self.inner },
+            inner: self.inner.with_body(body),
         }
     }
 }
 
 impl UnvalidatedInboundRequest {
     pub fn request_type(&self) -> &str {
-        self.inner.request_type.as_str()
+        self.inner.request_type()
+    }
+
+    pub fn ensure_within_limits(&self, limits: &FrameLimits) -> Result<(), LimitExceeded> {
+        self.inner.ensure_within_limits(limits)
     }
 
     pub fn ensure_no_unknown_mandatory_headers(
         self,
         known_headers: &HashSet<String>,
     ) -> Result<ValidatedInboundRequest, UnknownMandatoryHeaders> {
-        let (parsed_headers, unknown_mandatory_headers) = self.inner.headers.into_iter().fold(
-            (Headers::default(), UnknownMandatoryHeaders::default()),
-            |(parsed_headers, mut unknown_headers), (key, header)| {
-                if key.must_understand && !known_headers.contains(&key.value) {
-                    unknown_headers.add(key.value);
-
-                    (parsed_headers, unknown_headers)
-                } else {
-                    let parsed_headers = parsed_headers.with_header(&key.value, header);
-
-                    (parsed_headers, unknown_headers)
-                }
-            },
-        );
-
-        if !unknown_mandatory_headers.is_empty() {
-            return Err(unknown_mandatory_headers);
-        }
-
-        Ok(ValidatedInboundRequest {
-            inner: Request {
-                request_type: self.inner.request_type,
-                headers: parsed_headers,
-                body: self.inner.body,
-            },
-        })
+        self.inner
+            .ensure_no_unknown_mandatory_headers(known_headers)
+            .map(|inner| ValidatedInboundRequest { inner })
     }
 }
 
@@ -122,8 +118,9 @@ impl UnknownMandatoryHeaders {
     }
 }
 
+/// The payload of request and notification frames.
 #[derive(Debug, PartialEq, Serialize, Deserialize)]
-struct Request {
+pub(super) struct Request {
     #[serde(rename = "type")]
     request_type: String,
     #[serde(default)]
@@ -135,9 +132,78 @@ struct Request {
 }
 
 impl Request {
+    pub(super) fn new(request_type: String) -> Self {
+        Self {
+            request_type,
+            headers: Headers::default(),
+            body: serde_json::Value::Null,
+        }
+    }
+
+    pub(super) fn request_type(&self) -> &str {
+        self.request_type.as_str()
+    }
+
+    pub(super) fn header(&self, key: &str) -> Option<&Header> {
+        self.headers.get(key)
+    }
+
+    pub(super) fn take_header(&mut self, key: &str) -> Option<Header> {
+        self.headers.take(key)
+    }
+
+    pub(super) fn with_header(self, key: &str, header: Header) -> Self {
+        Self {
+            headers: self.headers.with_header(key, header),
+            ..self
+        }
+    }
+
+    pub(super) fn with_body(self, body: JsonValue) -> Self {
+        Self { body, ..self }
+    }
+
     pub fn take_body_as<B: DeserializeOwned>(self) -> Result<B, serde_json::Error> {
         B::deserialize(self.body)
     }
+
+    pub(super) fn ensure_within_limits(&self, limits: &FrameLimits) -> Result<(), LimitExceeded> {
+        if self.headers.len() > limits.max_headers {
+            return Err(LimitExceeded::HeaderCount(limits.max_headers));
+        }
+
+        Ok(())
+    }
+
+    pub(super) fn ensure_no_unknown_mandatory_headers(
+        self,
+        known_headers: &HashSet<String>,
+    ) -> Result<Request, UnknownMandatoryHeaders> {
+        let (parsed_headers, unknown_mandatory_headers) = self.headers.into_iter().fold(
+            (Headers::default(), UnknownMandatoryHeaders::default()),
+            |(parsed_headers, mut unknown_headers), (key, header)| {
+                if key.must_understand && !known_headers.contains(&key.value) {
+                    unknown_headers.add(key.value);
+
+                    (parsed_headers, unknown_headers)
+                } else {
+                    let parsed_headers = parsed_headers.with_header(&key.value, header);
+
+                    (parsed_headers, unknown_headers)
+                }
+            },
+        );
+
+        if !unknown_mandatory_headers.is_empty() {
+            return Err(unknown_mandatory_headers);
+        }
+
+        Ok(Request {
+            request_type: self.request_type,
+            headers: parsed_headers,
+            body: self.body,
+        })
+    }
 }
 
 impl IntoFrame<Frame> for OutboundRequest {
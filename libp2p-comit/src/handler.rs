@@ -1,18 +1,17 @@
 use crate::{
     frame::{
-        self, JsonFrameCodec, OutboundRequest, Response, UnknownMandatoryHeaders,
-        ValidatedInboundRequest,
+        self, FrameLimits, LimitExceeded, OutboundNotification, OutboundRequest, Response,
+        UnknownMandatoryHeaders, ValidatedInboundNotification, ValidatedInboundRequest,
     },
-    protocol::ComitProtocolConfig,
+    protocol::{ComitProtocolConfig, Frames},
     substream::{self, Advance, Advanced},
     ComitHandlerEvent, Frame, IntoFrame,
 };
 use futures::{
     sync::oneshot::{self, Canceled},
     task::Task,
-    Async, Poll,
+    Async, Future, Poll,
 };
-use libp2p_core::Negotiated;
 use libp2p_swarm::{
     KeepAlive, ProtocolsHandler, ProtocolsHandlerEvent, ProtocolsHandlerUpgrErr, SubstreamProtocol,
 };
@@ -20,12 +19,20 @@ use std::{
     collections::{HashMap, HashSet},
     convert::Infallible,
     fmt::Display,
+    time::{Duration, Instant},
 };
 use tokio::{
-    codec::Framed,
     prelude::{AsyncRead, AsyncWrite},
+    timer::Delay,
 };
 
+/// How long a peer has to answer a request if not configured otherwise.
+pub const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// How long the connection to a peer that exceeded the frame limits is kept
+/// open before it is closed, so that our error response can reach it.
+const OFFENDER_GRACE_PERIOD: Duration = Duration::from_secs(1);
+
 #[derive(derivative::Derivative)]
 #[derivative(Debug)]
 pub struct ComitHandler<TSubstream> {
@@ -34,24 +41,46 @@ pub struct ComitHandler<TSubstream> {
     #[derivative(Debug = "ignore")]
     outbound_substreams: Vec<substream::outbound::State<TSubstream>>,
 
-    to_send: Vec<PendingOutboundRequest>,
+    to_send: Vec<OutboundMessage>,
 
     #[derivative(Debug = "ignore")]
     current_task: Option<Task>,
 
-    known_headers: HashMap<String, HashSet<String>>,
+    known_headers: KnownHeaders,
+    request_timeout: Duration,
+    request_timeouts: HashMap<String, Duration>,
+    limits: FrameLimits,
+    /// When and why the connection is going to be closed.
+    #[derivative(Debug = "ignore")]
+    close: Option<(Delay, CloseReason)>,
+}
+
+/// Why a `ComitHandler` closed the connection to its peer.
+///
+/// The handler closes the connection by returning the reason as its error,
+/// which also closes the connection for all other protocols spoken over it.
+#[derive(Debug, Clone, Copy, PartialEq, thiserror::Error)]
+pub enum CloseReason {
+    #[error("the maximum number of connections is established")]
+    ConnectionLimit,
+    #[error("the peer exceeded the frame limits")]
+    LimitExceeded,
 }
 
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
     #[error("malformed frame: ")]
-    MalformedJson(#[from] frame::CodecError),
+    MalformedJson(frame::CodecError),
+    #[error("limit exceeded: {0}")]
+    LimitExceeded(LimitExceeded),
     #[error("dropped response: {0}")]
     DroppedResponseSender(Canceled),
     #[error("unknown mandatory header: {0:?}")]
     UnknownMandatoryHeader(UnknownMandatoryHeaders),
     #[error("unknown request type: {0}")]
     UnknownRequestType(String),
+    #[error("unknown notification type: {0}")]
+    UnknownNotificationType(String),
     #[error("unknown frame type")]
     UnknownFrameType,
     #[error("unexpected frame")]
@@ -62,20 +91,190 @@ pub enum Error {
     UnexpectedEOF,
 }
 
+impl From<frame::CodecError> for Error {
+    fn from(e: frame::CodecError) -> Self {
+        match e {
+            frame::CodecError::LimitExceeded(limit) => Error::LimitExceeded(limit),
+            e => Error::MalformedJson(e),
+        }
+    }
+}
+
 impl From<Canceled> for Error {
     fn from(e: Canceled) -> Self {
         Error::DroppedResponseSender(e)
     }
 }
 
+/// The reasons why a request we sent did not get a response.
+#[derive(Debug, Clone, Copy, PartialEq, thiserror::Error)]
+pub enum RequestError {
+    #[error("failed to connect to the peer")]
+    DialFailure,
+    #[error("the peer did not answer in time")]
+    Timeout,
+    #[error("the substream was closed before the peer answered")]
+    SubstreamClosed,
+    #[error("the peer sent a malformed response")]
+    MalformedResponse,
+}
+
+impl From<&frame::CodecError> for RequestError {
+    fn from(e: &frame::CodecError) -> Self {
+        match e {
+            frame::CodecError::Json(_)
+            | frame::CodecError::Cbor(_)
+            | frame::CodecError::LimitExceeded(_) => RequestError::MalformedResponse,
+            frame::CodecError::IO(_) => RequestError::SubstreamClosed,
+        }
+    }
+}
+
+/// Creates the two halves through which the progress of an outbound request
+/// is reported.
+pub fn response_channel() -> (ResponseSender, PendingResponse) {
+    let (sent_sender, sent_receiver) = oneshot::channel();
+    let (response_sender, response_receiver) = oneshot::channel();
+
+    let sender = ResponseSender {
+        sent: Some(sent_sender),
+        response: response_sender,
+    };
+    let pending_response = PendingResponse {
+        sent: sent_receiver,
+        response: response_receiver,
+    };
+
+    (sender, pending_response)
+}
+
+/// Reports the progress of an outbound request to its `PendingResponse`.
+///
+/// Dropping the sender fails the request with
+/// `RequestError::SubstreamClosed`.
+#[derive(Debug)]
+pub struct ResponseSender {
+    sent: Option<oneshot::Sender<Result<(), RequestError>>>,
+    response: oneshot::Sender<Result<Response, RequestError>>,
+}
+
+impl ResponseSender {
+    /// The request has been written to the substream.
+    pub fn notify_sent(&mut self) {
+        if let Some(sent) = self.sent.take() {
+            let _ = sent.send(Ok(()));
+        }
+    }
+
+    pub fn send(mut self, response: Response) {
+        self.notify_sent();
+        let _ = self.response.send(Ok(response));
+    }
+
+    pub fn fail(mut self, error: RequestError) {
+        if let Some(sent) = self.sent.take() {
+            let _ = sent.send(Err(error));
+        }
+        let _ = self.response.send(Err(error));
+    }
+}
+
+/// The response to a request sent through `Comit::send_request`.
+#[derive(Debug)]
+pub struct PendingResponse {
+    sent: oneshot::Receiver<Result<(), RequestError>>,
+    response: oneshot::Receiver<Result<Response, RequestError>>,
+}
+
+type BoxedFuture<T> = Box<dyn Future<Item = T, Error = RequestError> + Send>;
+
+impl PendingResponse {
+    /// Splits the response into a future that resolves once the request has
+    /// been sent to the peer and one that resolves to the response itself.
+    pub fn split(self) -> (BoxedFuture<()>, BoxedFuture<Response>) {
+        let sent = self.sent.then(flatten_channel_result);
+        let response = self.response.then(flatten_channel_result);
+
+        (Box::new(sent), Box::new(response))
+    }
+}
+
+impl Future for PendingResponse {
+    type Item = Response;
+    type Error = RequestError;
+
+    fn poll(&mut self) -> Poll<Response, RequestError> {
+        match self.response.poll() {
+            Ok(Async::Ready(result)) => result.map(Async::Ready),
+            Ok(Async::NotReady) => Ok(Async::NotReady),
+            Err(Canceled) => Err(RequestError::SubstreamClosed),
+        }
+    }
+}
+
+fn flatten_channel_result<T>(
+    result: Result<Result<T, RequestError>, Canceled>,
+) -> Result<T, RequestError> {
+    result.unwrap_or(Err(RequestError::SubstreamClosed))
+}
+
+/// The types of requests and notifications we accept, together with the
+/// headers we understand for each of them.
+#[derive(Debug, Clone, Default)]
+pub struct KnownHeaders {
+    pub requests: HashMap<String, HashSet<String>>,
+    pub notifications: HashMap<String, HashSet<String>>,
+}
+
 impl<TSubstream> ComitHandler<TSubstream> {
-    pub fn new(known_headers: HashMap<String, HashSet<String>>) -> Self {
+    pub fn new(known_request_headers: HashMap<String, HashSet<String>>) -> Self {
         Self {
-            known_headers,
+            known_headers: KnownHeaders {
+                requests: known_request_headers,
+                notifications: HashMap::new(),
+            },
             inbound_substreams: Vec::new(),
             outbound_substreams: Vec::new(),
             to_send: Vec::new(),
             current_task: None,
+            request_timeout: DEFAULT_REQUEST_TIMEOUT,
+            request_timeouts: HashMap::new(),
+            limits: FrameLimits::default(),
+            close: None,
+        }
+    }
+
+    /// Accept notifications of the given types, any other notification is
+    /// rejected as `Error::UnknownNotificationType`.
+    pub fn with_known_notification_headers(
+        mut self,
+        known_notification_headers: HashMap<String, HashSet<String>>,
+    ) -> Self {
+        self.known_headers.notifications = known_notification_headers;
+        self
+    }
+
+    /// Reject frames and requests of peers that exceed `limits` and close the
+    /// connection to them.
+    pub fn with_frame_limits(self, limits: FrameLimits) -> Self {
+        Self { limits, ..self }
+    }
+
+    /// Fail outbound requests with `RequestError::Timeout` if the peer does not
+    /// answer within `request_timeout` of the substream being opened.
+    pub fn with_request_timeout(self, request_timeout: Duration) -> Self {
+        Self {
+            request_timeout,
+            ..self
+        }
+    }
+
+    /// Use the given timeouts instead of `request_timeout` for outbound
+    /// requests of their type.
+    pub fn with_request_timeouts(self, request_timeouts: HashMap<String, Duration>) -> Self {
+        Self {
+            request_timeouts,
+            ..self
         }
     }
 }
@@ -83,7 +282,7 @@ impl<TSubstream> ComitHandler<TSubstream> {
 #[derive(Debug)]
 pub struct PendingOutboundRequest {
     pub request: OutboundRequest,
-    pub channel: oneshot::Sender<Response>,
+    pub channel: ResponseSender,
 }
 
 #[derive(Debug)]
@@ -95,13 +294,15 @@ pub struct PendingInboundRequest {
 #[derive(Debug)]
 pub struct PendingInboundResponse {
     pub response: Response,
-    pub channel: oneshot::Sender<Response>,
+    pub channel: ResponseSender,
 }
 
 /// Events that occur 'in' this node (as opposed to events from a peer node).
 #[derive(Debug)]
 pub enum ProtocolInEvent {
     Message(OutboundMessage),
+    /// Close the connection right away.
+    Close(CloseReason),
 }
 
 /// Different kinds of `OutboundOpenInfo` that we may want to pass when emitted
@@ -123,30 +324,29 @@ pub enum ProtocolOutEvent {
 pub enum InboundMessage {
     Request(PendingInboundRequest),
     Response(PendingInboundResponse),
+    Notification(ValidatedInboundNotification),
 }
 
 #[derive(Debug)]
 pub enum OutboundMessage {
     Request(PendingOutboundRequest),
+    Notification(OutboundNotification),
 }
 
 impl<TSubstream: AsyncRead + AsyncWrite> ProtocolsHandler for ComitHandler<TSubstream> {
     type InEvent = ProtocolInEvent;
     type OutEvent = ProtocolOutEvent;
-    type Error = frame::CodecError;
+    type Error = CloseReason;
     type Substream = TSubstream;
     type InboundProtocol = ComitProtocolConfig;
     type OutboundProtocol = ComitProtocolConfig;
     type OutboundOpenInfo = ProtocolOutboundOpenInfo;
 
     fn listen_protocol(&self) -> SubstreamProtocol<Self::InboundProtocol> {
-        SubstreamProtocol::new(ComitProtocolConfig {})
+        SubstreamProtocol::new(ComitProtocolConfig::new(self.limits))
     }
 
-    fn inject_fully_negotiated_inbound(
-        &mut self,
-        stream: Framed<Negotiated<TSubstream>, JsonFrameCodec>,
-    ) {
+    fn inject_fully_negotiated_inbound(&mut self, stream: Frames<TSubstream>) {
         self.inbound_substreams
             .push(substream::inbound::State::WaitingMessage { stream });
 
@@ -157,20 +357,35 @@ impl<TSubstream: AsyncRead + AsyncWrite> ProtocolsHandler for ComitHandler<TSubs
 
     fn inject_fully_negotiated_outbound(
         &mut self,
-        stream: Framed<Negotiated<TSubstream>, JsonFrameCodec>,
+        stream: Frames<TSubstream>,
         outbound_open_info: Self::OutboundOpenInfo,
     ) {
         match outbound_open_info {
             ProtocolOutboundOpenInfo::Message(OutboundMessage::Request(
                 PendingOutboundRequest { request, channel },
             )) => {
+                let timeout = self
+                    .request_timeouts
+                    .get(request.request_type())
+                    .copied()
+                    .unwrap_or(self.request_timeout);
+
                 self.outbound_substreams
                     .push(substream::outbound::State::WaitingSend {
                         frame: request.into_frame(),
                         response_sender: channel,
+                        deadline: Delay::new(Instant::now() + timeout),
                         stream,
                     });
             }
+            ProtocolOutboundOpenInfo::Message(OutboundMessage::Notification(notification)) => {
+                self.outbound_substreams.push(
+                    substream::outbound::State::WaitingSendNotification {
+                        frame: notification.into_frame(),
+                        stream,
+                    },
+                );
+            }
         }
 
         if let Some(task) = &self.current_task {
@@ -180,8 +395,9 @@ impl<TSubstream: AsyncRead + AsyncWrite> ProtocolsHandler for ComitHandler<TSubs
 
     fn inject_event(&mut self, event: Self::InEvent) {
         match event {
-            ProtocolInEvent::Message(OutboundMessage::Request(request)) => {
-                self.to_send.push(request)
+            ProtocolInEvent::Message(message) => self.to_send.push(message),
+            ProtocolInEvent::Close(reason) => {
+                self.close = Some((Delay::new(Instant::now()), reason))
             }
         }
 
@@ -192,9 +408,21 @@ impl<TSubstream: AsyncRead + AsyncWrite> ProtocolsHandler for ComitHandler<TSubs
 
     fn inject_dial_upgrade_error(
         &mut self,
-        _info: Self::OutboundOpenInfo,
-        _error: ProtocolsHandlerUpgrErr<Infallible>,
+        info: Self::OutboundOpenInfo,
+        error: ProtocolsHandlerUpgrErr<Infallible>,
     ) {
+        log::debug!("failed to open a substream: {:?}", error);
+
+        // `RequestError::Timeout` is reserved for peers that do not answer a request in
+        // time.
+        match info {
+            ProtocolOutboundOpenInfo::Message(OutboundMessage::Request(
+                PendingOutboundRequest { channel, .. },
+            )) => channel.fail(RequestError::SubstreamClosed),
+            ProtocolOutboundOpenInfo::Message(OutboundMessage::Notification(notification)) => {
+                log::debug!("dropping notification {:?}", notification)
+            }
+        }
     }
 
     fn connection_keep_alive(&self) -> KeepAlive {
@@ -202,21 +430,36 @@ impl<TSubstream: AsyncRead + AsyncWrite> ProtocolsHandler for ComitHandler<TSubs
     }
 
     fn poll(&mut self) -> Poll<ComitHandlerEvent, Self::Error> {
-        if let Some(request) = self.to_send.pop() {
+        if let Some((deadline, reason)) = &mut self.close {
+            // A failing timer must not keep the connection open.
+            if let Ok(Async::Ready(())) | Err(_) = deadline.poll() {
+                return Err(*reason);
+            }
+        }
+
+        if let Some(message) = self.to_send.pop() {
             return Ok(Async::Ready(
                 ProtocolsHandlerEvent::OutboundSubstreamRequest {
-                    protocol: SubstreamProtocol::new(ComitProtocolConfig {}),
-                    info: ProtocolOutboundOpenInfo::Message(OutboundMessage::Request(request)),
+                    protocol: SubstreamProtocol::new(ComitProtocolConfig::new(self.limits)),
+                    info: ProtocolOutboundOpenInfo::Message(message),
                 },
             ));
         }
 
-        if let Some(result) = poll_substreams(&mut self.outbound_substreams, &self.known_headers) {
-            return result;
+        if let Some(result) = poll_substreams(
+            &mut self.outbound_substreams,
+            &self.known_headers,
+            &self.limits,
+        ) {
+            return self.disconnect_offenders(result);
         }
 
-        if let Some(result) = poll_substreams(&mut self.inbound_substreams, &self.known_headers) {
-            return result;
+        if let Some(result) = poll_substreams(
+            &mut self.inbound_substreams,
+            &self.known_headers,
+            &self.limits,
+        ) {
+            return self.disconnect_offenders(result);
         }
 
         self.current_task = Some(futures::task::current());
@@ -225,17 +468,39 @@ impl<TSubstream: AsyncRead + AsyncWrite> ProtocolsHandler for ComitHandler<TSubs
     }
 }
 
+impl<TSubstream> ComitHandler<TSubstream> {
+    fn disconnect_offenders(
+        &mut self,
+        result: Poll<ComitHandlerEvent, CloseReason>,
+    ) -> Poll<ComitHandlerEvent, CloseReason> {
+        if let Ok(Async::Ready(ProtocolsHandlerEvent::Custom(ProtocolOutEvent::Error(
+            Error::LimitExceeded(_),
+        )))) = &result
+        {
+            if self.close.is_none() {
+                self.close = Some((
+                    Delay::new(Instant::now() + OFFENDER_GRACE_PERIOD),
+                    CloseReason::LimitExceeded,
+                ));
+            }
+        }
+
+        result
+    }
+}
+
 fn poll_substreams<S: Display + Advance>(
     substreams: &mut Vec<S>,
-    known_headers: &HashMap<String, HashSet<String>>,
-) -> Option<Poll<ComitHandlerEvent, frame::CodecError>> {
+    known_headers: &KnownHeaders,
+    limits: &FrameLimits,
+) -> Option<Poll<ComitHandlerEvent, CloseReason>> {
     // We remove each element from `substreams` one by one and add them back.
     for n in (0..substreams.len()).rev() {
         let substream_state = substreams.swap_remove(n);
 
         let log_message = format!("transition from {}", substream_state);
 
-        let Advanced { new_state, event } = substream_state.advance(known_headers);
+        let Advanced { new_state, event } = substream_state.advance(known_headers, limits);
 
         if let Some(new_state) = new_state {
             log::trace!("{} to {}", log_message, new_state);
@@ -253,10 +518,10 @@ fn poll_substreams<S: Display + Advance>(
 mod tests {
     use super::*;
     use crate::{
-        frame::{Header, JsonFrameCodec, OutboundRequest, Response},
+        frame::{FrameCodec, Header, OutboundNotification, OutboundRequest, Response},
         test_harness::{
-            request_with_no_headers, setup_substream, setup_substream_with_json_codec,
-            IntoEventStream, IntoFutureWithResponse, WaitForFrame,
+            request_with_no_headers, setup_substream, setup_substream_with_cbor_codec,
+            setup_substream_with_json_codec, IntoEventStream, IntoFutureWithResponse, WaitForFrame,
         },
     };
     use futures::{Future, Sink, Stream};
@@ -293,6 +558,35 @@ mod tests {
         );
     }
 
+    #[test]
+    fn given_an_inbound_request_over_cbor_handler_sends_response() {
+        let mut runtime = tokio::runtime::Runtime::new().unwrap();
+        let (dialer, listener) = runtime.block_on(setup_substream_with_cbor_codec()).unwrap();
+        let mut handler = ComitHandler::new(request_with_no_headers("PING"));
+
+        handler.inject_fully_negotiated_inbound(listener);
+
+        let send = dialer.send(
+            OutboundRequest::new("PING")
+                .with_body(serde_json::json!({ "quantity": 42 }))
+                .into_frame(),
+        );
+        let dialer = runtime.block_on(send).unwrap();
+
+        let future = handler.into_future_with_response(
+            Response::empty().with_header("decision", Header::with_str_value("declined")),
+        );
+        runtime.spawn(future);
+
+        let response = runtime.block_on(dialer.wait_for_frame());
+
+        assert_that(&response).is_ok().is_some().is_equal_to(
+            Response::empty()
+                .with_header("decision", Header::with_str_value("declined"))
+                .into_frame(),
+        );
+    }
+
     #[test]
     fn given_inbound_substream_when_unknown_request_should_emit_unknown_request_type() {
         let mut runtime = tokio::runtime::Runtime::new().unwrap();
@@ -363,7 +657,7 @@ mod tests {
         let (dialer, listener) = runtime
             .block_on(setup_substream(
                 LinesCodec::new(),
-                JsonFrameCodec::default(),
+                FrameCodec::default(),
             ))
             .unwrap();
         let mut handler = ComitHandler::new(request_with_no_headers("PING"));
@@ -395,7 +689,7 @@ mod tests {
         let (dialer, listener) = runtime
             .block_on(setup_substream(
                 LinesCodec::new(),
-                JsonFrameCodec::default(),
+                FrameCodec::default(),
             ))
             .unwrap();
         let mut handler = ComitHandler::new(request_with_no_headers("PING"));
@@ -429,7 +723,7 @@ mod tests {
         let (dialer, listener) = runtime
             .block_on(setup_substream(
                 LinesCodec::new(),
-                JsonFrameCodec::default(),
+                FrameCodec::default(),
             ))
             .unwrap();
         let mut handler = ComitHandler::new(request_with_no_headers("PING"));
@@ -461,7 +755,7 @@ mod tests {
         let (dialer, listener) = runtime
             .block_on(setup_substream(
                 LinesCodec::new(),
-                JsonFrameCodec::default(),
+                FrameCodec::default(),
             ))
             .unwrap();
         let mut handler = ComitHandler::new(request_with_no_headers("PING"));
@@ -495,7 +789,7 @@ mod tests {
         let (dialer, listener) = runtime
             .block_on(setup_substream(
                 LinesCodec::new(),
-                JsonFrameCodec::default(),
+                FrameCodec::default(),
             ))
             .unwrap();
         let mut handler = ComitHandler::new(request_with_no_headers("PING"));
@@ -526,14 +820,14 @@ mod tests {
         let mut runtime = tokio::runtime::Runtime::new().unwrap();
         let (dialer, listener) = runtime
             .block_on(setup_substream(
-                JsonFrameCodec::default(),
+                FrameCodec::default(),
                 LinesCodec::new(),
             ))
             .unwrap();
         let mut handler = ComitHandler::new(request_with_no_headers("PING"));
 
         // given an outbound substream
-        let (sender, _receiver) = oneshot::channel();
+        let (sender, _pending_response) = response_channel();
         handler.inject_fully_negotiated_outbound(
             dialer,
             ProtocolOutboundOpenInfo::Message(OutboundMessage::Request(PendingOutboundRequest {
@@ -567,14 +861,14 @@ mod tests {
         let mut runtime = tokio::runtime::Runtime::new().unwrap();
         let (dialer, listener) = runtime
             .block_on(setup_substream(
-                JsonFrameCodec::default(),
+                FrameCodec::default(),
                 LinesCodec::new(),
             ))
             .unwrap();
         let mut handler = ComitHandler::new(request_with_no_headers("PING"));
 
         // given an outbound substream
-        let (sender, _receiver) = oneshot::channel();
+        let (sender, _pending_response) = response_channel();
         handler.inject_fully_negotiated_outbound(
             dialer,
             ProtocolOutboundOpenInfo::Message(OutboundMessage::Request(PendingOutboundRequest {
@@ -606,14 +900,14 @@ mod tests {
         let mut runtime = tokio::runtime::Runtime::new().unwrap();
         let (dialer, listener) = runtime
             .block_on(setup_substream(
-                JsonFrameCodec::default(),
+                FrameCodec::default(),
                 LinesCodec::new(),
             ))
             .unwrap();
         let mut handler = ComitHandler::new(request_with_no_headers("PING"));
 
         // given an outbound substream
-        let (sender, _receiver) = oneshot::channel();
+        let (sender, _pending_response) = response_channel();
         handler.inject_fully_negotiated_outbound(
             dialer,
             ProtocolOutboundOpenInfo::Message(OutboundMessage::Request(PendingOutboundRequest {
@@ -639,4 +933,272 @@ mod tests {
             Some(ProtocolsHandlerEvent::Custom(ProtocolOutEvent::Error(Error::MalformedJson(_))))
         )
     }
+
+    #[test]
+    fn given_an_outbound_request_when_no_answer_in_time_should_fail_with_timeout() {
+        let mut runtime = tokio::runtime::Runtime::new().unwrap();
+        let (dialer, _listener) = runtime.block_on(setup_substream_with_json_codec()).unwrap();
+        let mut handler = ComitHandler::new(request_with_no_headers("PING"))
+            .with_request_timeout(Duration::from_millis(100));
+
+        // given an outbound substream
+        let (sender, pending_response) = response_channel();
+        handler.inject_fully_negotiated_outbound(
+            dialer,
+            ProtocolOutboundOpenInfo::Message(OutboundMessage::Request(PendingOutboundRequest {
+                request: OutboundRequest::new("PING"),
+                channel: sender,
+            })),
+        );
+        runtime.spawn(handler.into_event_stream().for_each(|_| Ok(())));
+
+        // when the listener never answers
+        let (sent, response) = pending_response.split();
+
+        // then the request is sent but times out
+        assert_that(&runtime.block_on(sent)).is_ok();
+        assert_that(&runtime.block_on(response))
+            .is_err()
+            .is_equal_to(RequestError::Timeout);
+    }
+
+    #[test]
+    fn given_a_timeout_for_the_request_type_when_no_answer_in_time_should_fail_with_timeout() {
+        let mut runtime = tokio::runtime::Runtime::new().unwrap();
+        let (dialer, _listener) = runtime.block_on(setup_substream_with_json_codec()).unwrap();
+        let mut handler = ComitHandler::new(request_with_no_headers("PING"))
+            .with_request_timeout(Duration::from_secs(60 * 60))
+            .with_request_timeouts(
+                vec![("PING".to_owned(), Duration::from_millis(100))]
+                    .into_iter()
+                    .collect(),
+            );
+
+        // given an outbound substream
+        let (sender, pending_response) = response_channel();
+        handler.inject_fully_negotiated_outbound(
+            dialer,
+            ProtocolOutboundOpenInfo::Message(OutboundMessage::Request(PendingOutboundRequest {
+                request: OutboundRequest::new("PING"),
+                channel: sender,
+            })),
+        );
+        runtime.spawn(handler.into_event_stream().for_each(|_| Ok(())));
+
+        // when the listener never answers
+        let (_sent, response) = pending_response.split();
+
+        // then the request times out long before the default timeout
+        assert_that(&runtime.block_on(response))
+            .is_err()
+            .is_equal_to(RequestError::Timeout);
+    }
+
+    #[test]
+    fn given_an_outbound_request_when_request_should_fail_with_malformed_response() {
+        let mut runtime = tokio::runtime::Runtime::new().unwrap();
+        let (dialer, listener) = runtime
+            .block_on(setup_substream(
+                FrameCodec::default(),
+                LinesCodec::new(),
+            ))
+            .unwrap();
+        let mut handler = ComitHandler::new(request_with_no_headers("PING"));
+
+        // given an outbound substream
+        let (sender, pending_response) = response_channel();
+        handler.inject_fully_negotiated_outbound(
+            dialer,
+            ProtocolOutboundOpenInfo::Message(OutboundMessage::Request(PendingOutboundRequest {
+                request: OutboundRequest::new("PING"),
+                channel: sender,
+            })),
+        );
+
+        // when receiving a REQUEST instead of a RESPONSE
+        let send = listener
+            .send(r#"{"type": "REQUEST", "payload":{}}"#.to_owned())
+            .map(|_| ())
+            .map_err(|_| ());
+        let _ = runtime.spawn(send);
+
+        let _ = runtime
+            .block_on(handler.into_event_stream().take(1).collect())
+            .unwrap();
+
+        // then
+        assert_that(&runtime.block_on(pending_response))
+            .is_err()
+            .is_equal_to(RequestError::MalformedResponse);
+    }
+
+    #[test]
+    fn given_inbound_request_with_too_many_headers_should_answer_with_error_and_emit_limit_exceeded(
+    ) {
+        let mut runtime = tokio::runtime::Runtime::new().unwrap();
+        let (dialer, listener) = runtime.block_on(setup_substream_with_json_codec()).unwrap();
+        let mut handler =
+            ComitHandler::new(request_with_no_headers("PING")).with_frame_limits(FrameLimits {
+                max_headers: 1,
+                ..FrameLimits::default()
+            });
+
+        // given an inbound substream
+        handler.inject_fully_negotiated_inbound(listener);
+
+        // when receiving a request with more headers than allowed
+        let request = OutboundRequest::new("PING")
+            .with_header("_first", Header::with_str_value("1"))
+            .with_header("_second", Header::with_str_value("2"));
+        let dialer = runtime.block_on(dialer.send(request.into_frame())).unwrap();
+
+        let (event, events) = runtime
+            .block_on(handler.into_event_stream().into_future())
+            .map_err(|(e, _)| e)
+            .unwrap();
+        runtime.spawn(events.for_each(|_| Ok(())));
+
+        // then
+        matches::assert_matches!(
+            event,
+            Some(ProtocolsHandlerEvent::Custom(ProtocolOutEvent::Error(
+                Error::LimitExceeded(LimitExceeded::HeaderCount(1))
+            )))
+        );
+
+        let response = runtime.block_on(dialer.wait_for_frame());
+
+        assert_that(&response).is_ok().is_some().is_equal_to(
+            Response::empty()
+                .with_header("error", Header::with_str_value("limit-exceeded"))
+                .into_frame(),
+        );
+    }
+
+    #[test]
+    fn given_inbound_request_with_too_many_headers_should_close_the_connection() {
+        let mut runtime = tokio::runtime::Runtime::new().unwrap();
+        let (dialer, listener) = runtime.block_on(setup_substream_with_json_codec()).unwrap();
+        let mut handler =
+            ComitHandler::new(request_with_no_headers("PING")).with_frame_limits(FrameLimits {
+                max_headers: 1,
+                ..FrameLimits::default()
+            });
+
+        // given an inbound substream
+        handler.inject_fully_negotiated_inbound(listener);
+
+        // when receiving a request with more headers than allowed
+        let request = OutboundRequest::new("PING")
+            .with_header("_first", Header::with_str_value("1"))
+            .with_header("_second", Header::with_str_value("2"));
+        let _dialer = runtime.block_on(dialer.send(request.into_frame())).unwrap();
+
+        // then the handler closes the connection once the grace period is over
+        let closed = runtime.block_on(futures::future::poll_fn(move || loop {
+            match handler.poll() {
+                Ok(Async::Ready(_)) => continue,
+                Ok(Async::NotReady) => return Ok(Async::NotReady),
+                Err(reason) => return Ok::<_, ()>(Async::Ready(reason)),
+            }
+        }));
+
+        assert_that(&closed)
+            .is_ok()
+            .is_equal_to(CloseReason::LimitExceeded);
+    }
+
+    #[test]
+    fn given_inbound_substream_when_notification_should_emit_notification_without_answering() {
+        let mut runtime = tokio::runtime::Runtime::new().unwrap();
+        let (dialer, listener) = runtime.block_on(setup_substream_with_json_codec()).unwrap();
+        let mut handler = ComitHandler::new(HashMap::new())
+            .with_known_notification_headers(request_with_no_headers("FUNDED"));
+
+        // given an inbound substream
+        handler.inject_fully_negotiated_inbound(listener);
+
+        // when receiving a notification
+        let notification = OutboundNotification::new("FUNDED")
+            .with_body(serde_json::json!({ "transaction": "0xabc" }));
+        let dialer = runtime
+            .block_on(dialer.send(notification.into_frame()))
+            .unwrap();
+
+        let (event, events) = runtime
+            .block_on(handler.into_event_stream().into_future())
+            .map_err(|(e, _)| e)
+            .unwrap();
+        runtime.spawn(events.for_each(|_| Ok(())));
+
+        // then
+        matches::assert_matches!(
+            event,
+            Some(ProtocolsHandlerEvent::Custom(ProtocolOutEvent::Message(
+                InboundMessage::Notification(ref notification)
+            ))) if notification.notification_type() == "FUNDED"
+        );
+
+        // and the substream is closed without an answer
+        assert_that(&runtime.block_on(dialer.wait_for_frame()))
+            .is_ok()
+            .is_none();
+    }
+
+    #[test]
+    fn given_inbound_substream_when_unknown_notification_should_emit_unknown_notification_type() {
+        let mut runtime = tokio::runtime::Runtime::new().unwrap();
+        let (dialer, listener) = runtime.block_on(setup_substream_with_json_codec()).unwrap();
+        let mut handler = ComitHandler::new(request_with_no_headers("FUNDED"));
+
+        // given a substream
+        handler.inject_fully_negotiated_inbound(listener);
+
+        // when receiving a notification of a type only known as a request
+        let send = dialer.send(OutboundNotification::new("FUNDED").into_frame());
+        let _ = runtime.block_on(send).unwrap();
+
+        let events = runtime
+            .block_on(handler.into_event_stream().take(1).collect())
+            .unwrap();
+
+        // then
+        matches::assert_matches!(
+            events.get(0),
+            Some(ProtocolsHandlerEvent::Custom(ProtocolOutEvent::Error(
+                Error::UnknownNotificationType(_)
+            )))
+        )
+    }
+
+    #[test]
+    fn given_an_outbound_notification_should_send_it_and_close_the_substream() {
+        let mut runtime = tokio::runtime::Runtime::new().unwrap();
+        let (dialer, listener) = runtime.block_on(setup_substream_with_json_codec()).unwrap();
+        let mut handler = ComitHandler::new(HashMap::new());
+
+        // given an outbound substream
+        handler.inject_fully_negotiated_outbound(
+            dialer,
+            ProtocolOutboundOpenInfo::Message(OutboundMessage::Notification(
+                OutboundNotification::new("ABORT"),
+            )),
+        );
+        runtime.spawn(handler.into_event_stream().for_each(|_| Ok(())));
+
+        // then the listener receives the notification
+        let (frame, listener) = runtime
+            .block_on(listener.into_future())
+            .map_err(|(e, _)| e)
+            .unwrap();
+
+        assert_that(&frame)
+            .is_some()
+            .is_equal_to(OutboundNotification::new("ABORT").into_frame());
+
+        // and the substream is closed without waiting for an answer
+        assert_that(&runtime.block_on(listener.wait_for_frame()))
+            .is_ok()
+            .is_none();
+    }
 }
@@ -6,17 +6,22 @@ pub mod frame;
 mod behaviour;
 pub mod handler;
 mod protocol;
+mod request_type;
 mod substream;
-#[cfg(test)]
+#[cfg(any(test, feature = "test-harness"))]
 pub mod test_harness;
 
 use serde::{Deserialize, Serialize};
 use serde_json::{self, Value as JsonValue};
 
 pub use self::{
-    behaviour::{BehaviourOutEvent, Comit},
-    handler::{ComitHandler, PendingInboundRequest, PendingOutboundRequest},
-    protocol::{ComitProtocolConfig, Frames},
+    behaviour::{BehaviourOutEvent, Comit, RequestLimit},
+    handler::{
+        ComitHandler, PendingInboundRequest, PendingOutboundRequest, PendingResponse,
+        RequestError,
+    },
+    protocol::{ComitProtocolConfig, Frames, PROTOCOL_VERSIONS},
+    request_type::{MalformedRequest, NoHeaders, PendingTypedRequest, RequestType},
 };
 use crate::handler::{ProtocolOutEvent, ProtocolOutboundOpenInfo};
 use libp2p_swarm::ProtocolsHandlerEvent;
@@ -40,6 +45,7 @@ pub struct Frame {
 pub enum FrameType {
     Request,
     Response,
+    Notification,
 
     // This is currently the fallback to not fail on serialisation if the frame type is unknown
     // Unfortunately serde does not support deserialization into a String when using other
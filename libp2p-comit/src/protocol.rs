@@ -1,23 +1,51 @@
-use crate::frame::{self, JsonFrameCodec};
+use crate::frame::{CborFrameCodec, FrameCodec, FrameLimits, JsonFrameCodec};
 use futures::future::FutureResult;
 use libp2p_core::{InboundUpgrade, Negotiated, OutboundUpgrade, UpgradeInfo};
-use std::{convert::Infallible, iter};
+use std::{convert::Infallible, iter, slice};
 use tokio::{
     codec::{Decoder, Framed},
     prelude::*,
 };
 
-pub type Frames<TSubstream> = Framed<Negotiated<TSubstream>, JsonFrameCodec>;
+/// The versions of the COMIT messaging protocol we speak, the preferred one
+/// first.
+///
+/// Version 1.1.0 adds the `CAPABILITIES` request and notifications. Its `/cbor`
+/// variant encodes frames with the `CborFrameCodec` instead of the
+/// `JsonFrameCodec`.
+pub const PROTOCOL_VERSIONS: &[&[u8]] = &[CBOR_PROTOCOL, b"/comit/1.1.0", b"/comit/1.0.0"];
 
-#[derive(Clone, Copy, Debug)]
-pub struct ComitProtocolConfig {}
+const CBOR_PROTOCOL: &[u8] = b"/comit/1.1.0/cbor";
+
+pub type Frames<TSubstream> = Framed<Negotiated<TSubstream>, FrameCodec>;
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ComitProtocolConfig {
+    limits: FrameLimits,
+}
+
+impl ComitProtocolConfig {
+    pub fn new(limits: FrameLimits) -> Self {
+        Self { limits }
+    }
+
+    fn codec(&self, info: &[u8]) -> FrameCodec {
+        log::trace!("negotiated {}", String::from_utf8_lossy(info));
+
+        if info == CBOR_PROTOCOL {
+            CborFrameCodec::new(self.limits).into()
+        } else {
+            JsonFrameCodec::new(self.limits).into()
+        }
+    }
+}
 
 impl UpgradeInfo for ComitProtocolConfig {
     type Info = &'static [u8];
-    type InfoIter = iter::Once<Self::Info>;
+    type InfoIter = iter::Cloned<slice::Iter<'static, Self::Info>>;
 
     fn protocol_info(&self) -> Self::InfoIter {
-        iter::once(b"/comit/1.0.0")
+        PROTOCOL_VERSIONS.iter().cloned()
     }
 }
 
@@ -30,9 +58,8 @@ where
     type Future = FutureResult<Self::Output, Self::Error>;
 
     #[inline]
-    fn upgrade_inbound(self, socket: Negotiated<TSubstream>, _: Self::Info) -> Self::Future {
-        let codec = frame::JsonFrameCodec::default();
-        futures::future::ok(codec.framed(socket))
+    fn upgrade_inbound(self, socket: Negotiated<TSubstream>, info: Self::Info) -> Self::Future {
+        futures::future::ok(self.codec(info).framed(socket))
     }
 }
 
@@ -45,8 +72,7 @@ where
     type Future = FutureResult<Self::Output, Self::Error>;
 
     #[inline]
-    fn upgrade_outbound(self, socket: Negotiated<TSubstream>, _: Self::Info) -> Self::Future {
-        let codec = frame::JsonFrameCodec::default();
-        futures::future::ok(codec.framed(socket))
+    fn upgrade_outbound(self, socket: Negotiated<TSubstream>, info: Self::Info) -> Self::Future {
+        futures::future::ok(self.codec(info).framed(socket))
     }
 }
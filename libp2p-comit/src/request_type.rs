@@ -0,0 +1,224 @@
+use crate::{frame::Response, PendingInboundRequest};
+use futures::sync::oneshot;
+use serde::{de::DeserializeOwned, Deserialize};
+use std::{fmt::Debug, time::Duration};
+
+/// A type of request an application handles, registered through
+/// `Comit::with_request_type`.
+///
+/// Inbound requests of this type are decoded into `Headers` and `Body` by
+/// `PendingInboundRequest::decode`.
+pub trait RequestType {
+    /// The value of the `type` field of requests of this type.
+    const NAME: &'static str;
+    /// The headers we understand, requests of this type have to carry all of
+    /// them. Requests with any other mandatory header are rejected before they
+    /// reach the application.
+    const HEADERS: &'static [&'static str];
+    /// Whether requests of this type count towards the `RequestLimit` of the
+    /// peer. Only requests that are cheap to answer should be exempt.
+    const RATE_LIMITED: bool = true;
+    /// How long the peer has to answer requests of this type we send, `None`
+    /// falls back to the timeout configured through
+    /// `Comit::with_request_timeout`.
+    const TIMEOUT: Option<Duration> = None;
+
+    /// The headers of a request, deserialized from a map of header names
+    /// (without the `_` prefix of optional headers) to `Header`s.
+    type Headers: DeserializeOwned + Debug + Send;
+    type Body: DeserializeOwned + Debug + Send;
+
+    /// The answer to a request of this type that cannot be decoded.
+    fn decline(error: &MalformedRequest) -> Response;
+}
+
+/// Headers of request types that do not carry any.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Deserialize)]
+pub struct NoHeaders {}
+
+#[derive(Debug, thiserror::Error)]
+pub enum MalformedRequest {
+    #[error("expected a request of type {expected} but got {actual}")]
+    UnexpectedType {
+        expected: &'static str,
+        actual: String,
+    },
+    #[error("missing header {0}")]
+    MissingHeader(&'static str),
+    #[error("malformed headers: {0}")]
+    Headers(serde_json::Error),
+    #[error("malformed body: {0}")]
+    Body(serde_json::Error),
+}
+
+/// An inbound request of type `R`, waiting for the application to answer it
+/// through `channel`.
+#[derive(Debug)]
+pub struct PendingTypedRequest<R: RequestType> {
+    pub headers: R::Headers,
+    pub body: R::Body,
+    pub channel: oneshot::Sender<Response>,
+}
+
+impl PendingInboundRequest {
+    /// Decode the request as a request of type `R`.
+    ///
+    /// Requests that cannot be decoded are answered with `R::decline` right
+    /// away.
+    pub fn decode<R: RequestType>(self) -> Result<PendingTypedRequest<R>, MalformedRequest> {
+        let PendingInboundRequest { request, channel } = self;
+
+        let decoded = if request.request_type() != R::NAME {
+            Err(MalformedRequest::UnexpectedType {
+                expected: R::NAME,
+                actual: request.request_type().to_owned(),
+            })
+        } else if let Some(header) = R::HEADERS
+            .iter()
+            .find(|header| request.header(header).is_none())
+        {
+            Err(MalformedRequest::MissingHeader(*header))
+        } else {
+            request.decode::<R::Headers, R::Body>()
+        };
+
+        match decoded {
+            Ok((headers, body)) => Ok(PendingTypedRequest {
+                headers,
+                body,
+                channel,
+            }),
+            Err(error) => {
+                channel
+                    .send(R::decline(&error))
+                    .unwrap_or_else(|_| log::debug!("failed to send response through channel"));
+
+                Err(error)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::frame::{Header, OutboundRequest, UnvalidatedInboundRequest};
+    use futures::Future;
+    use spectral::prelude::*;
+    use std::collections::HashSet;
+
+    #[derive(Debug, Deserialize)]
+    struct PingHeaders {
+        sequence: Header,
+        #[serde(default)]
+        note: Option<String>,
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct PingBody {
+        payload: String,
+    }
+
+    struct Ping;
+
+    impl RequestType for Ping {
+        const NAME: &'static str = "PING";
+        const HEADERS: &'static [&'static str] = &["sequence"];
+
+        type Headers = PingHeaders;
+        type Body = PingBody;
+
+        fn decline(_: &MalformedRequest) -> Response {
+            Response::empty().with_header("error", Header::with_str_value("malformed"))
+        }
+    }
+
+    fn pending_request(
+        request: OutboundRequest,
+    ) -> (PendingInboundRequest, oneshot::Receiver<Response>) {
+        let request: UnvalidatedInboundRequest =
+            serde_json::from_value(serde_json::to_value(request).unwrap()).unwrap();
+        let known_headers = Ping::HEADERS
+            .iter()
+            .map(|header| header.to_string())
+            .collect::<HashSet<_>>();
+        let request = request
+            .ensure_no_unknown_mandatory_headers(&known_headers)
+            .unwrap();
+
+        let (channel, receiver) = oneshot::channel();
+
+        (PendingInboundRequest { request, channel }, receiver)
+    }
+
+    #[test]
+    fn decodes_headers_and_body_of_a_request() {
+        let (request, _receiver) = pending_request(
+            OutboundRequest::new("PING")
+                .with_header("sequence", Header::with_value(1).unwrap())
+                .with_header("_note", Header::with_str_value("hello"))
+                .with_body(serde_json::json!({ "payload": "ping" })),
+        );
+
+        let request = request.decode::<Ping>().unwrap();
+
+        assert_that(&request.headers.sequence).is_equal_to(Header::with_value(1).unwrap());
+        assert_that(&request.headers.note).is_equal_to(Some("hello".to_owned()));
+        assert_that(&request.body).is_equal_to(PingBody {
+            payload: "ping".to_owned(),
+        });
+    }
+
+    #[test]
+    fn declines_requests_with_missing_headers() {
+        let (request, receiver) = pending_request(
+            OutboundRequest::new("PING").with_body(serde_json::json!({ "payload": "ping" })),
+        );
+
+        let result = request.decode::<Ping>();
+
+        matches::assert_matches!(result, Err(MalformedRequest::MissingHeader("sequence")));
+        assert_that(&receiver.wait()).is_ok().is_equal_to(
+            Response::empty().with_header("error", Header::with_str_value("malformed")),
+        );
+    }
+
+    #[test]
+    fn declines_requests_with_malformed_headers() {
+        let (request, receiver) = pending_request(
+            OutboundRequest::new("PING")
+                .with_header("sequence", Header::with_value(1).unwrap())
+                .with_header("_note", Header::with_value(42).unwrap())
+                .with_body(serde_json::json!({ "payload": "ping" })),
+        );
+
+        let result = request.decode::<Ping>();
+
+        matches::assert_matches!(result, Err(MalformedRequest::Headers(_)));
+        assert_that(&receiver.wait()).is_ok();
+    }
+
+    #[test]
+    fn declines_requests_with_malformed_body() {
+        let (request, receiver) = pending_request(
+            OutboundRequest::new("PING")
+                .with_header("sequence", Header::with_value(1).unwrap())
+                .with_body(serde_json::json!({ "payload": 42 })),
+        );
+
+        let result = request.decode::<Ping>();
+
+        matches::assert_matches!(result, Err(MalformedRequest::Body(_)));
+        assert_that(&receiver.wait()).is_ok();
+    }
+
+    #[test]
+    fn declines_requests_of_other_types() {
+        let (request, receiver) = pending_request(OutboundRequest::new("PONG"));
+
+        let result = request.decode::<Ping>();
+
+        matches::assert_matches!(result, Err(MalformedRequest::UnexpectedType { .. }));
+        assert_that(&receiver.wait()).is_ok();
+    }
+}
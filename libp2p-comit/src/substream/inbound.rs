@@ -1,20 +1,22 @@
 use crate::{
-    frame::{Response, UnvalidatedInboundRequest},
-    handler::{self, InboundMessage, PendingInboundRequest, ProtocolOutEvent},
+    frame::{
+        self, FrameLimits, Header, LimitExceeded, Response, UnvalidatedInboundNotification,
+        UnvalidatedInboundRequest,
+    },
+    handler::{self, InboundMessage, KnownHeaders, PendingInboundRequest, ProtocolOutEvent},
     protocol::Frames,
     substream::{Advance, Advanced, CloseStream},
     Frame, FrameType, IntoFrame,
 };
 use futures::sync::oneshot;
 use libp2p_swarm::ProtocolsHandlerEvent;
-use std::collections::{HashMap, HashSet};
 use tokio::prelude::*;
 
 #[derive(strum_macros::Display)]
 #[allow(missing_debug_implementations)]
 /// States of an inbound substream i.e. from peer node to us.
 pub enum State<TSubstream> {
-    /// Waiting for a request from the remote.
+    /// Waiting for a request or notification from the remote.
     WaitingMessage { stream: Frames<TSubstream> },
     /// Waiting for the user to send the response back to us.
     WaitingUser {
@@ -43,7 +45,8 @@ impl<TSubstream> CloseStream for State<TSubstream> {
 impl<TSubstream: AsyncRead + AsyncWrite> Advance for State<TSubstream> {
     fn advance(
         self,
-        known_headers: &HashMap<String, HashSet<String>>,
+        known_headers: &KnownHeaders,
+        limits: &FrameLimits,
     ) -> Advanced<State<TSubstream>> {
         use self::State::*;
         match self {
@@ -53,8 +56,15 @@ impl<TSubstream: AsyncRead + AsyncWrite> Advance for State<TSubstream> {
                         let request =
                             serde_json::from_value::<UnvalidatedInboundRequest>(frame.payload)
                                 .map_err(handler::Error::MalformedFrame)
+                                .and_then(|request| {
+                                    request
+                                        .ensure_within_limits(limits)
+                                        .map(|_| request)
+                                        .map_err(handler::Error::LimitExceeded)
+                                })
                                 .and_then(|request| {
                                     known_headers
+                                        .requests
                                         .get(request.request_type())
                                         .ok_or_else(|| {
                                             handler::Error::UnknownRequestType(
@@ -83,6 +93,46 @@ impl<TSubstream: AsyncRead + AsyncWrite> Advance for State<TSubstream> {
                                     )),
                                 }
                             }
+                            Err(handler::Error::LimitExceeded(limit)) => reject(stream, limit),
+                            Err(error) => Advanced::error(stream, error),
+                        }
+                    }
+                    FrameType::Notification => {
+                        let notification =
+                            serde_json::from_value::<UnvalidatedInboundNotification>(frame.payload)
+                                .map_err(handler::Error::MalformedFrame)
+                                .and_then(|notification| {
+                                    notification
+                                        .ensure_within_limits(limits)
+                                        .map(|_| notification)
+                                        .map_err(handler::Error::LimitExceeded)
+                                })
+                                .and_then(|notification| {
+                                    known_headers
+                                        .notifications
+                                        .get(notification.notification_type())
+                                        .ok_or_else(|| {
+                                            handler::Error::UnknownNotificationType(
+                                                notification.notification_type().to_owned(),
+                                            )
+                                        })
+                                        .and_then(|known_headers| {
+                                            notification
+                                                .ensure_no_unknown_mandatory_headers(known_headers)
+                                                .map_err(handler::Error::UnknownMandatoryHeader)
+                                        })
+                                });
+
+                        // Nothing is sent back for a notification, not even an error.
+                        match notification {
+                            Ok(notification) => Advanced {
+                                new_state: Some(WaitingClose { stream }),
+                                event: Some(ProtocolsHandlerEvent::Custom(
+                                    ProtocolOutEvent::Message(InboundMessage::Notification(
+                                        notification,
+                                    )),
+                                )),
+                            },
                             Err(error) => Advanced::error(stream, error),
                         }
                     }
@@ -93,6 +143,7 @@ impl<TSubstream: AsyncRead + AsyncWrite> Advance for State<TSubstream> {
                 },
                 Ok(Async::NotReady) => Advanced::transition_to(WaitingMessage { stream }),
                 Ok(Async::Ready(None)) => Advanced::error(stream, handler::Error::UnexpectedEOF),
+                Err(frame::CodecError::LimitExceeded(limit)) => reject(stream, limit),
                 Err(error) => Advanced::error(stream, error),
             },
             WaitingUser {
@@ -103,12 +154,12 @@ impl<TSubstream: AsyncRead + AsyncWrite> Advance for State<TSubstream> {
                     msg: response.into_frame(),
                     stream,
                 }
-                .advance(known_headers),
+                .advance(known_headers, limits),
                 Ok(Async::NotReady) => Advanced::transition_to(WaitingUser { receiver, stream }),
                 Err(error) => Advanced::error(stream, error),
             },
             WaitingSend { msg, mut stream } => match stream.start_send(msg) {
-                Ok(AsyncSink::Ready) => WaitingFlush { stream }.advance(known_headers),
+                Ok(AsyncSink::Ready) => WaitingFlush { stream }.advance(known_headers, limits),
                 Ok(AsyncSink::NotReady(msg)) => {
                     Advanced::transition_to(WaitingSend { msg, stream })
                 }
@@ -127,3 +178,22 @@ impl<TSubstream: AsyncRead + AsyncWrite> Advance for State<TSubstream> {
         }
     }
 }
+
+/// Answers a request that exceeds the frame limits with an error response and
+/// closes the substream afterwards.
+fn reject<TSubstream>(
+    stream: Frames<TSubstream>,
+    limit: LimitExceeded,
+) -> Advanced<State<TSubstream>> {
+    let response = Response::empty().with_header("error", Header::with_str_value("limit-exceeded"));
+
+    Advanced {
+        new_state: Some(State::WaitingSend {
+            msg: response.into_frame(),
+            stream,
+        }),
+        event: Some(ProtocolsHandlerEvent::Custom(ProtocolOutEvent::Error(
+            handler::Error::LimitExceeded(limit),
+        ))),
+    }
+}
@@ -1,10 +1,10 @@
 use crate::{
-    handler::{Error, ProtocolOutEvent},
+    frame::FrameLimits,
+    handler::{Error, KnownHeaders, ProtocolOutEvent},
     protocol::Frames,
     ComitHandlerEvent,
 };
 use libp2p_swarm::ProtocolsHandlerEvent;
-use std::collections::{HashMap, HashSet};
 
 pub mod inbound;
 pub mod outbound;
@@ -18,7 +18,7 @@ pub struct Advanced<S> {
 }
 
 pub trait Advance: Sized {
-    fn advance(self, known_headers: &HashMap<String, HashSet<String>>) -> Advanced<Self>;
+    fn advance(self, known_headers: &KnownHeaders, limits: &FrameLimits) -> Advanced<Self>;
 }
 
 impl<S> Advanced<S> {
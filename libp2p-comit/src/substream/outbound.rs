@@ -1,14 +1,15 @@
 use crate::{
-    frame::Response,
-    handler::{self, InboundMessage, PendingInboundResponse, ProtocolOutEvent},
+    frame::{FrameLimits, Response},
+    handler::{
+        self, InboundMessage, KnownHeaders, PendingInboundResponse, ProtocolOutEvent, RequestError,
+        ResponseSender,
+    },
     protocol::Frames,
     substream::{Advance, Advanced, CloseStream},
     Frame, FrameType,
 };
-use futures::sync::oneshot;
 use libp2p_swarm::ProtocolsHandlerEvent;
-use std::collections::{HashMap, HashSet};
-use tokio::prelude::*;
+use tokio::{prelude::*, timer::Delay};
 
 #[derive(strum_macros::Display)]
 #[allow(missing_debug_implementations)]
@@ -17,19 +18,30 @@ pub enum State<TSubstream> {
     /// Waiting to send a message to the remote.
     WaitingSend {
         frame: Frame,
-        response_sender: oneshot::Sender<Response>,
+        response_sender: ResponseSender,
+        deadline: Delay,
         stream: Frames<TSubstream>,
     },
     /// Waiting to flush the substream so that the data arrives at the remote.
     WaitingFlush {
-        response_sender: oneshot::Sender<Response>,
+        response_sender: ResponseSender,
+        deadline: Delay,
         stream: Frames<TSubstream>,
     },
     /// Waiting for the answer to our message.
     WaitingAnswer {
-        response_sender: oneshot::Sender<Response>,
+        response_sender: ResponseSender,
+        deadline: Delay,
         stream: Frames<TSubstream>,
     },
+    /// Waiting to send a notification to the remote, which is not answered.
+    WaitingSendNotification {
+        frame: Frame,
+        stream: Frames<TSubstream>,
+    },
+    /// Waiting to flush the substream so that the notification arrives at the
+    /// remote.
+    WaitingFlushNotification { stream: Frames<TSubstream> },
     /// The substream is being closed.
     WaitingClose { stream: Frames<TSubstream> },
 }
@@ -45,78 +57,139 @@ impl<TSubstream> CloseStream for State<TSubstream> {
 impl<TSubstream: AsyncRead + AsyncWrite> Advance for State<TSubstream> {
     fn advance(
         self,
-        known_headers: &HashMap<String, HashSet<String>>,
+        known_headers: &KnownHeaders,
+        limits: &FrameLimits,
     ) -> Advanced<State<TSubstream>> {
         use self::State::*;
         match self {
             WaitingSend {
                 frame,
                 response_sender,
+                mut deadline,
                 mut stream,
-            } => match stream.start_send(frame) {
-                Ok(AsyncSink::Ready) => WaitingFlush {
-                    response_sender,
-                    stream,
+            } => {
+                if is_expired(&mut deadline) {
+                    return timed_out(stream, response_sender);
                 }
-                .advance(known_headers),
-                Ok(AsyncSink::NotReady(frame)) => Advanced::transition_to(WaitingSend {
-                    frame,
-                    response_sender,
-                    stream,
-                }),
-                Err(error) => Advanced::error(stream, error),
-            },
+
+                match stream.start_send(frame) {
+                    Ok(AsyncSink::Ready) => WaitingFlush {
+                        response_sender,
+                        deadline,
+                        stream,
+                    }
+                    .advance(known_headers, limits),
+                    Ok(AsyncSink::NotReady(frame)) => Advanced::transition_to(WaitingSend {
+                        frame,
+                        response_sender,
+                        deadline,
+                        stream,
+                    }),
+                    Err(error) => {
+                        response_sender.fail(RequestError::from(&error));
+                        Advanced::error(stream, error)
+                    }
+                }
+            }
             WaitingFlush {
-                response_sender,
+                mut response_sender,
+                mut deadline,
                 mut stream,
-            } => match stream.poll_complete() {
-                Ok(Async::Ready(_)) => WaitingAnswer {
-                    response_sender,
-                    stream,
+            } => {
+                if is_expired(&mut deadline) {
+                    return timed_out(stream, response_sender);
                 }
-                .advance(&known_headers),
-                Ok(Async::NotReady) => Advanced::transition_to(WaitingFlush {
-                    response_sender,
-                    stream,
-                }),
-                Err(error) => Advanced::error(stream, error),
-            },
+
+                match stream.poll_complete() {
+                    Ok(Async::Ready(_)) => {
+                        response_sender.notify_sent();
+
+                        WaitingAnswer {
+                            response_sender,
+                            deadline,
+                            stream,
+                        }
+                        .advance(&known_headers, limits)
+                    }
+                    Ok(Async::NotReady) => Advanced::transition_to(WaitingFlush {
+                        response_sender,
+                        deadline,
+                        stream,
+                    }),
+                    Err(error) => {
+                        response_sender.fail(RequestError::from(&error));
+                        Advanced::error(stream, error)
+                    }
+                }
+            }
             WaitingAnswer {
                 response_sender,
+                mut deadline,
                 mut stream,
-            } => match stream.poll() {
-                Ok(Async::Ready(Some(frame))) => match frame.frame_type {
-                    FrameType::Response => {
-                        let event = serde_json::from_value(frame.payload)
-                            .map(|response| {
-                                ProtocolOutEvent::Message(InboundMessage::Response(
-                                    PendingInboundResponse {
+            } => {
+                if is_expired(&mut deadline) {
+                    return timed_out(stream, response_sender);
+                }
+
+                match stream.poll() {
+                    Ok(Async::Ready(Some(frame))) => match frame.frame_type {
+                        FrameType::Response => {
+                            let event = match serde_json::from_value(frame.payload) {
+                                Ok(response) => ProtocolOutEvent::Message(
+                                    InboundMessage::Response(PendingInboundResponse {
                                         response,
                                         channel: response_sender,
-                                    },
-                                ))
-                            })
-                            .map_err(handler::Error::MalformedFrame)
-                            .unwrap_or_else(ProtocolOutEvent::Error);
+                                    }),
+                                ),
+                                Err(error) => {
+                                    response_sender.fail(RequestError::MalformedResponse);
+                                    ProtocolOutEvent::Error(handler::Error::MalformedFrame(error))
+                                }
+                            };
 
-                        Advanced {
-                            new_state: Some(WaitingClose { stream }),
-                            event: Some(ProtocolsHandlerEvent::Custom(event)),
+                            Advanced {
+                                new_state: Some(WaitingClose { stream }),
+                                event: Some(ProtocolsHandlerEvent::Custom(event)),
+                            }
+                        }
+                        FrameType::Request | FrameType::Notification => {
+                            response_sender.fail(RequestError::MalformedResponse);
+                            Advanced::error(stream, handler::Error::UnexpectedFrame(frame))
+                        }
+                        FrameType::Unknown => {
+                            response_sender.fail(RequestError::MalformedResponse);
+                            Advanced::error(stream, handler::Error::UnknownFrameType)
                         }
+                    },
+                    Ok(Async::NotReady) => Advanced::transition_to(WaitingAnswer {
+                        response_sender,
+                        deadline,
+                        stream,
+                    }),
+                    Ok(Async::Ready(None)) => {
+                        response_sender.fail(RequestError::SubstreamClosed);
+                        Advanced::error(stream, handler::Error::UnexpectedEOF)
                     }
-                    FrameType::Request => {
-                        Advanced::error(stream, handler::Error::UnexpectedFrame(frame))
+                    Err(error) => {
+                        response_sender.fail(RequestError::from(&error));
+                        Advanced::error(stream, error)
                     }
-                    FrameType::Unknown => Advanced::error(stream, handler::Error::UnknownFrameType),
-                },
-                Ok(Async::NotReady) => Advanced::transition_to(WaitingAnswer {
-                    response_sender,
-                    stream,
-                }),
-                Ok(Async::Ready(None)) => Advanced::error(stream, handler::Error::UnexpectedEOF),
+                }
+            }
+            WaitingSendNotification { frame, mut stream } => match stream.start_send(frame) {
+                Ok(AsyncSink::Ready) => {
+                    WaitingFlushNotification { stream }.advance(known_headers, limits)
+                }
+                Ok(AsyncSink::NotReady(frame)) => {
+                    Advanced::transition_to(WaitingSendNotification { frame, stream })
+                }
+                Err(error) => Advanced::error(stream, error),
+            },
+            WaitingFlushNotification { mut stream } => match stream.poll_complete() {
+                Ok(Async::Ready(_)) => Advanced::transition_to(WaitingClose { stream }),
+                Ok(Async::NotReady) => Advanced::transition_to(WaitingFlushNotification { stream }),
                 Err(error) => Advanced::error(stream, error),
             },
-
             WaitingClose { mut stream } => match stream.close() {
                 Ok(Async::Ready(_)) => Advanced::end(),
                 Ok(Async::NotReady) => Advanced::transition_to(WaitingClose { stream }),
@@ -125,3 +198,26 @@ impl<TSubstream: AsyncRead + AsyncWrite> Advance for State<TSubstream> {
         }
     }
 }
+
+/// Whether the deadline of a request has passed, registering the current task
+/// to be woken up once it does otherwise.
+fn is_expired(deadline: &mut Delay) -> bool {
+    match deadline.poll() {
+        Ok(Async::Ready(())) => true,
+        Ok(Async::NotReady) => false,
+        Err(e) => {
+            log::warn!("failed to poll the deadline of a request: {}", e);
+            true
+        }
+    }
+}
+
+fn timed_out<TSubstream>(
+    stream: Frames<TSubstream>,
+    response_sender: ResponseSender,
+) -> Advanced<State<TSubstream>> {
+    log::debug!("peer did not answer the request in time, closing the substream");
+    response_sender.fail(RequestError::Timeout);
+
+    Advanced::transition_to(State::WaitingClose { stream })
+}
@@ -0,0 +1,10 @@
+//! Helpers for testing applications built on top of `Comit`, available
+//! through the `test-harness` feature.
+
+#[cfg(test)]
+mod substream;
+mod swarm;
+
+#[cfg(test)]
+pub use self::substream::*;
+pub use self::swarm::{ConnectedNodes, TestNode, TestSubstream, TestSwarm};
@@ -1,5 +1,5 @@
 use crate::{
-    frame::{self, JsonFrameCodec, Response},
+    frame::{self, CborFrameCodec, FrameCodec, JsonFrameCodec, Response},
     handler::{InboundMessage, ProtocolOutEvent},
     ComitHandler, ComitHandlerEvent, Frame, PendingInboundRequest,
 };
@@ -51,12 +51,28 @@ pub fn setup_substream<CD: Encoder + Decoder, CL: Encoder + Decoder>(
 
 pub fn setup_substream_with_json_codec() -> impl Future<
     Item = (
-        Framed<Negotiated<TcpStream>, JsonFrameCodec>,
-        Framed<Negotiated<TcpStream>, JsonFrameCodec>,
+        Framed<Negotiated<TcpStream>, FrameCodec>,
+        Framed<Negotiated<TcpStream>, FrameCodec>,
     ),
     Error = multistream_select::NegotiationError,
 > {
-    setup_substream(JsonFrameCodec::default(), JsonFrameCodec::default())
+    setup_substream(
+        FrameCodec::from(JsonFrameCodec::default()),
+        FrameCodec::from(JsonFrameCodec::default()),
+    )
+}
+
+pub fn setup_substream_with_cbor_codec() -> impl Future<
+    Item = (
+        Framed<Negotiated<TcpStream>, FrameCodec>,
+        Framed<Negotiated<TcpStream>, FrameCodec>,
+    ),
+    Error = multistream_select::NegotiationError,
+> {
+    setup_substream(
+        FrameCodec::from(CborFrameCodec::default()),
+        FrameCodec::from(CborFrameCodec::default()),
+    )
 }
 
 pub fn request_with_no_headers<S: Into<String>>(
@@ -120,7 +136,7 @@ pub trait WaitForFrame {
     ) -> Box<dyn Future<Item = Option<Frame>, Error = frame::CodecError> + Send>;
 }
 
-impl WaitForFrame for Framed<Negotiated<TcpStream>, JsonFrameCodec> {
+impl WaitForFrame for Framed<Negotiated<TcpStream>, FrameCodec> {
     fn wait_for_frame(
         self,
     ) -> Box<dyn Future<Item = Option<Frame>, Error = frame::CodecError> + Send> {
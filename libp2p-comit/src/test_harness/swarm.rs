@@ -0,0 +1,311 @@
+use crate::{
+    frame::{OutboundRequest, Response},
+    BehaviourOutEvent, Comit, PendingInboundRequest, PendingResponse, RequestError,
+};
+use futures::{future, Async, Future, Stream};
+use libp2p_core::{
+    identity,
+    multiaddr::Protocol,
+    muxing::{StreamMuxerBox, SubstreamRef},
+    transport::{boxed::Boxed, MemoryTransport},
+    upgrade::Version,
+    Multiaddr, PeerId, Transport,
+};
+use libp2p_mplex::MplexConfig;
+use libp2p_secio::SecioConfig;
+use libp2p_swarm::Swarm;
+use std::{
+    collections::VecDeque,
+    io,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+use tokio::{prelude::FutureExt, runtime::current_thread::Runtime};
+
+pub type TestSubstream = SubstreamRef<Arc<StreamMuxerBox>>;
+pub type TestSwarm = Swarm<Boxed<(PeerId, StreamMuxerBox), io::Error>, Comit<TestSubstream>>;
+
+/// How long `ConnectedNodes` waits for something to happen before failing
+/// the test.
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// In-memory addresses are global to the process, every node gets its own
+/// port.
+static NEXT_PORT: AtomicU64 = AtomicU64::new(1);
+
+/// A `Comit` behaviour listening on an in-memory address.
+#[derive(derivative::Derivative)]
+#[derivative(Debug)]
+pub struct TestNode {
+    #[derivative(Debug = "ignore")]
+    pub swarm: TestSwarm,
+    pub peer_id: PeerId,
+    pub address: Multiaddr,
+    /// Events of `swarm` that have not been asked for yet.
+    events: VecDeque<BehaviourOutEvent>,
+}
+
+impl TestNode {
+    pub fn new(behaviour: Comit<TestSubstream>) -> Self {
+        let keypair = identity::Keypair::generate_ed25519();
+        let peer_id = keypair.public().into_peer_id();
+
+        let transport = MemoryTransport::default()
+            .upgrade(Version::V1)
+            .authenticate(SecioConfig::new(keypair))
+            .multiplex(MplexConfig::new())
+            .map(|(peer, muxer), _| (peer, StreamMuxerBox::new(muxer)))
+            .map_err(|e| io::Error::new(io::ErrorKind::Other, e))
+            .boxed();
+
+        let mut swarm = Swarm::new(transport, behaviour, peer_id.clone());
+        let address = Multiaddr::from(Protocol::Memory(NEXT_PORT.fetch_add(1, Ordering::SeqCst)));
+        Swarm::listen_on(&mut swarm, address.clone())
+            .expect("memory transport should listen on any unused port");
+
+        Self {
+            swarm,
+            peer_id,
+            address,
+            events: VecDeque::new(),
+        }
+    }
+
+    /// What other nodes pass to `Comit::send_request` to reach this one.
+    pub fn dial_information(&self) -> (PeerId, Option<Multiaddr>) {
+        (self.peer_id.clone(), Some(self.address.clone()))
+    }
+
+    fn poll_events(&mut self) {
+        loop {
+            match self.swarm.poll() {
+                Ok(Async::Ready(Some(event))) => self.events.push_back(event),
+                Ok(Async::Ready(None)) | Ok(Async::NotReady) => return,
+                Err(e) => panic!("swarm of {} failed: {}", self.peer_id, e),
+            }
+        }
+    }
+
+    fn take_inbound_request(&mut self) -> Option<PendingInboundRequest> {
+        let position = self.events.iter().position(|event| {
+            if let BehaviourOutEvent::PendingInboundRequest { .. } = event {
+                true
+            } else {
+                false
+            }
+        })?;
+
+        match self.events.remove(position) {
+            Some(BehaviourOutEvent::PendingInboundRequest { request, .. }) => Some(request),
+            _ => unreachable!("position points at an inbound request"),
+        }
+    }
+}
+
+/// Two nodes, Alice and Bob, that are connected to each other and driven on
+/// a single-threaded runtime.
+///
+/// All methods that wait for the nodes panic if nothing happens within ten
+/// seconds.
+#[derive(derivative::Derivative)]
+#[derivative(Debug)]
+pub struct ConnectedNodes {
+    pub alice: TestNode,
+    pub bob: TestNode,
+    #[derivative(Debug = "ignore")]
+    runtime: Runtime,
+}
+
+impl ConnectedNodes {
+    /// Connects Alice to Bob. Events emitted while connecting are discarded.
+    pub fn new(alice: Comit<TestSubstream>, bob: Comit<TestSubstream>) -> Self {
+        let mut nodes = Self {
+            alice: TestNode::new(alice),
+            bob: TestNode::new(bob),
+            runtime: Runtime::new().expect("failed to create runtime"),
+        };
+
+        let bob_address = nodes.bob.address.clone();
+        Swarm::dial_addr(&mut nodes.alice.swarm, bob_address)
+            .expect("memory transport should dial any memory address");
+
+        nodes.run_until(|alice, bob| {
+            let connected = |node: &TestNode| {
+                node.events.iter().any(|event| {
+                    if let BehaviourOutEvent::PeerConnected { .. } = event {
+                        true
+                    } else {
+                        false
+                    }
+                })
+            };
+
+            if connected(alice) && connected(bob) {
+                Some(())
+            } else {
+                None
+            }
+        });
+        nodes.alice.events.clear();
+        nodes.bob.events.clear();
+
+        nodes
+    }
+
+    /// Drives both nodes until `f` returns `Some`.
+    pub fn run_until<T>(
+        &mut self,
+        mut f: impl FnMut(&mut TestNode, &mut TestNode) -> Option<T>,
+    ) -> T {
+        let Self {
+            alice,
+            bob,
+            runtime,
+        } = self;
+
+        let future = future::poll_fn(move || {
+            alice.poll_events();
+            bob.poll_events();
+
+            Ok::<_, ()>(match f(alice, bob) {
+                Some(item) => Async::Ready(item),
+                None => Async::NotReady,
+            })
+        })
+        .timeout(TIMEOUT);
+
+        runtime
+            .block_on(future)
+            .unwrap_or_else(|_| panic!("nodes did not make progress within {:?}", TIMEOUT))
+    }
+
+    /// Drives both nodes until `future` resolves.
+    pub fn wait_for<F: Future>(&mut self, mut future: F) -> Result<F::Item, F::Error> {
+        self.run_until(|_, _| match future.poll() {
+            Ok(Async::Ready(item)) => Some(Ok(item)),
+            Ok(Async::NotReady) => None,
+            Err(e) => Some(Err(e)),
+        })
+    }
+
+    pub fn next_alice_event(&mut self) -> BehaviourOutEvent {
+        self.run_until(|alice, _| alice.events.pop_front())
+    }
+
+    pub fn next_bob_event(&mut self) -> BehaviourOutEvent {
+        self.run_until(|_, bob| bob.events.pop_front())
+    }
+
+    /// Sends `request` from Alice to Bob and waits until Bob receives it.
+    pub fn send_request(
+        &mut self,
+        request: OutboundRequest,
+    ) -> (PendingInboundRequest, PendingResponse) {
+        let dial_information = self.bob.dial_information();
+        let response = self.alice.swarm.send_request(dial_information, request);
+
+        let request = self.run_until(|_, bob| bob.take_inbound_request());
+
+        (request, response)
+    }
+
+    /// Sends `request` from Alice to Bob, lets `respond` answer it on Bob's
+    /// side and returns the response Alice received.
+    pub fn request_response(
+        &mut self,
+        request: OutboundRequest,
+        respond: impl FnOnce(PendingInboundRequest),
+    ) -> Result<Response, RequestError> {
+        let (request, response) = self.send_request(request);
+
+        respond(request);
+
+        self.wait_for(response)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{frame::OutboundNotification, test_harness::request_with_no_headers};
+    use spectral::prelude::*;
+
+    #[test]
+    fn alice_receives_the_response_of_bob() {
+        let mut nodes = ConnectedNodes::new(
+            Comit::new(request_with_no_headers("PING")),
+            Comit::new(request_with_no_headers("PING")),
+        );
+
+        let response = nodes.request_response(OutboundRequest::new("PING"), |request| {
+            request
+                .channel
+                .send(Response::empty().with_body(serde_json::json!("PONG")))
+                .unwrap()
+        });
+
+        assert_that(&response)
+            .is_ok()
+            .is_equal_to(Response::empty().with_body(serde_json::json!("PONG")));
+    }
+
+    #[test]
+    fn bob_receives_the_notification_of_alice() {
+        let mut nodes = ConnectedNodes::new(
+            Comit::new(request_with_no_headers("PING")),
+            Comit::new(request_with_no_headers("PING"))
+                .with_known_notification_headers(request_with_no_headers("ABORT")),
+        );
+
+        let dial_information = nodes.bob.dial_information();
+        nodes
+            .alice
+            .swarm
+            .send_notification(dial_information, OutboundNotification::new("ABORT"));
+
+        matches::assert_matches!(
+            nodes.next_bob_event(),
+            BehaviourOutEvent::InboundNotification { .. }
+        );
+    }
+
+    #[test]
+    fn alice_is_told_when_bob_closes_the_connection() {
+        let mut nodes = ConnectedNodes::new(
+            Comit::new(request_with_no_headers("PING")),
+            Comit::new(request_with_no_headers("PING")),
+        );
+
+        let alice = nodes.alice.peer_id.clone();
+        let bob = nodes.bob.peer_id.clone();
+        Swarm::ban_peer_id(&mut nodes.bob.swarm, alice);
+
+        matches::assert_matches!(
+            nodes.next_alice_event(),
+            BehaviourOutEvent::PeerDisconnected { peer_id } if peer_id == bob
+        );
+    }
+
+    #[test]
+    fn alice_reconnects_to_a_known_peer_after_a_restart() {
+        let mut nodes = ConnectedNodes::new(
+            Comit::new(request_with_no_headers("PING")),
+            Comit::new(request_with_no_headers("PING")),
+        );
+        let bob = nodes.bob.peer_id.clone();
+        let bob_address = nodes.bob.address.clone();
+
+        // Alice restarts with nothing but the address of Bob she saved.
+        nodes.alice = TestNode::new(Comit::new(request_with_no_headers("PING")));
+        nodes.alice.swarm.add_address(bob.clone(), bob_address);
+        nodes.alice.swarm.dial(bob.clone());
+
+        matches::assert_matches!(
+            nodes.next_alice_event(),
+            BehaviourOutEvent::PeerConnected { peer_id, .. } if peer_id == bob
+        );
+    }
+}